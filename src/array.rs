@@ -0,0 +1,347 @@
+//! Python [`array.array`](https://docs.python.org/3/library/array.html) support
+//!
+//! `array.array` objects are always accepted as sequences by [`from_pyobject`](crate::from_pyobject),
+//! and their contents are copied from the underlying buffer at once.
+//! Other one-dimensional buffers of numbers, e.g. `bytes` or `memoryview` cast to `d`,
+//! are also copied at once when deserialized into sequences such as `Vec<u8>` or `Vec<f64>`.
+//!
+//! Use this module with `#[serde(with = "serde_pyobject::array")]` on a `Vec<T>` field
+//! to serialize it into an `array.array` instead of a `list`.
+//! Other serde formats see a usual sequence.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Samples {
+//!     #[serde(with = "serde_pyobject::array")]
+//!     values: Vec<i32>,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let samples = Samples { values: vec![1, 2, 3] };
+//!     let obj = to_pyobject(py, &samples).unwrap();
+//!     let values = obj.get_item("values").unwrap();
+//!     assert_eq!(values.getattr("typecode").unwrap().extract::<String>().unwrap(), "i");
+//!
+//!     let reverted: Samples = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, samples);
+//! });
+//! ```
+
+use crate::{
+    error::{Error, Result},
+    py_module_cache,
+    scratch::Scratch,
+};
+use pyo3::{buffer::PyBuffer, exceptions::PyTypeError, prelude::*, types::PyBytes};
+use serde::{
+    de::{value::SeqDeserializer, Visitor},
+    ser::{self, Impossible},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::os::raw::{c_long, c_ulong};
+
+/// Prefix of the newtype struct name which `PyAnySerializer` recognizes.
+/// The type code of `array.array` follows the prefix.
+pub(crate) const TOKEN_PREFIX: &str = "$serde_pyobject::array::";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Element types which `array.array` can hold
+pub trait ArrayElement: sealed::Sealed + Serialize {
+    #[doc(hidden)]
+    const TOKEN: &'static str;
+}
+
+macro_rules! array_element {
+    ($t:ty, $code:literal) => {
+        impl sealed::Sealed for $t {}
+        impl ArrayElement for $t {
+            const TOKEN: &'static str = concat!("$serde_pyobject::array::", $code);
+        }
+    };
+}
+
+array_element!(i8, "b");
+array_element!(u8, "B");
+array_element!(i16, "h");
+array_element!(u16, "H");
+array_element!(i32, "i");
+array_element!(u32, "I");
+array_element!(i64, "q");
+array_element!(u64, "Q");
+array_element!(f32, "f");
+array_element!(f64, "d");
+
+/// Serialize `[T]` as `array.array`
+pub fn serialize<S, T>(value: &[T], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ArrayElement,
+{
+    serializer.serialize_newtype_struct(T::TOKEN, value)
+}
+
+/// Deserialize `Vec<T>` from `array.array` or any other sequence
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ArrayElement + Deserialize<'de>,
+{
+    Vec::deserialize(deserializer)
+}
+
+/// Create `array.array(typecode)` filled by the serialized sequence `value`
+pub(crate) fn to_array<'py, T>(
+    py: Python<'py>,
+    typecode: &str,
+    value: &T,
+) -> Result<Bound<'py, PyAny>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut bytes = Scratch::<Vec<u8>>::take();
+    value.serialize(BytesCollector(&mut bytes))?;
+    let array = py_module_cache::get(py, "array", "array")?.call1((typecode,))?;
+    array.call_method1("frombytes", (PyBytes::new(py, &bytes),))?;
+    Ok(array)
+}
+
+pub(crate) fn is_array(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    obj.is_instance(&py_module_cache::get(obj.py(), "array", "array")?)
+}
+
+/// Visit the contents of `array.array` as a sequence
+pub(crate) fn visit_array<'de, V>(obj: &Bound<'_, PyAny>, visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    let typecode: String = obj.getattr("typecode")?.extract()?;
+    if is_number_format(&typecode) {
+        return visit_numbers(obj, &typecode, visitor);
+    }
+    // unicode arrays
+    let s: String = obj.call_method0("tounicode")?.extract()?;
+    visitor.visit_string(s)
+}
+
+/// Format and length of the buffer of `obj` if it is a one-dimensional buffer of numbers in the native byte order,
+/// e.g. `bytes`, `memoryview` cast to `d` or one-dimensional `numpy.ndarray`
+pub(crate) fn number_buffer(obj: &Bound<'_, PyAny>) -> PyResult<Option<(String, usize)>> {
+    let py = obj.py();
+    let view = match py_module_cache::get(py, "builtins", "memoryview")?.call1((obj,)) {
+        Ok(view) => view,
+        // Objects without the buffer protocol
+        Err(err) if err.is_instance_of::<PyTypeError>(py) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let ndim: usize = view.getattr("ndim")?.extract()?;
+    let format: String = view.getattr("format")?.extract()?;
+    let len = view.len()?;
+    view.call_method0("release")?;
+    let format = format.strip_prefix('@').unwrap_or(&format);
+    if ndim != 1 || !is_number_format(format) {
+        return Ok(None);
+    }
+    Ok(Some((format.to_string(), len)))
+}
+
+/// Whether `format` of the `struct` module is a number which [`visit_numbers`] copies
+fn is_number_format(format: &str) -> bool {
+    matches!(
+        format,
+        "b" | "B" | "h" | "H" | "i" | "I" | "l" | "L" | "q" | "Q" | "f" | "d"
+    )
+}
+
+/// Visit the buffer of `obj`, whose elements are numbers of `format`, as a sequence
+pub(crate) fn visit_numbers<'de, V>(
+    obj: &Bound<'_, PyAny>,
+    format: &str,
+    visitor: V,
+) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    match format {
+        "b" => visit_buffer::<i8, V>(obj, visitor),
+        "B" => visit_buffer::<u8, V>(obj, visitor),
+        "h" => visit_buffer::<i16, V>(obj, visitor),
+        "H" => visit_buffer::<u16, V>(obj, visitor),
+        "i" => visit_buffer::<i32, V>(obj, visitor),
+        "I" => visit_buffer::<u32, V>(obj, visitor),
+        "l" => visit_buffer::<c_long, V>(obj, visitor),
+        "L" => visit_buffer::<c_ulong, V>(obj, visitor),
+        "q" => visit_buffer::<i64, V>(obj, visitor),
+        "Q" => visit_buffer::<u64, V>(obj, visitor),
+        "f" => visit_buffer::<f32, V>(obj, visitor),
+        "d" => visit_buffer::<f64, V>(obj, visitor),
+        _ => unreachable!("{format} is not a number format"),
+    }
+}
+
+fn visit_buffer<'de, T, V>(obj: &Bound<'_, PyAny>, visitor: V) -> Result<V::Value>
+where
+    T: pyo3::buffer::Element + Copy + serde::de::IntoDeserializer<'de, Error>,
+    V: Visitor<'de>,
+{
+    let buffer = PyBuffer::<T>::get(obj)?;
+    let values = buffer.to_vec(obj.py())?;
+    let mut seq = SeqDeserializer::new(values.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn not_a_number() -> Error {
+    ser::Error::custom("array.array accepts only a sequence of numbers")
+}
+
+/// Serializer which writes a sequence of numbers into native-endian bytes
+struct BytesCollector<'a>(&'a mut Vec<u8>);
+
+macro_rules! collect_number {
+    ($f:ident, $t:ty) => {
+        fn $f(self, v: $t) -> Result<()> {
+            self.0.extend_from_slice(&v.to_ne_bytes());
+            Ok(())
+        }
+    };
+}
+
+macro_rules! unsupported {
+    ($f:ident $(, $arg:ty)*) => {
+        fn $f(self $(, _: $arg)*) -> Result<()> {
+            Err(not_a_number())
+        }
+    };
+}
+
+impl<'a> Serializer for BytesCollector<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    collect_number!(serialize_i8, i8);
+    collect_number!(serialize_i16, i16);
+    collect_number!(serialize_i32, i32);
+    collect_number!(serialize_i64, i64);
+    collect_number!(serialize_u8, u8);
+    collect_number!(serialize_u16, u16);
+    collect_number!(serialize_u32, u32);
+    collect_number!(serialize_u64, u64);
+    collect_number!(serialize_f32, f32);
+    collect_number!(serialize_f64, f64);
+
+    unsupported!(serialize_bool, bool);
+    unsupported!(serialize_char, char);
+    unsupported!(serialize_str, &str);
+    unsupported!(serialize_bytes, &[u8]);
+    unsupported!(serialize_none);
+    unsupported!(serialize_unit);
+    unsupported!(serialize_unit_struct, &'static str);
+    unsupported!(serialize_unit_variant, &'static str, u32, &'static str);
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
+        Err(not_a_number())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(not_a_number())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(not_a_number())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(not_a_number())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(not_a_number())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(not_a_number())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(not_a_number())
+    }
+}
+
+impl ser::SerializeSeq for BytesCollector<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        value.serialize(BytesCollector(self.0))
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for BytesCollector<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        value.serialize(BytesCollector(self.0))
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Reusable converter between a Rust type and Python objects
+
+use crate::{
+    de::from_pyobject_with,
+    error::Result,
+    options::{FieldNames, PyObjectDeserializerOptions, PyObjectSerializerOptions, StructPlans},
+    ser::to_pyobject_with,
+};
+use pyo3::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, marker::PhantomData, sync::Arc};
+
+/// Converter between `T` and Python objects, which is constructed once and used many times
+///
+/// A codec captures the options, including the Python classes registered in them,
+/// and keeps the field names of structs as interned Python strings across calls,
+/// so that they are created only at the first call.
+/// This amortizes the per-call setup of [`to_pyobject_with`] and [`from_pyobject_with`]
+/// for hot paths converting the same type repeatedly.
+///
+/// A codec also makes a plan of each struct deserialized at the first time, i.e. the keys of its fields.
+/// Structs are read from `dict` objects by looking up the planned keys in the declared order of the fields,
+/// rather than by extracting every key of the `dict` into a Rust string and matching it with the fields.
+/// A `dict` having keys other than the fields is read as usual, so that the result does not change.
+///
+/// Plans are made only for deserialization, and only cover the field keys of structs.
+/// Values are still converted through serde, and [`Codec::serialize`] runs the usual serializer
+/// with the interned field names, so that the gain is moderate rather than multiples.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde::{Deserialize, Serialize};
+/// use serde_pyobject::Codec;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let codec = Codec::<Point>::new();
+/// Python::with_gil(|py| {
+///     for i in 0..3 {
+///         let obj = codec.serialize(py, &Point { x: i, y: -i }).unwrap();
+///         assert_eq!(codec.deserialize(obj).unwrap(), Point { x: i, y: -i });
+///     }
+/// });
+/// ```
+pub struct Codec<T> {
+    ser_options: PyObjectSerializerOptions,
+    de_options: PyObjectDeserializerOptions,
+    marker: PhantomData<fn(&T) -> T>,
+}
+
+impl<T> Codec<T> {
+    /// Codec with the default options
+    pub fn new() -> Self {
+        Self::with_options(&Default::default(), &Default::default())
+    }
+
+    /// Codec with the options of serialization and deserialization
+    pub fn with_options(
+        ser_options: &PyObjectSerializerOptions,
+        de_options: &PyObjectDeserializerOptions,
+    ) -> Self {
+        let mut ser_options = ser_options.clone();
+        ser_options.field_names = Some(Arc::new(FieldNames::default()));
+        let mut de_options = de_options.clone();
+        de_options.struct_plans = Some(Arc::new(StructPlans::default()));
+        Self {
+            ser_options,
+            de_options,
+            marker: PhantomData,
+        }
+    }
+
+    /// Serialize `value` into a Python object
+    pub fn serialize<'py>(&self, py: Python<'py>, value: &T) -> Result<Bound<'py, PyAny>>
+    where
+        T: Serialize,
+    {
+        to_pyobject_with(py, value, &self.ser_options)
+    }
+
+    /// Deserialize a Python object into `T`
+    pub fn deserialize<'py, Any>(&self, obj: Bound<'py, Any>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        from_pyobject_with(obj, &self.de_options)
+    }
+}
+
+impl<T> Default for Codec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            ser_options: self.ser_options.clone(),
+            de_options: self.de_options.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Codec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Codec")
+            .field("ser_options", &self.ser_options)
+            .field("de_options", &self.de_options)
+            .finish()
+    }
+}
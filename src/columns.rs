@@ -0,0 +1,427 @@
+//! Columnar conversion between `[T]` and a `dict` of `list`s
+
+use crate::{
+    de::{Context, PyAnyDeserializer},
+    error::{Error, Result},
+    options::PyObjectSerializerOptions,
+    ser::PyAnySerializer,
+    shared,
+};
+use pyo3::{prelude::*, types::*};
+use serde::{
+    de::{self, DeserializeSeed, MapAccess},
+    ser::{self, Impossible},
+    Deserialize, Serialize, Serializer,
+};
+use std::rc::Rc;
+
+/// Serialize a slice of records into a `dict` which maps each field name to a `list` of values.
+///
+/// This produces `{"field": [v0, v1, ...], ...}` instead of a list of dicts,
+/// which is the shape pandas or polars constructors accept,
+/// and avoids creating a small dict for each record.
+/// Each record must be a struct or a map, and every record must have the same fields.
+///
+/// ```
+/// use serde::Serialize;
+/// use pyo3::{Python, types::PyAnyMethods};
+/// use serde_pyobject::{to_columns, pydict, pylist};
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+///     let columns = to_columns(py, &points).unwrap();
+///     assert!(columns.eq(pydict! { py,
+///         "x" => pylist![py; 1, 3].unwrap(),
+///         "y" => pylist![py; 2, 4].unwrap()
+///     }.unwrap()).unwrap());
+/// });
+/// ```
+pub fn to_columns<'py, T>(py: Python<'py>, rows: &[T]) -> Result<Bound<'py, PyDict>>
+where
+    T: Serialize,
+{
+    to_columns_with(py, rows, &PyObjectSerializerOptions::default())
+}
+
+/// [`to_columns`] with [`PyObjectSerializerOptions`] applied to each value
+pub fn to_columns_with<'py, T>(
+    py: Python<'py>,
+    rows: &[T],
+    options: &PyObjectSerializerOptions,
+) -> Result<Bound<'py, PyDict>>
+where
+    T: Serialize,
+{
+    let mut columns = Columns {
+        ser: PyAnySerializer::new(py, options),
+        columns: PyDict::new(py),
+        row: 0,
+        filled: 0,
+    };
+    for row in rows {
+        row.serialize(&mut columns)?;
+        columns.row += 1;
+    }
+    Ok(columns.columns)
+}
+
+struct Columns<'py> {
+    ser: PyAnySerializer<'py>,
+    columns: Bound<'py, PyDict>,
+    /// Index of the current row
+    row: usize,
+    /// Number of fields in the current row
+    filled: usize,
+}
+
+impl<'py> Columns<'py> {
+    fn push<T>(&mut self, key: Bound<'py, PyAny>, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.count_node()?;
+        let value = value.serialize(self.ser.clone())?;
+        match self.columns.get_item(&key)? {
+            Some(column) => {
+                let column = column.downcast_into::<PyList>()?;
+                if column.len() != self.row {
+                    return Err(ser::Error::custom(format_args!(
+                        "row {} has duplicated field {}",
+                        self.row,
+                        key.repr()?
+                    )));
+                }
+                column.append(value)?;
+            }
+            None if self.row == 0 => {
+                self.columns
+                    .set_item(key, PyList::new(self.ser.py(), [value])?)?;
+            }
+            None => {
+                return Err(ser::Error::custom(format_args!(
+                    "row {} has field {} which previous rows do not have",
+                    self.row,
+                    key.repr()?
+                )));
+            }
+        }
+        self.filled += 1;
+        Ok(())
+    }
+
+    fn finish_row(&mut self) -> Result<()> {
+        let filled = std::mem::take(&mut self.filled);
+        if filled == self.columns.len() {
+            return Ok(());
+        }
+        for (key, column) in self.columns.iter() {
+            if column.len()? == self.row {
+                return Err(ser::Error::custom(format_args!(
+                    "row {} does not have field {}",
+                    self.row,
+                    key.repr()?
+                )));
+            }
+        }
+        unreachable!("All columns have a value for the current row")
+    }
+}
+
+fn not_a_record() -> Error {
+    ser::Error::custom("to_columns accepts only a sequence of structs or maps")
+}
+
+macro_rules! unsupported {
+    ($f:ident $(, $arg:ty)*) => {
+        fn $f(self $(, _: $arg)*) -> Result<()> {
+            Err(not_a_record())
+        }
+    };
+}
+
+impl<'a, 'py> Serializer for &'a mut Columns<'py> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Row<'a, 'py>;
+    type SerializeStruct = Row<'a, 'py>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    unsupported!(serialize_bool, bool);
+    unsupported!(serialize_i8, i8);
+    unsupported!(serialize_i16, i16);
+    unsupported!(serialize_i32, i32);
+    unsupported!(serialize_i64, i64);
+    unsupported!(serialize_u8, u8);
+    unsupported!(serialize_u16, u16);
+    unsupported!(serialize_u32, u32);
+    unsupported!(serialize_u64, u64);
+    unsupported!(serialize_f32, f32);
+    unsupported!(serialize_f64, f64);
+    unsupported!(serialize_char, char);
+    unsupported!(serialize_str, &str);
+    unsupported!(serialize_bytes, &[u8]);
+    unsupported!(serialize_none);
+    unsupported!(serialize_unit);
+    unsupported!(serialize_unit_struct, &'static str);
+    unsupported!(serialize_unit_variant, &'static str, u32, &'static str);
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(not_a_record())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(not_a_record())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(not_a_record())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Row {
+            columns: self,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(Row {
+            columns: self,
+            key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(not_a_record())
+    }
+}
+
+struct Row<'a, 'py> {
+    columns: &'a mut Columns<'py>,
+    key: Option<Bound<'py, PyAny>>,
+}
+
+impl ser::SerializeStruct for Row<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = PyString::intern(self.columns.ser.py(), key).into_any();
+        self.columns.push(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.columns.finish_row()
+    }
+}
+
+impl ser::SerializeMap for Row<'_, '_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(self.columns.ser.clone())?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("Invalid Serialize implementation. Key is missing.");
+        self.columns.push(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.columns.finish_row()
+    }
+}
+
+/// Deserialize a `dict` of equally long sequences into a `Vec` of records, the reverse of [`to_columns`].
+///
+/// The `i`-th record is deserialized from a map which has the `i`-th element of each column.
+/// Errors report the offending column or row.
+///
+/// ```
+/// use serde::Deserialize;
+/// use pyo3::Python;
+/// use serde_pyobject::{from_columns, pydict, pylist};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let columns = pydict! { py,
+///         "x" => pylist![py; 1, 3].unwrap(),
+///         "y" => pylist![py; 2, 4].unwrap()
+///     }.unwrap();
+///     let points: Vec<Point> = from_columns(columns).unwrap();
+///     assert_eq!(points, vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+/// });
+/// ```
+pub fn from_columns<'py, 'de, T, Any>(columns: Bound<'py, Any>) -> Result<Vec<T>>
+where
+    T: Deserialize<'de>,
+{
+    let py = columns.py();
+    let columns = columns.into_any().downcast_into::<PyDict>()?;
+    let mut names = Vec::with_capacity(columns.len());
+    let mut values = Vec::with_capacity(columns.len());
+    let mut rows = None;
+    for (name, column) in columns.iter() {
+        let column = match column.downcast_into::<PySequence>() {
+            Ok(column) if !column.is_instance_of::<PyString>() => column,
+            _ => {
+                return Err(de::Error::custom(format_args!(
+                    "column {} is not a sequence",
+                    name.repr()?
+                )))
+            }
+        };
+        let len = column.len()?;
+        match rows {
+            None => rows = Some((len, name.clone())),
+            Some((rows, ref first)) if rows != len => {
+                return Err(de::Error::custom(format_args!(
+                    "column {} has {} rows while column {} has {} rows",
+                    name.repr()?,
+                    len,
+                    first.repr()?,
+                    rows
+                )));
+            }
+            Some(_) => {}
+        }
+        names.push(name);
+        values.push(column);
+    }
+
+    let _scope = shared::IdentityScope::enter();
+    let ctx = Context::new(&Default::default());
+    let rows = rows.map_or(0, |(rows, _)| rows);
+    let mut out = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let access = RowAccess {
+            names: &names,
+            columns: &values,
+            row,
+            index: 0,
+            ctx: &ctx,
+        };
+        let value = T::deserialize(de::value::MapAccessDeserializer::new(access))
+            .map_err(|err: Error| err.context(py, format_args!("row {}", row)))?;
+        out.push(value);
+    }
+    Ok(out)
+}
+
+/// Map of the `row`-th elements of columns
+struct RowAccess<'a, 'py> {
+    names: &'a [Bound<'py, PyAny>],
+    columns: &'a [Bound<'py, PySequence>],
+    row: usize,
+    /// Index of the next column
+    index: usize,
+    ctx: &'a Rc<Context>,
+}
+
+impl<'de> MapAccess<'de> for RowAccess<'_, '_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.names.get(self.index) {
+            Some(name) => seed
+                .deserialize(PyAnyDeserializer::with_context(name.clone(), self.ctx)?)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = &self.names[self.index];
+        let value = self.columns[self.index].get_item(self.row)?;
+        self.index += 1;
+        PyAnyDeserializer::with_context(value, self.ctx)
+            .and_then(|de| seed.deserialize(de))
+            .map_err(|err| {
+                let name = name
+                    .repr()
+                    .map_or_else(|_| "?".to_string(), |r| r.to_string());
+                err.context(self.names[0].py(), format_args!("column {}", name))
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.names.len() - self.index)
+    }
+}
@@ -0,0 +1,151 @@
+//! Python [`complex`](https://docs.python.org/3/library/functions.html#complex) support
+//!
+//! Serde has no complex numbers, and they are usually serialized as `(re, im)` tuples.
+//! Use this module with `#[serde(with = "serde_pyobject::complex")]` on a field
+//! of `(f64, f64)`, or `num_complex::Complex<f64>` and `Complex<f32>` with the `num-complex` feature,
+//! to serialize it into a `complex` object.
+//! Other serde formats, and [`PyObjectSerializerOptions::json_compatible`](crate::PyObjectSerializerOptions::json_compatible),
+//! see the `(re, im)` tuple.
+//!
+//! `complex` objects are always accepted as `(re, im)` tuples by [`from_pyobject`](crate::from_pyobject),
+//! so that `num_complex::Complex` deserializes from them even without this module.
+//! This module accepts real numbers as well.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Impedance {
+//!     #[serde(with = "serde_pyobject::complex")]
+//!     z: (f64, f64),
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let impedance = Impedance { z: (50.0, -12.5) };
+//!     let obj = to_pyobject(py, &impedance).unwrap();
+//!     assert_eq!(obj.repr().unwrap().to_string(), "{'z': (50-12.5j)}");
+//!
+//!     let reverted: Impedance = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, impedance);
+//! });
+//! ```
+
+use crate::error::Result;
+use pyo3::{prelude::*, types::PyComplex};
+use serde::{de, Deserializer, Serializer};
+use std::{fmt, marker::PhantomData};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is `(re, im)`
+pub(crate) const TOKEN: &str = "$serde_pyobject::complex";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Complex number types which this module converts into `complex`
+pub trait ComplexNumber: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    fn to_parts(&self) -> (f64, f64);
+    #[doc(hidden)]
+    fn from_parts(re: f64, im: f64) -> Self;
+}
+
+/// Serialize a complex number as `complex`
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ComplexNumber,
+{
+    serializer.serialize_newtype_struct(TOKEN, &value.to_parts())
+}
+
+/// Deserialize a complex number from `complex`, `(re, im)` or a real number
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ComplexNumber,
+{
+    deserializer.deserialize_tuple(2, ComplexVisitor(PhantomData))
+}
+
+struct ComplexVisitor<T>(PhantomData<T>);
+
+impl<'de, T: ComplexNumber> de::Visitor<'de> for ComplexVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a complex number")
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<T, E> {
+        Ok(T::from_parts(v, 0.0))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<T, E> {
+        Ok(T::from_parts(v as f64, 0.0))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<T, E> {
+        Ok(T::from_parts(v as f64, 0.0))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<T, A::Error> {
+        let re = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let im = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(T::from_parts(re, im))
+    }
+}
+
+impl sealed::Sealed for (f64, f64) {}
+
+impl ComplexNumber for (f64, f64) {
+    fn to_parts(&self) -> (f64, f64) {
+        *self
+    }
+
+    fn from_parts(re: f64, im: f64) -> Self {
+        (re, im)
+    }
+}
+
+#[cfg(feature = "num-complex")]
+mod num_complex_impl {
+    use super::{sealed::Sealed, ComplexNumber};
+    use num_complex::Complex;
+
+    impl Sealed for Complex<f64> {}
+
+    impl ComplexNumber for Complex<f64> {
+        fn to_parts(&self) -> (f64, f64) {
+            (self.re, self.im)
+        }
+
+        fn from_parts(re: f64, im: f64) -> Self {
+            Complex::new(re, im)
+        }
+    }
+
+    impl Sealed for Complex<f32> {}
+
+    impl ComplexNumber for Complex<f32> {
+        fn to_parts(&self) -> (f64, f64) {
+            (self.re.into(), self.im.into())
+        }
+
+        fn from_parts(re: f64, im: f64) -> Self {
+            Complex::new(re as f32, im as f32)
+        }
+    }
+}
+
+/// Create `complex` from the serialized `(re, im)`
+pub(crate) fn to_py<'py>(payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let (re, im): (f64, f64) = payload.extract()?;
+    Ok(PyComplex::from_doubles(payload.py(), re, im).into_any())
+}
@@ -0,0 +1,227 @@
+//! Flatten Python objects into `dict`
+//!
+//! These helpers are independent of serde, and useful to pre-flatten objects
+//! before passing them into other libraries which only accept `dict`.
+//! The conversion is recursive as `dataclasses.asdict` and `pydantic.BaseModel.model_dump` are.
+//!
+//! ```
+//! use pyo3::{prelude::*, ffi::c_str};
+//! use serde_pyobject::{convert::object_to_dict, pydict};
+//!
+//! Python::with_gil(|py| {
+//!     let globals = pyo3::types::PyDict::new(py);
+//!     py.run(c_str!(r#"
+//! from dataclasses import dataclass
+//!
+//! @dataclass
+//! class Point:
+//!     x: int
+//!     y: int
+//!
+//! point = Point(1, 2)
+//! "#), Some(&globals), None).unwrap();
+//!     let point = globals.get_item("point").unwrap().unwrap();
+//!     let dict = object_to_dict(&point).unwrap();
+//!     assert!(dict.eq(pydict! { py, "x" => 1, "y" => 2 }.unwrap()).unwrap());
+//! });
+//! ```
+
+use crate::error::Result;
+use pyo3::{
+    exceptions::{PyAttributeError, PyTypeError},
+    intern,
+    prelude::*,
+    types::*,
+};
+
+/// `dataclasses.asdict(obj)` if `obj` is a dataclass instance, otherwise `None`
+///
+/// A dataclass type itself is not an instance, and results in `None`.
+#[cfg(feature = "dataclass_support")]
+pub fn dataclass_as_dict<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyDict>>> {
+    let py = obj.py();
+    if obj.is_instance_of::<PyType>()
+        || !crate::py_module_cache::get(py, "dataclasses", "is_dataclass")?
+            .call1((obj,))?
+            .is_truthy()?
+    {
+        return Ok(None);
+    }
+    let dict = crate::py_module_cache::get(py, "dataclasses", "asdict")?.call1((obj,))?;
+    Ok(Some(dict.downcast_into()?))
+}
+
+/// `obj.model_dump()` if `obj` is a pydantic model instance, otherwise `None`
+///
+/// Models are detected by duck typing without importing pydantic:
+/// pydantic v2 models by `model_dump`, and v1 models by `__fields__` and `dict`.
+#[cfg(feature = "pydantic_support")]
+pub fn pydantic_model_as_dict<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyDict>>> {
+    pydantic_dump(obj, None)
+}
+
+/// `obj.model_dump(**kwargs)`, or `obj.dict(**kwargs)` of pydantic v1, if `obj` is a pydantic model instance
+#[cfg(feature = "pydantic_support")]
+pub(crate) fn pydantic_dump<'py>(
+    obj: &Bound<'py, PyAny>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> Result<Option<Bound<'py, PyDict>>> {
+    let py = obj.py();
+    if obj.is_instance_of::<PyType>() {
+        return Ok(None);
+    }
+    let dict = if obj.hasattr(intern!(py, "model_dump"))? {
+        obj.call_method(intern!(py, "model_dump"), (), kwargs)?
+    } else if obj.hasattr(intern!(py, "__fields__"))? && obj.hasattr(intern!(py, "dict"))? {
+        obj.call_method(intern!(py, "dict"), (), kwargs)?
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(dict.downcast_into()?))
+}
+
+/// `attr.asdict(obj)` if `obj` is an instance of a class decorated by `attrs`, otherwise `None`
+///
+/// Classes are detected by `attr.has` only when `attr` is imported already, since no attrs instance exists otherwise.
+/// An attrs class itself is not an instance, and results in `None`.
+#[cfg(feature = "attrs_support")]
+pub fn attrs_as_dict<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyDict>>> {
+    let py = obj.py();
+    if obj.is_instance_of::<PyType>() {
+        return Ok(None);
+    }
+    let Some(has) = crate::py_module_cache::get_imported(py, "attr", "has")? else {
+        return Ok(None);
+    };
+    if !has.call1((obj.get_type(),))?.is_truthy()? {
+        return Ok(None);
+    }
+    let dict = crate::py_module_cache::get(py, "attr", "asdict")?.call1((obj,))?;
+    Ok(Some(dict.downcast_into()?))
+}
+
+/// `msgspec.to_builtins(obj)` if `obj` is a `msgspec.Struct` instance, otherwise `None`
+///
+/// The result is a `dict`, or a `list` for structs declared with `array_like=True`.
+/// `bytes`, `datetime`, `uuid.UUID` and `decimal.Decimal` values are kept as they are rather than encoded into strings.
+/// Structs are detected only when `msgspec` is imported already, since no instance exists otherwise.
+#[cfg(feature = "msgspec")]
+pub fn msgspec_to_builtins<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    let Some(base) = crate::py_module_cache::get_imported(py, "msgspec", "Struct")? else {
+        return Ok(None);
+    };
+    if !obj.is_instance(&base)? {
+        return Ok(None);
+    }
+    let get = |module, name| crate::py_module_cache::get(py, module, name);
+    let builtin_types = PyTuple::new(
+        py,
+        [
+            get("builtins", "bytes")?,
+            get("builtins", "bytearray")?,
+            get("builtins", "memoryview")?,
+            get("datetime", "datetime")?,
+            get("datetime", "date")?,
+            get("datetime", "time")?,
+            get("uuid", "UUID")?,
+            get("decimal", "Decimal")?,
+        ],
+    )?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "builtin_types"), builtin_types)?;
+    let builtins = get("msgspec", "to_builtins")?.call((obj,), Some(&kwargs))?;
+    Ok(Some(builtins))
+}
+
+/// Attributes named in `__slots__` of the class of `obj` and its bases, or `None` if none of them defines `__slots__`
+///
+/// Slots which are not set, and `__dict__` and `__weakref__` slots, are skipped.
+pub fn slots_as_dict<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyDict>>> {
+    let py = obj.py();
+    let mut dict = None;
+    for class in obj.get_type().mro().iter() {
+        let Some(slots) = class
+            .getattr(intern!(py, "__dict__"))?
+            .get_item(intern!(py, "__slots__"))
+            .ok()
+        else {
+            continue;
+        };
+        let dict = dict.get_or_insert_with(|| PyDict::new(py));
+        // `__slots__ = "name"` declares a single slot
+        let names = match slots.downcast::<PyString>() {
+            Ok(name) => vec![name.clone().into_any()],
+            Err(_) => slots.try_iter()?.collect::<PyResult<_>>()?,
+        };
+        for name in names {
+            let name = name.downcast_into::<PyString>()?;
+            if name == "__dict__" || name == "__weakref__" || dict.contains(&name)? {
+                continue;
+            }
+            match obj.getattr(&name) {
+                Ok(value) => dict.set_item(name, value)?,
+                Err(err) if err.is_instance_of::<PyAttributeError>(py) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+    Ok(dict)
+}
+
+/// Convert `obj` into `dict` by the first applicable way of the following:
+///
+/// 1. `dict` itself is returned as is
+/// 2. `dataclass_as_dict` if `dataclass_support` feature is enabled
+/// 3. `pydantic_model_as_dict` if `pydantic_support` feature is enabled
+/// 4. `attrs_as_dict` if `attrs_support` feature is enabled
+/// 5. `dict(obj)` for other `collections.abc.Mapping`
+/// 6. a copy of `obj.__dict__` for other objects having it, with [`slots_as_dict`] added
+/// 7. [`slots_as_dict`] for other objects whose classes define `__slots__`
+///
+/// Otherwise `TypeError` is raised.
+///
+/// [`from_pyobject`](crate::from_pyobject) follows this chain for objects which are not mappings, sequences
+/// or other iterables, and for any objects of other types into maps, e.g. `HashMap`,
+/// except functions, classes and modules.
+pub fn object_to_dict<'py>(obj: &Bound<'py, PyAny>) -> Result<Bound<'py, PyDict>> {
+    let py = obj.py();
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        return Ok(dict.clone());
+    }
+    #[cfg(feature = "dataclass_support")]
+    if let Some(dict) = dataclass_as_dict(obj)? {
+        return Ok(dict);
+    }
+    #[cfg(feature = "pydantic_support")]
+    if let Some(dict) = pydantic_model_as_dict(obj)? {
+        return Ok(dict);
+    }
+    #[cfg(feature = "attrs_support")]
+    if let Some(dict) = attrs_as_dict(obj)? {
+        return Ok(dict);
+    }
+    if let Ok(mapping) = obj.downcast::<PyMapping>() {
+        let dict = PyDict::new(py);
+        dict.update(mapping)?;
+        return Ok(dict);
+    }
+    let slots = slots_as_dict(obj)?;
+    if let Ok(dict) = obj.getattr(intern!(py, "__dict__")) {
+        if let Ok(dict) = dict.downcast::<PyDict>() {
+            let dict = dict.copy()?;
+            if let Some(slots) = slots {
+                dict.update(slots.as_mapping())?;
+            }
+            return Ok(dict);
+        }
+    }
+    if let Some(slots) = slots {
+        return Ok(slots);
+    }
+    Err(PyTypeError::new_err(format!(
+        "'{}' object cannot be converted to dict",
+        obj.get_type().name()?
+    ))
+    .into())
+}
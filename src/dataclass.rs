@@ -0,0 +1,132 @@
+//! Python dataclasses generated from Rust types
+
+use crate::{error::Result, py_module_cache};
+use pyo3::{exceptions::PyTypeError, prelude::*, types::*};
+use serde::de::{self, value, DeserializeOwned, Deserializer, Visitor};
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+    sync::Mutex,
+};
+
+/// `TypeId` of a Rust struct -> dataclass mirroring it
+type Classes = HashMap<TypeId, Py<PyType>>;
+
+static CLASSES: Mutex<Option<Classes>> = Mutex::new(None);
+
+/// Create a dataclass mirroring the Rust struct `T` by `dataclasses.make_dataclass`
+///
+/// The class has the name of `T` and its fields in the declared order,
+/// with the names after `#[serde(rename)]`. Fields are annotated as `typing.Any`
+/// since their types are not visible through [`Deserialize`](serde::Deserialize).
+/// The class is created at the first call for each `T`, and the same class is returned afterwards
+/// until [`clear_caches`](crate::clear_caches) is called.
+///
+/// `T` must be deserialized as a struct, otherwise `TypeError` is raised.
+/// This requires the `dataclass_support` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde::Deserialize;
+/// use serde_pyobject::{create_dataclass, from_pyobject};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let class = create_dataclass::<Point>(py).unwrap();
+///     assert_eq!(class.name().unwrap(), "Point");
+///
+///     let point = class.call1((1, 2)).unwrap();
+///     assert_eq!(point.repr().unwrap(), "Point(x=1, y=2)");
+///     assert_eq!(from_pyobject::<Point, _>(point).unwrap(), Point { x: 1, y: 2 });
+/// });
+/// ```
+pub fn create_dataclass<T>(py: Python<'_>) -> Result<Bound<'_, PyType>>
+where
+    T: DeserializeOwned + 'static,
+{
+    let id = TypeId::of::<T>();
+    if let Some(class) = lookup(py, id) {
+        return Ok(class);
+    }
+
+    let mut traced = None;
+    let _ = T::deserialize(StructTracer(&mut traced));
+    let Some((name, fields)) = traced else {
+        return Err(PyTypeError::new_err(format!(
+            "cannot create a dataclass from {}, which is not a struct",
+            type_name::<T>()
+        ))
+        .into());
+    };
+
+    let class = make_dataclass(py, name, fields)?;
+
+    // Another thread may have created the class meanwhile, and the first one wins
+    let mut classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+    let class = classes
+        .get_or_insert_with(HashMap::new)
+        .entry(id)
+        .or_insert_with(|| class.unbind());
+    Ok(class.bind(py).clone())
+}
+
+/// Drop the classes created by [`create_dataclass`]
+pub(crate) fn clear() {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    // Release Python references outside of the lock
+    drop(classes);
+}
+
+/// `dataclasses.make_dataclass` with the fields annotated as `typing.Any`
+pub(crate) fn make_dataclass<'py>(
+    py: Python<'py>,
+    name: &str,
+    fields: &[impl AsRef<str>],
+) -> Result<Bound<'py, PyType>> {
+    let any = py_module_cache::get(py, "typing", "Any")?;
+    let fields = PyList::new(py, fields.iter().map(|field| (field.as_ref(), &any)))?;
+    Ok(py_module_cache::get(py, "dataclasses", "make_dataclass")?
+        .call1((name, fields))?
+        .downcast_into::<PyType>()?)
+}
+
+fn lookup(py: Python<'_>, id: TypeId) -> Option<Bound<'_, PyType>> {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+    let class = classes.as_ref()?.get(&id)?;
+    Some(class.bind(py).clone())
+}
+
+/// Deserializer which records the name and fields of the struct and aborts
+struct StructTracer<'a>(&'a mut Option<(&'static str, &'static [&'static str])>);
+
+impl<'de> Deserializer<'de> for StructTracer<'_> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> std::result::Result<V::Value, value::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> std::result::Result<V::Value, value::Error> {
+        *self.0 = Some((name, fields));
+        Err(de::Error::custom("traced"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
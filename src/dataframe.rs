@@ -0,0 +1,109 @@
+//! Data frames as sources of [`from_pyobject`](crate::from_pyobject)
+//!
+//! `pandas.DataFrame` with the `pandas` feature, `pyarrow.Table` and `pyarrow.RecordBatch`
+//! with the `pyarrow` feature, and `polars.DataFrame` with the `polars` feature,
+//! are deserialized as a sequence of their rows, each of which is a `dict` from column names to values
+//! as `to_dict("records")`, `to_pylist()` or `to_dicts()` gives.
+//! `pandas.Series` is deserialized as a sequence of its values, or as a map from its index to its values
+//! when the target is a map or a struct, e.g. `HashMap<String, f64>`.
+//! `polars.Series`, which has no index, is always a sequence of its values.
+//! Data frames are detected without importing these modules.
+
+use crate::{de::from_pyobject, error::Result, py_module_cache};
+use pyo3::{exceptions::PyTypeError, intern, prelude::*};
+use serde::de::DeserializeOwned;
+
+/// Deserialize the rows of a data frame, e.g. `pandas.DataFrame` or `pyarrow.Table`, into `Vec<T>`
+///
+/// This requires the `pandas`, `pyarrow` or `polars` feature, which enables the data frames of the module.
+/// [`from_pyobject`](crate::from_pyobject) accepts data frames as well,
+/// and this function additionally rejects other objects with `TypeError`.
+///
+/// ```no_run
+/// use serde::Deserialize;
+/// use pyo3::{prelude::*, ffi::c_str};
+/// use serde_pyobject::from_dataframe;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Row {
+///     name: String,
+///     score: f64,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let df = py
+///         .eval(
+///             c_str!("__import__('pandas').DataFrame({'name': ['a', 'b'], 'score': [0.5, 1.5]})"),
+///             None,
+///             None,
+///         )
+///         .unwrap();
+///     let rows: Vec<Row> = from_dataframe(df).unwrap();
+///     assert_eq!(rows[1], Row { name: "b".to_string(), score: 1.5 });
+/// });
+/// ```
+pub fn from_dataframe<'py, T, Any>(df: Bound<'py, Any>) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    let df = df.into_any();
+    match records(&df)? {
+        Some(rows) => from_pyobject(rows),
+        None => Err(
+            PyTypeError::new_err(format!("expected a data frame, got {}", df.get_type())).into(),
+        ),
+    }
+}
+
+/// Rows of `obj` as `list` of `dict` if it is a data frame, otherwise `None`
+pub(crate) fn records<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    #[cfg(feature = "pandas")]
+    if is_instance(obj, "pandas", "DataFrame")? {
+        let rows = obj.call_method1(intern!(py, "to_dict"), (intern!(py, "records"),))?;
+        return Ok(Some(rows));
+    }
+    #[cfg(feature = "pyarrow")]
+    if is_instance(obj, "pyarrow", "Table")? || is_instance(obj, "pyarrow", "RecordBatch")? {
+        return Ok(Some(obj.call_method0(intern!(py, "to_pylist"))?));
+    }
+    #[cfg(feature = "polars")]
+    if is_instance(obj, "polars", "DataFrame")? {
+        return Ok(Some(obj.call_method0(intern!(py, "to_dicts"))?));
+    }
+    Ok(None)
+}
+
+/// Values of `obj` as `list` if it is a series, otherwise `None`
+#[cfg(any(feature = "pandas", feature = "polars"))]
+pub(crate) fn series_values<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    #[cfg(feature = "pandas")]
+    if is_instance(obj, "pandas", "Series")? {
+        return Ok(Some(obj.call_method0(intern!(obj.py(), "tolist"))?));
+    }
+    #[cfg(feature = "polars")]
+    if is_instance(obj, "polars", "Series")? {
+        return Ok(Some(obj.call_method0(intern!(obj.py(), "to_list"))?));
+    }
+    Ok(None)
+}
+
+/// `dict` from the index to the values of `obj` if it is a series, otherwise `None`
+#[cfg(feature = "pandas")]
+pub(crate) fn series_items<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    if is_instance(obj, "pandas", "Series")? {
+        return Ok(Some(obj.call_method0(intern!(obj.py(), "to_dict"))?));
+    }
+    Ok(None)
+}
+
+fn is_instance(
+    obj: &Bound<'_, PyAny>,
+    module: &'static str,
+    class: &'static str,
+) -> PyResult<bool> {
+    match py_module_cache::get_imported(obj.py(), module, class)? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
+    }
+}
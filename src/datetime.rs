@@ -0,0 +1,880 @@
+//! Python [`datetime`](https://docs.python.org/3/library/datetime.html) support
+//!
+//! Date and time types of Rust crates have no counterparts in the serde data model,
+//! and their own `Serialize` implementations usually give strings.
+//! Use this module with `#[serde(with = "serde_pyobject::datetime")]` on a field
+//! to serialize it into a `datetime.datetime`, `datetime.date`, `datetime.time` or `datetime.timedelta` object,
+//! and to deserialize it back from such an object or its ISO 8601 string.
+//! Other serde formats see an ISO 8601 string, or `(seconds, microseconds)` for durations.
+//!
+//! Types of the standard library are always supported, and those of other crates are enabled by their features:
+//!
+//! | Feature  | Rust type                                               | Python type          |
+//! |----------|---------------------------------------------------------|----------------------|
+//! |          | `std::time::SystemTime`                                 | `datetime.datetime` in UTC |
+//! |          | `std::time::Duration`                                   | `datetime.timedelta` |
+//! | `chrono` | `DateTime<Utc>`, `DateTime<FixedOffset>`, `NaiveDateTime` | `datetime.datetime`  |
+//! | `chrono` | `NaiveDate`                                             | `datetime.date`      |
+//! | `chrono` | `NaiveTime`                                             | `datetime.time`      |
+//! | `chrono` | `TimeDelta`                                             | `datetime.timedelta` |
+//! | `chrono-tz` | `DateTime<chrono_tz::Tz>`                            | `datetime.datetime` in `zoneinfo.ZoneInfo` |
+//! | `time`   | `OffsetDateTime`, `PrimitiveDateTime`                   | `datetime.datetime`  |
+//! | `time`   | `Date`                                                  | `datetime.date`      |
+//! | `time`   | `Time`                                                  | `datetime.time`      |
+//! | `time`   | `Duration`                                              | `datetime.timedelta` |
+//! | `jiff`   | `Zoned`                                                 | `datetime.datetime` in `zoneinfo.ZoneInfo` |
+//! | `jiff`   | `Timestamp`, `civil::DateTime`                          | `datetime.datetime`  |
+//! | `jiff`   | `civil::Date`                                           | `datetime.date`      |
+//! | `jiff`   | `civil::Time`                                           | `datetime.time`      |
+//! | `jiff`   | `SignedDuration`                                        | `datetime.timedelta` |
+//!
+//! Python objects have microsecond precision, and finer digits are truncated.
+//! Without this module, [`from_pyobject`](crate::from_pyobject) reads `datetime`, `date` and `time` objects
+//! as their ISO 8601 strings, and `timedelta` objects as ISO 8601 durations in seconds, e.g. `PT90.5S`,
+//! so that types parsing such strings, e.g. `chrono::DateTime<Utc>`, work as they are.
+//! `SystemTime` is `(seconds, microseconds)` since the Unix epoch for other serde formats.
+//! Types of instants, e.g. `SystemTime` or `DateTime<Utc>`, reject naive `datetime` objects unless
+//! [`PyObjectDeserializerOptions::naive_datetimes`](crate::PyObjectDeserializerOptions::naive_datetimes)
+//! assumes their time zone.
+//! Use [`option`] for `Option<T>` fields.
+//!
+//! ```
+//! # #[cfg(feature = "chrono")] {
+//! use chrono::{DateTime, NaiveDate, Utc};
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "serde_pyobject::datetime")]
+//!     at: DateTime<Utc>,
+//!     #[serde(with = "serde_pyobject::datetime")]
+//!     day: NaiveDate,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let event = Event {
+//!         at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+//!         day: NaiveDate::from_ymd_opt(2023, 11, 14).unwrap(),
+//!     };
+//!     let obj = to_pyobject(py, &event).unwrap();
+//!     assert_eq!(
+//!         obj.repr().unwrap().to_string(),
+//!         "{'at': datetime.datetime(2023, 11, 14, 22, 13, 20, tzinfo=datetime.timezone.utc), 'day': datetime.date(2023, 11, 14)}"
+//!     );
+//!
+//!     let reverted: Event = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, event);
+//! });
+//! # }
+//! ```
+
+use crate::{error::Result, options::NaiveDateTimePolicy, py_module_cache};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict, IntoPyObjectExt};
+use serde::{de, de::DeserializeOwned, Deserializer, Serialize, Serializer};
+use std::{fmt, marker::PhantomData};
+
+/// Name of the newtype struct of `datetime.datetime`, whose payload is an ISO 8601 string
+pub(crate) const DATETIME_TOKEN: &str = "$serde_pyobject::datetime";
+/// Name of the newtype struct of an aware `datetime.datetime`, whose payload is an ISO 8601 string with the offset
+///
+/// Naive `datetime` objects are read following [`NaiveDateTimePolicy`].
+pub(crate) const AWARE_DATETIME_TOKEN: &str = "$serde_pyobject::aware_datetime";
+/// Name of the newtype struct of `datetime.date`, whose payload is an ISO 8601 string
+pub(crate) const DATE_TOKEN: &str = "$serde_pyobject::date";
+/// Name of the newtype struct of `datetime.time`, whose payload is an ISO 8601 string
+pub(crate) const TIME_TOKEN: &str = "$serde_pyobject::time";
+/// Name of the newtype struct of `datetime.timedelta`, whose payload is `(seconds, microseconds)`
+pub(crate) const TIMEDELTA_TOKEN: &str = "$serde_pyobject::timedelta";
+/// Name of the newtype struct of `datetime.datetime` in a `zoneinfo.ZoneInfo` time zone,
+/// whose payload is an ISO 8601 string followed by the time zone name in brackets as RFC 9557
+pub(crate) const ZONED_TOKEN: &str = "$serde_pyobject::zoned";
+/// Name of the newtype struct of `datetime.datetime` in UTC, whose payload is `(seconds, microseconds)` since the Unix epoch
+pub(crate) const TIMESTAMP_TOKEN: &str = "$serde_pyobject::timestamp";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Date and time types which this module converts into `datetime` objects
+pub trait Temporal: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    const TOKEN: &'static str;
+    #[doc(hidden)]
+    type Payload: Serialize + DeserializeOwned;
+    #[doc(hidden)]
+    fn to_payload(&self) -> Self::Payload;
+    #[doc(hidden)]
+    fn from_payload(payload: Self::Payload) -> std::result::Result<Self, String>;
+}
+
+/// Serialize a date or time value as a `datetime` object
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Temporal,
+{
+    serializer.serialize_newtype_struct(T::TOKEN, &value.to_payload())
+}
+
+/// Deserialize a date or time value from a `datetime` object or its ISO 8601 string
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Temporal,
+{
+    let payload = deserializer.deserialize_newtype_struct(T::TOKEN, PayloadVisitor(PhantomData))?;
+    T::from_payload(payload).map_err(de::Error::custom)
+}
+
+/// `#[serde(with = "serde_pyobject::datetime::option")]` for `Option<T>` fields, where `None` is `None`
+pub mod option {
+    use super::*;
+
+    /// Serialize an optional date or time value as a `datetime` object or `None`
+    pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Temporal,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(&Wrap(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize an optional date or time value from a `datetime` object, its ISO 8601 string or `None`
+    pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Temporal,
+    {
+        struct Unwrap<T>(T);
+
+        impl<'de, T: Temporal> de::Deserialize<'de> for Unwrap<T> {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                super::deserialize(deserializer).map(Unwrap)
+            }
+        }
+
+        let value: Option<Unwrap<T>> = de::Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+
+    struct Wrap<'a, T>(&'a T);
+
+    impl<T: Temporal> Serialize for Wrap<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+}
+
+/// Accepts the payload in the newtype struct
+struct PayloadVisitor<P>(PhantomData<P>);
+
+impl<'de, P: DeserializeOwned> de::Visitor<'de> for PayloadVisitor<P> {
+    type Value = P;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a date, time or duration")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<P, D::Error> {
+        P::deserialize(deserializer)
+    }
+}
+
+/// Whether `name` is one of the newtype structs of this module
+pub(crate) fn is_token(name: &str) -> bool {
+    matches!(
+        name,
+        DATETIME_TOKEN
+            | AWARE_DATETIME_TOKEN
+            | DATE_TOKEN
+            | TIME_TOKEN
+            | TIMEDELTA_TOKEN
+            | ZONED_TOKEN
+            | TIMESTAMP_TOKEN
+    )
+}
+
+/// Create the `datetime` object of the newtype struct `name` from the serialized `payload`
+pub(crate) fn to_py<'py>(name: &str, payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let py = payload.py();
+    let class = match name {
+        DATETIME_TOKEN | AWARE_DATETIME_TOKEN => "datetime",
+        DATE_TOKEN => "date",
+        TIME_TOKEN => "time",
+        ZONED_TOKEN => {
+            let payload: String = payload.extract()?;
+            let datetime = py_module_cache::get(py, "datetime", "datetime")?;
+            let Some((iso, zone)) = payload.strip_suffix(']').and_then(|s| s.split_once('['))
+            else {
+                return Ok(datetime.call_method1("fromisoformat", (payload,))?);
+            };
+            let datetime = datetime.call_method1("fromisoformat", (iso,))?;
+            let zone = zone.trim_start_matches('!');
+            // Fixed offsets are kept by `fromisoformat`
+            if zone.starts_with(['+', '-']) {
+                return Ok(datetime);
+            }
+            let zone = py_module_cache::get(py, "zoneinfo", "ZoneInfo")?.call1((zone,))?;
+            return Ok(datetime.call_method1("astimezone", (zone,))?);
+        }
+        TIMESTAMP_TOKEN => {
+            let (seconds, microseconds): (i64, u32) = payload.extract()?;
+            let timedelta = py_module_cache::get(py, "datetime", "timedelta")?;
+            let delta = timedelta.call1((0, seconds, microseconds))?;
+            return Ok(epoch(py)?.add(delta)?);
+        }
+        _ => {
+            let (seconds, microseconds): (i64, u32) = payload.extract()?;
+            let timedelta = py_module_cache::get(py, "datetime", "timedelta")?;
+            return Ok(timedelta.call1((0, seconds, microseconds))?);
+        }
+    };
+    let class = py_module_cache::get(py, "datetime", class)?;
+    Ok(class.call_method1("fromisoformat", (payload,))?)
+}
+
+/// Payload of the newtype struct `name` from `obj`, or `None` if `obj` is not the `datetime` object of `name`
+///
+/// Naive `datetime` objects for aware types are made aware following `naive`.
+pub(crate) fn from_py<'py>(
+    name: &str,
+    obj: &Bound<'py, PyAny>,
+    naive: NaiveDateTimePolicy,
+) -> Result<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    let is_instance = |class| -> PyResult<bool> {
+        obj.is_instance(&py_module_cache::get(py, "datetime", class)?)
+    };
+    let aware;
+    let obj = match name {
+        AWARE_DATETIME_TOKEN | ZONED_TOKEN | TIMESTAMP_TOKEN if is_instance("datetime")? => {
+            aware = make_aware(obj, naive)?;
+            &aware
+        }
+        _ => obj,
+    };
+    let matched = match name {
+        DATETIME_TOKEN | AWARE_DATETIME_TOKEN => is_instance("datetime")?,
+        // `datetime` is a subclass of `date`
+        DATE_TOKEN => is_instance("date")? && !is_instance("datetime")?,
+        TIME_TOKEN => is_instance("time")?,
+        ZONED_TOKEN => {
+            if !is_instance("datetime")? {
+                return Ok(None);
+            }
+            let iso = obj.call_method0("isoformat")?.extract::<String>()?;
+            // `zoneinfo.ZoneInfo` has the name as `key`
+            let tzinfo = obj.getattr("tzinfo")?;
+            let payload = match tzinfo.hasattr("key")? {
+                true => format!("{iso}[{}]", tzinfo.getattr("key")?.extract::<String>()?),
+                false => iso,
+            };
+            return Ok(Some(payload.into_bound_py_any(py)?));
+        }
+        TIMESTAMP_TOKEN => {
+            if !is_instance("datetime")? {
+                return Ok(None);
+            }
+            return Ok(Some(timedelta_payload(&obj.sub(epoch(py)?)?)?));
+        }
+        _ => {
+            if !is_instance("timedelta")? {
+                return Ok(None);
+            }
+            return Ok(Some(timedelta_payload(obj)?));
+        }
+    };
+    if !matched {
+        return Ok(None);
+    }
+    Ok(Some(obj.call_method0("isoformat")?))
+}
+
+/// `datetime` object `obj` as it is if aware, or made aware following `policy` if naive
+fn make_aware<'py>(
+    obj: &Bound<'py, PyAny>,
+    policy: NaiveDateTimePolicy,
+) -> Result<Bound<'py, PyAny>> {
+    if !obj.call_method0("utcoffset")?.is_none() {
+        return Ok(obj.clone());
+    }
+    match policy {
+        NaiveDateTimePolicy::Error => Err(PyValueError::new_err(format!(
+            "naive datetime {} is not allowed, see PyObjectDeserializerOptions::naive_datetimes",
+            obj.str()?
+        ))
+        .into()),
+        NaiveDateTimePolicy::AssumeUtc => {
+            let utc = py_module_cache::get(obj.py(), "datetime", "timezone")?.getattr("utc")?;
+            let kwargs = PyDict::new(obj.py());
+            kwargs.set_item("tzinfo", utc)?;
+            Ok(obj.call_method("replace", (), Some(&kwargs))?)
+        }
+        // `astimezone` regards naive `datetime` as the local time of the system
+        NaiveDateTimePolicy::AssumeLocal => Ok(obj.call_method0("astimezone")?),
+    }
+}
+
+/// Whether `obj` is a `date`, `time` or `timedelta` object, including `datetime` as a subclass of `date`
+///
+/// Cheaper than [`iso_string`] since this only checks the types.
+pub(crate) fn is_datetime_like(obj: &Bound<'_, PyAny>) -> Result<bool> {
+    for name in ["date", "time", "timedelta"] {
+        if let Some(class) = py_module_cache::get_imported(obj.py(), "datetime", name)? {
+            if obj.is_instance(&class)? {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// ISO 8601 string of a `datetime`, `date` or `time` object, or the ISO 8601 duration of a `timedelta` object
+/// for `deserialize_any`, or `None` for other objects
+///
+/// Durations are in seconds, e.g. `PT90.5S` or `-PT1S`, which parsers of ISO 8601 durations accept.
+pub(crate) fn iso_string(obj: &Bound<'_, PyAny>) -> Result<Option<String>> {
+    let py = obj.py();
+    // `datetime` is a subclass of `date`
+    for class in ["date", "time"] {
+        if obj.is_instance(&py_module_cache::get(py, "datetime", class)?)? {
+            return Ok(Some(obj.call_method0("isoformat")?.extract()?));
+        }
+    }
+    if !obj.is_instance(&py_module_cache::get(py, "datetime", "timedelta")?)? {
+        return Ok(None);
+    }
+    let (seconds, microseconds): (i64, u32) = timedelta_payload(obj)?.extract()?;
+    let total = i128::from(seconds) * 1_000_000 + i128::from(microseconds);
+    let sign = if total < 0 { "-" } else { "" };
+    let (seconds, microseconds) = (total.abs() / 1_000_000, total.abs() % 1_000_000);
+    let iso = match microseconds {
+        0 => format!("{sign}PT{seconds}S"),
+        _ => {
+            let fraction = format!("{microseconds:06}");
+            format!("{sign}PT{seconds}.{}S", fraction.trim_end_matches('0'))
+        }
+    };
+    Ok(Some(iso))
+}
+
+/// `(seconds, microseconds)` of `timedelta`
+fn timedelta_payload<'py>(timedelta: &Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let days: i64 = timedelta.getattr("days")?.extract()?;
+    let seconds: i64 = timedelta.getattr("seconds")?.extract()?;
+    let microseconds: u32 = timedelta.getattr("microseconds")?.extract()?;
+    Ok((days * 86400 + seconds, microseconds).into_bound_py_any(timedelta.py())?)
+}
+
+/// The Unix epoch as an aware `datetime`
+fn epoch(py: Python<'_>) -> Result<Bound<'_, PyAny>> {
+    let utc = py_module_cache::get(py, "datetime", "timezone")?.getattr("utc")?;
+    let datetime = py_module_cache::get(py, "datetime", "datetime")?;
+    Ok(datetime.call1((1970, 1, 1, 0, 0, 0, 0, utc))?)
+}
+
+mod std_impl {
+    use super::{sealed::Sealed, Temporal, TIMEDELTA_TOKEN, TIMESTAMP_TOKEN};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    impl Sealed for Duration {}
+
+    impl Temporal for Duration {
+        const TOKEN: &'static str = TIMEDELTA_TOKEN;
+        type Payload = (i64, u32);
+
+        fn to_payload(&self) -> (i64, u32) {
+            let seconds = i64::try_from(self.as_secs()).unwrap_or(i64::MAX);
+            (seconds, self.subsec_micros())
+        }
+
+        fn from_payload((seconds, microseconds): (i64, u32)) -> Result<Self, String> {
+            let seconds = u64::try_from(seconds)
+                .map_err(|_| format!("negative duration of {seconds} seconds"))?;
+            if microseconds >= 1_000_000 {
+                return Err(format!("{microseconds} microseconds are out of range"));
+            }
+            Ok(Duration::new(seconds, microseconds * 1000))
+        }
+    }
+
+    impl Sealed for SystemTime {}
+
+    impl Temporal for SystemTime {
+        const TOKEN: &'static str = TIMESTAMP_TOKEN;
+        type Payload = (i64, u32);
+
+        fn to_payload(&self) -> (i64, u32) {
+            match self.duration_since(UNIX_EPOCH) {
+                Ok(after) => after.to_payload(),
+                // Round down to the earlier microsecond as `timedelta` does
+                Err(before) => {
+                    let before = before.duration();
+                    let seconds = i64::try_from(before.as_secs()).unwrap_or(i64::MAX);
+                    match before.subsec_nanos() {
+                        0 => (-seconds, 0),
+                        nanoseconds => (-seconds - 1, (1_000_000_000 - nanoseconds) / 1000),
+                    }
+                }
+            }
+        }
+
+        fn from_payload((seconds, microseconds): (i64, u32)) -> Result<Self, String> {
+            let out_of_range =
+                || format!("time of {seconds} seconds since the Unix epoch is out of range");
+            let since = Duration::from_secs(seconds.unsigned_abs());
+            let time = match seconds < 0 {
+                true => UNIX_EPOCH.checked_sub(since),
+                false => UNIX_EPOCH.checked_add(since),
+            }
+            .ok_or_else(out_of_range)?;
+            if microseconds >= 1_000_000 {
+                return Err(format!("{microseconds} microseconds are out of range"));
+            }
+            time.checked_add(Duration::from_micros(microseconds.into()))
+                .ok_or_else(out_of_range)
+        }
+    }
+}
+
+/// Format the fraction of a second as Python's `isoformat` does, i.e. six digits unless it is zero
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn fraction(nanoseconds: u32) -> String {
+    match nanoseconds / 1000 {
+        0 => String::new(),
+        micros => format!(".{micros:06}"),
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use super::{
+        fraction, sealed::Sealed, Temporal, AWARE_DATETIME_TOKEN, DATETIME_TOKEN, DATE_TOKEN,
+        TIMEDELTA_TOKEN, TIME_TOKEN,
+    };
+    use chrono::{
+        DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Utc,
+    };
+
+    impl Sealed for NaiveDate {}
+
+    impl Temporal for NaiveDate {
+        const TOKEN: &'static str = DATE_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            self.format("%Y-%m-%d").to_string()
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            NaiveDate::parse_from_str(&payload, "%Y-%m-%d").map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for NaiveTime {}
+
+    impl Temporal for NaiveTime {
+        const TOKEN: &'static str = TIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            let fraction = fraction(self.nanosecond() % 1_000_000_000);
+            format!("{}{fraction}", self.format("%H:%M:%S"))
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            NaiveTime::parse_from_str(&payload, "%H:%M:%S%.f").map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for NaiveDateTime {}
+
+    impl Temporal for NaiveDateTime {
+        const TOKEN: &'static str = DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            let fraction = fraction(self.nanosecond() % 1_000_000_000);
+            format!("{}{fraction}", self.format("%Y-%m-%dT%H:%M:%S"))
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            NaiveDateTime::parse_from_str(&payload, "%Y-%m-%dT%H:%M:%S%.f")
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for DateTime<FixedOffset> {}
+
+    impl Temporal for DateTime<FixedOffset> {
+        const TOKEN: &'static str = AWARE_DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            let fraction = fraction(self.nanosecond() % 1_000_000_000);
+            format!(
+                "{}{fraction}{}",
+                self.format("%Y-%m-%dT%H:%M:%S"),
+                self.format("%:z")
+            )
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            DateTime::parse_from_rfc3339(&payload).map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for DateTime<Utc> {}
+
+    impl Temporal for DateTime<Utc> {
+        const TOKEN: &'static str = AWARE_DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            self.fixed_offset().to_payload()
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            DateTime::<FixedOffset>::from_payload(payload).map(|datetime| datetime.to_utc())
+        }
+    }
+
+    impl Sealed for TimeDelta {}
+
+    impl Temporal for TimeDelta {
+        const TOKEN: &'static str = TIMEDELTA_TOKEN;
+        type Payload = (i64, u32);
+
+        fn to_payload(&self) -> (i64, u32) {
+            // `timedelta` keeps the microseconds non-negative
+            let mut seconds = self.num_seconds();
+            let mut nanoseconds = self.subsec_nanos();
+            if nanoseconds < 0 {
+                seconds -= 1;
+                nanoseconds += 1_000_000_000;
+            }
+            (seconds, nanoseconds as u32 / 1000)
+        }
+
+        fn from_payload((seconds, microseconds): (i64, u32)) -> Result<Self, String> {
+            TimeDelta::new(seconds, microseconds.saturating_mul(1000))
+                .ok_or_else(|| format!("duration of {seconds} seconds is out of range"))
+        }
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+mod chrono_tz_impl {
+    use super::{fraction, sealed::Sealed, Temporal, ZONED_TOKEN};
+    use chrono::{DateTime, Timelike};
+    use chrono_tz::Tz;
+
+    impl Sealed for DateTime<Tz> {}
+
+    impl Temporal for DateTime<Tz> {
+        const TOKEN: &'static str = ZONED_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            let fraction = fraction(self.nanosecond() % 1_000_000_000);
+            format!(
+                "{}{fraction}{}[{}]",
+                self.format("%Y-%m-%dT%H:%M:%S"),
+                self.format("%:z"),
+                self.timezone().name()
+            )
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            let (iso, zone) = match payload.strip_suffix(']').and_then(|s| s.split_once('[')) {
+                Some((iso, zone)) => (iso, zone.trim_start_matches('!')),
+                // Time zones other than `zoneinfo.ZoneInfo`, e.g. `datetime.timezone.utc`, have no name
+                None => (payload.as_str(), "UTC"),
+            };
+            let datetime = DateTime::parse_from_rfc3339(iso).map_err(|e| e.to_string())?;
+            if zone == "UTC" && datetime.offset().local_minus_utc() != 0 {
+                return Err(format!("{payload} has no IANA time zone name"));
+            }
+            let tz: Tz = zone
+                .parse()
+                .map_err(|e: chrono_tz::ParseError| e.to_string())?;
+            Ok(datetime.with_timezone(&tz))
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use super::{
+        fraction, sealed::Sealed, Temporal, AWARE_DATETIME_TOKEN, DATETIME_TOKEN, DATE_TOKEN,
+        TIMEDELTA_TOKEN, TIME_TOKEN,
+    };
+    use time::{
+        format_description::well_known::{Iso8601, Rfc3339},
+        Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+    };
+
+    fn date(date: Date) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        )
+    }
+
+    fn time(time: Time) -> String {
+        let fraction = fraction(time.nanosecond());
+        format!(
+            "{:02}:{:02}:{:02}{fraction}",
+            time.hour(),
+            time.minute(),
+            time.second()
+        )
+    }
+
+    fn offset(offset: UtcOffset) -> String {
+        let (hours, minutes, seconds) = offset.as_hms();
+        let sign = if offset.is_negative() { '-' } else { '+' };
+        let (hours, minutes, seconds) = (hours.abs(), minutes.abs(), seconds.abs());
+        match seconds {
+            0 => format!("{sign}{hours:02}:{minutes:02}"),
+            _ => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}"),
+        }
+    }
+
+    impl Sealed for Date {}
+
+    impl Temporal for Date {
+        const TOKEN: &'static str = DATE_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            date(*self)
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            Date::parse(&payload, &Iso8601::DEFAULT).map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for Time {}
+
+    impl Temporal for Time {
+        const TOKEN: &'static str = TIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            time(*self)
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            Time::parse(&payload, &Iso8601::DEFAULT).map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for PrimitiveDateTime {}
+
+    impl Temporal for PrimitiveDateTime {
+        const TOKEN: &'static str = DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            format!("{}T{}", date(self.date()), time(self.time()))
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            PrimitiveDateTime::parse(&payload, &Iso8601::DEFAULT).map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for OffsetDateTime {}
+
+    impl Temporal for OffsetDateTime {
+        const TOKEN: &'static str = AWARE_DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            format!(
+                "{}T{}{}",
+                date(self.date()),
+                time(self.time()),
+                offset(self.offset())
+            )
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            OffsetDateTime::parse(&payload, &Rfc3339)
+                .or_else(|_| OffsetDateTime::parse(&payload, &Iso8601::DEFAULT))
+                .map_err(|e| e.to_string())
+        }
+    }
+
+    impl Sealed for Duration {}
+
+    impl Temporal for Duration {
+        const TOKEN: &'static str = TIMEDELTA_TOKEN;
+        type Payload = (i64, u32);
+
+        fn to_payload(&self) -> (i64, u32) {
+            // `timedelta` keeps the microseconds non-negative
+            let mut seconds = self.whole_seconds();
+            let mut nanoseconds = self.subsec_nanoseconds();
+            if nanoseconds < 0 {
+                seconds -= 1;
+                nanoseconds += 1_000_000_000;
+            }
+            (seconds, nanoseconds as u32 / 1000)
+        }
+
+        fn from_payload((seconds, microseconds): (i64, u32)) -> Result<Self, String> {
+            if microseconds >= 1_000_000 {
+                return Err(format!("{microseconds} microseconds are out of range"));
+            }
+            Ok(Duration::new(seconds, microseconds as i32 * 1000))
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_impl {
+    use super::{
+        sealed::Sealed, Temporal, AWARE_DATETIME_TOKEN, DATETIME_TOKEN, DATE_TOKEN,
+        TIMEDELTA_TOKEN, TIME_TOKEN, ZONED_TOKEN,
+    };
+    use jiff::{
+        civil::{Date, DateTime, Time},
+        fmt::temporal::Pieces,
+        tz::{Offset, TimeZone},
+        SignedDuration, Timestamp, Zoned,
+    };
+    use std::fmt::Display;
+
+    /// Format with six fractional digits as Python's `isoformat` does, or none if they are zero
+    fn iso(value: impl Display, nanoseconds: i32) -> String {
+        let precision = if nanoseconds / 1000 == 0 { 0 } else { 6 };
+        format!("{value:.precision$}")
+    }
+
+    impl Sealed for Date {}
+
+    impl Temporal for Date {
+        const TOKEN: &'static str = DATE_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            self.to_string()
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            payload.parse().map_err(|e: jiff::Error| e.to_string())
+        }
+    }
+
+    impl Sealed for Time {}
+
+    impl Temporal for Time {
+        const TOKEN: &'static str = TIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            iso(self, self.subsec_nanosecond())
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            payload.parse().map_err(|e: jiff::Error| e.to_string())
+        }
+    }
+
+    impl Sealed for DateTime {}
+
+    impl Temporal for DateTime {
+        const TOKEN: &'static str = DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            iso(self, self.subsec_nanosecond())
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            payload.parse().map_err(|e: jiff::Error| e.to_string())
+        }
+    }
+
+    impl Sealed for Timestamp {}
+
+    impl Temporal for Timestamp {
+        const TOKEN: &'static str = AWARE_DATETIME_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            // `+00:00` rather than `Z`, which `fromisoformat` rejects before Python 3.11
+            let datetime = Offset::UTC.to_datetime(*self);
+            format!("{}+00:00", iso(datetime, datetime.subsec_nanosecond()))
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            payload.parse().map_err(|e: jiff::Error| e.to_string())
+        }
+    }
+
+    impl Sealed for Zoned {}
+
+    impl Temporal for Zoned {
+        const TOKEN: &'static str = ZONED_TOKEN;
+        type Payload = String;
+
+        fn to_payload(&self) -> String {
+            iso(self, self.subsec_nanosecond())
+        }
+
+        fn from_payload(payload: String) -> Result<Self, String> {
+            if payload.ends_with(']') {
+                return payload.parse().map_err(|e: jiff::Error| e.to_string());
+            }
+            // Time zones other than `zoneinfo.ZoneInfo` give only the offset
+            let timestamp: Timestamp = payload.parse().map_err(|e: jiff::Error| e.to_string())?;
+            let offset = Pieces::parse(&payload)
+                .map_err(|e| e.to_string())?
+                .to_numeric_offset()
+                .unwrap_or(Offset::UTC);
+            Ok(timestamp.to_zoned(TimeZone::fixed(offset)))
+        }
+    }
+
+    impl Sealed for SignedDuration {}
+
+    impl Temporal for SignedDuration {
+        const TOKEN: &'static str = TIMEDELTA_TOKEN;
+        type Payload = (i64, u32);
+
+        fn to_payload(&self) -> (i64, u32) {
+            // `timedelta` keeps the microseconds non-negative
+            let mut seconds = self.as_secs();
+            let mut nanoseconds = self.subsec_nanos();
+            if nanoseconds < 0 {
+                seconds -= 1;
+                nanoseconds += 1_000_000_000;
+            }
+            (seconds, nanoseconds as u32 / 1000)
+        }
+
+        fn from_payload((seconds, microseconds): (i64, u32)) -> Result<Self, String> {
+            if microseconds >= 1_000_000 {
+                return Err(format!("{microseconds} microseconds are out of range"));
+            }
+            Ok(SignedDuration::new(seconds, microseconds as i32 * 1000))
+        }
+    }
+}
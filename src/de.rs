@@ -1,12 +1,42 @@
-use crate::error::{Error, Result};
-use pyo3::{types::*, Bound};
+use crate::{
+    array, convert, datetime, decimal,
+    error::{Error, Result},
+    fraction, ipaddress,
+    options::{NonFinitePolicy, PyObjectDeserializerOptions, SingletonPolicy, UnknownKeyPolicy},
+    path, py_module_cache, set, shared,
+    singleton::{ELLIPSIS_TOKEN, NOT_IMPLEMENTED_TOKEN},
+    SIGNAL_CHECK_INTERVAL,
+};
+use pyo3::{
+    exceptions::{PyAttributeError, PyReferenceError, PyTypeError, PyValueError},
+    intern,
+    prelude::*,
+    types::*,
+    Bound, IntoPyObjectExt,
+};
 use serde::{
-    de::{self, value::StrDeserializer, MapAccess, SeqAccess, Visitor},
+    de::{
+        self,
+        value::{StrDeserializer, U32Deserializer},
+        MapAccess, SeqAccess, Visitor,
+    },
     forward_to_deserialize_any, Deserialize, Deserializer,
 };
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    rc::Rc,
+};
 
 /// Deserialize a Python object into Rust type `T: Deserialize`.
 ///
+/// `weakref.ref` and `weakref.proxy` are dereferenced, and their referents are deserialized.
+/// A dead weak reference results in `ReferenceError`.
+///
+/// Objects implementing `collections.abc.Mapping` or `collections.abc.Sequence`,
+/// e.g. `collections.UserDict` or `collections.UserList`, are deserialized by their contents
+/// as `dict` or `list` are, and `collections.UserString` as `str`.
+///
 /// # Examples
 ///
 /// ## primitive
@@ -84,7 +114,7 @@ use serde::{
 ///
 /// ```
 /// use serde::Deserialize;
-/// use pyo3::{Python, types::PyString};
+/// use pyo3::{Python, IntoPyObject, types::PyString};
 /// use serde_pyobject::from_pyobject;
 ///
 /// #[derive(Debug, PartialEq, Deserialize)]
@@ -97,6 +127,11 @@ use serde::{
 ///     let any = PyString::new_bound(py, "A");
 ///     let out: E = from_pyobject(any).unwrap();
 ///     assert_eq!(out, E::A);
+///
+///     // by the index of the variant
+///     let any = 1.into_pyobject(py).unwrap();
+///     let out: E = from_pyobject(any).unwrap();
+///     assert_eq!(out, E::B);
 /// })
 /// ```
 ///
@@ -266,6 +301,64 @@ use serde::{
 /// });
 /// ```
 ///
+/// ## struct from sequence
+///
+/// A `list` or `tuple`, e.g. a row from a database cursor, is deserialized in the declared order of fields.
+/// Surplus elements are ignored, and missing trailing fields take their `#[serde(default)]`.
+/// [`PyObjectDeserializerOptions::exact_struct_length`] requires exactly one element for each field instead.
+///
+/// ```
+/// use serde::Deserialize;
+/// use pyo3::{Python, IntoPyObject};
+/// use serde_pyobject::from_pyobject;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Row {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let row = (1, "alice").into_pyobject(py).unwrap();
+///     let row: Row = from_pyobject(row).unwrap();
+///     assert_eq!(row, Row { id: 1, name: "alice".to_string() });
+/// });
+/// ```
+///
+/// ## struct from attributes
+///
+/// Objects other than `dict` provide the fields of a struct as their attributes.
+/// Only the attributes named by the fields are looked up.
+/// Missing attributes, i.e. `AttributeError`, are treated as missing keys of `dict`,
+/// so that `Option` fields become `None` and `#[serde(default)]` fields take their defaults.
+///
+/// ```
+/// use serde::Deserialize;
+/// use pyo3::{Python, ffi::c_str};
+/// use serde_pyobject::from_pyobject;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let obj = py.eval(c_str!("__import__('types').SimpleNamespace(x=1, y=2, z=3)"), None, None).unwrap();
+///     let point: Point = from_pyobject(obj).unwrap();
+///     assert_eq!(point, Point { x: 1, y: 2 });
+///
+///     #[derive(Debug, PartialEq, Deserialize)]
+///     struct Label {
+///         text: String,
+///         color: Option<String>,
+///     }
+///     let obj = py.eval(c_str!("__import__('types').SimpleNamespace(text='hi')"), None, None).unwrap();
+///     let label: Label = from_pyobject(obj).unwrap();
+///     assert_eq!(label, Label { text: "hi".to_string(), color: None });
+/// });
+/// ```
+///
 /// ## struct variant
 ///
 /// ```
@@ -293,11 +386,502 @@ use serde::{
 /// });
 /// ```
 pub fn from_pyobject<'py, 'de, T: Deserialize<'de>, Any>(any: Bound<'py, Any>) -> Result<T> {
+    from_pyobject_with(any, &PyObjectDeserializerOptions::default())
+}
+
+/// Deserialize a Python object into Rust type `T: Deserialize` with options
+///
+/// ```
+/// use pyo3::Python;
+/// use serde_pyobject::{from_pyobject_with, pylist, PyObjectDeserializerOptions};
+///
+/// Python::with_gil(|py| {
+///     let list = pylist![py; "short", "too long"].unwrap();
+///     let options = PyObjectDeserializerOptions::new().max_str_len(5);
+///     let err = from_pyobject_with::<Vec<String>, _>(list, &options).unwrap_err();
+///     assert_eq!(err.to_string(), "ValueError: str of length 8 exceeds the limit 5");
+/// });
+/// ```
+pub fn from_pyobject_with<'py, 'de, T: Deserialize<'de>, Any>(
+    any: Bound<'py, Any>,
+    options: &PyObjectDeserializerOptions,
+) -> Result<T> {
     let any = any.into_any();
-    T::deserialize(PyAnyDeserializer(any))
+    let _scope = shared::IdentityScope::enter();
+    let ctx = Context::new(options);
+    T::deserialize(PyAnyDeserializer::with_context(any, &ctx)?)
+}
+
+/// Same as [`from_pyobject_with`], but an error is prefixed by the path to the value which failed
+pub(crate) fn from_pyobject_with_path<'py, 'de, T: Deserialize<'de>>(
+    any: Bound<'py, PyAny>,
+    options: &PyObjectDeserializerOptions,
+) -> Result<T> {
+    let py = any.py();
+    let _scope = shared::IdentityScope::enter();
+    let ctx = Context::new(options);
+    PyAnyDeserializer::with_context(any, &ctx)
+        .and_then(T::deserialize)
+        .map_err(|err| match ctx.error_path() {
+            Some(path) => err.context(py, path),
+            None => err,
+        })
+}
+
+/// State shared in a single deserialization
+pub(crate) struct Context {
+    options: PyObjectDeserializerOptions,
+    /// Number of elements of sequences and entries of dicts visited so far
+    elements: Cell<usize>,
+    /// Number of objects visited so far
+    nodes: Cell<usize>,
+    /// Number of containers enclosing the value being visited
+    depth: Cell<usize>,
+    /// Segments like `[0]` or `.field` pushed while an error goes up from the failed value
+    error_path: RefCell<Vec<String>>,
+}
+
+impl Context {
+    pub(crate) fn new(options: &PyObjectDeserializerOptions) -> Rc<Self> {
+        Rc::new(Context {
+            options: options.clone(),
+            elements: Cell::new(0),
+            nodes: Cell::new(0),
+            depth: Cell::new(0),
+            error_path: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Record `segment` of the path if `result` is an error
+    fn trace<T>(&self, result: Result<T>, segment: impl FnOnce() -> String) -> Result<T> {
+        if result.is_err() {
+            self.error_path.borrow_mut().push(segment());
+        }
+        result
+    }
+
+    /// Path to the value which failed like `$.a[0]['key']`, or `None` if it is the root
+    fn error_path(&self) -> Option<String> {
+        let segments = self.error_path.borrow();
+        if segments.is_empty() {
+            return None;
+        }
+        Some(
+            segments
+                .iter()
+                .rev()
+                .fold("$".to_string(), |path, s| path + s),
+        )
+    }
+
+    /// Count a visited object, and check Python signals periodically
+    fn count_node(&self, py: Python<'_>) -> Result<()> {
+        let nodes = self.nodes.get().wrapping_add(1);
+        self.nodes.set(nodes);
+        if nodes & (SIGNAL_CHECK_INTERVAL - 1) == 0 {
+            py.check_signals()?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` deserializing an element of a container one level deeper
+    fn nested<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let depth = self.depth.get() + 1;
+        if let Some(limit) = self.options.max_depth {
+            if depth > limit {
+                return Err(PyValueError::new_err(format!(
+                    "nesting depth exceeds the limit {}",
+                    limit
+                ))
+                .into());
+            }
+        }
+        self.depth.set(depth);
+        let out = f();
+        self.depth.set(depth - 1);
+        out
+    }
+
+    /// Count `n` elements of a container before visiting them
+    fn consume(&self, n: usize) -> Result<()> {
+        let total = self.elements.get().saturating_add(n);
+        self.elements.set(total);
+        match self.options.max_elements {
+            Some(limit) if total > limit => Err(PyValueError::new_err(format!(
+                "total number of elements exceeds the limit {}",
+                limit
+            ))
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// [`Deserializer`] over a Python object, which [`from_pyobject`] uses internally
+///
+/// This is a building block for manual [`Deserialize`] implementations
+/// which start from a Python object, e.g. within [`FromPyObject`].
+/// Drive it by any `deserialize_*` method of [`Deserializer`] with your own [`Visitor`],
+/// or take the contents of a sequence or a mapping
+/// by [`seq_access`](Self::seq_access) or [`map_access`](Self::map_access).
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde::de::{Deserializer, SeqAccess, Visitor};
+/// use serde_pyobject::{pylist, PyAnyDeserializer};
+/// use std::fmt;
+///
+/// /// Sum of a sequence of integers without collecting them
+/// struct Sum;
+///
+/// impl<'de> Visitor<'de> for Sum {
+///     type Value = i64;
+///     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("a sequence of integers")
+///     }
+///     fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<i64, A::Error> {
+///         let mut sum = 0;
+///         while let Some(x) = seq.next_element::<i64>()? {
+///             sum += x;
+///         }
+///         Ok(sum)
+///     }
+/// }
+///
+/// Python::with_gil(|py| {
+///     let list = pylist![py; 1, 2, 3].unwrap();
+///     let de = PyAnyDeserializer::new(list.clone()).unwrap();
+///     assert_eq!(de.deserialize_seq(Sum).unwrap(), 6);
+///
+///     let seq = PyAnyDeserializer::new(list).unwrap().seq_access().unwrap();
+///     assert_eq!(Sum.visit_seq(seq).unwrap(), 6);
+/// });
+/// ```
+pub struct PyAnyDeserializer<'py> {
+    obj: Bound<'py, PyAny>,
+    ctx: Rc<Context>,
+}
+
+impl<'py> PyAnyDeserializer<'py> {
+    /// Deserializer of `obj` with the default options
+    pub fn new<Any>(obj: Bound<'py, Any>) -> Result<Self> {
+        Self::with_options(obj, &PyObjectDeserializerOptions::default())
+    }
+
+    /// Deserializer of `obj` with `options`
+    pub fn with_options<Any>(
+        obj: Bound<'py, Any>,
+        options: &PyObjectDeserializerOptions,
+    ) -> Result<Self> {
+        Self::with_context(obj.into_any(), &Context::new(options))
+    }
+
+    /// The Python object to be deserialized
+    pub fn as_bound(&self) -> &Bound<'py, PyAny> {
+        &self.obj
+    }
+
+    /// Elements of `list`, `tuple`, other `collections.abc.Sequence`, or other objects with `__len__` and `__getitem__`
+    /// as [`SeqAccess`]
+    pub fn seq_access(self) -> Result<SeqDeserializer<'py>> {
+        if let Ok(list) = self.obj.downcast::<PyList>() {
+            return SeqDeserializer::from_list(list, &self.ctx);
+        }
+        if let Ok(tuple) = self.obj.downcast::<PyTuple>() {
+            return SeqDeserializer::from_tuple(tuple, &self.ctx);
+        }
+        if self.obj.downcast::<PySequence>().is_err() && is_sequence_like(&self.obj)? {
+            return SeqDeserializer::from_list(&to_list(&self.obj)?, &self.ctx);
+        }
+        let seq = self.obj.downcast::<PySequence>()?;
+        SeqDeserializer::from_list(&seq.to_list()?, &self.ctx)
+    }
+
+    /// Entries of `dict`, other `collections.abc.Mapping`, or other objects with `keys()` and `__getitem__`
+    /// as [`MapAccess`]
+    pub fn map_access(self) -> Result<MapDeserializer<'py>> {
+        if let Ok(dict) = self.obj.downcast::<PyDict>() {
+            return MapDeserializer::new(dict, &self.ctx);
+        }
+        if self.obj.downcast::<PyMapping>().is_err() && is_mapping_like(&self.obj)? {
+            return MapDeserializer::from_keys(&self.obj, &self.ctx);
+        }
+        MapDeserializer::from_mapping(self.obj.downcast()?, &self.ctx)
+    }
+
+    /// Deserializer of `obj`, or of its referent if `obj` is `weakref.ref` or `weakref.proxy`
+    pub(crate) fn with_context(obj: Bound<'py, PyAny>, ctx: &Rc<Context>) -> Result<Self> {
+        ctx.count_node(obj.py())?;
+        let obj = match obj.downcast::<PyWeakref>() {
+            Ok(weakref) => match weakref.upgrade() {
+                Some(referent) => referent,
+                None => {
+                    return Err(PyReferenceError::new_err(
+                        "weakly-referenced object no longer exists",
+                    )
+                    .into())
+                }
+            },
+            Err(_) => obj,
+        };
+        Ok(Self {
+            obj,
+            ctx: ctx.clone(),
+        })
+    }
+
+    /// Reject `str`, `bytes`, `bytearray` and `memoryview` longer than [`PyObjectDeserializerOptions::max_str_len`]
+    fn check_str_len(&self) -> Result<()> {
+        let Some(limit) = self.ctx.options.max_str_len else {
+            return Ok(());
+        };
+        let (kind, len) = if let Ok(s) = self.obj.downcast::<PyString>() {
+            ("str", s.len()?)
+        } else if let Ok(b) = self.obj.downcast::<PyBytes>() {
+            ("bytes", b.len()?)
+        } else if let Ok(b) = self.obj.downcast::<PyByteArray>() {
+            ("bytearray", b.len())
+        } else if self.obj.is_instance_of::<PyMemoryView>() {
+            let nbytes = self.obj.getattr(intern!(self.obj.py(), "nbytes"))?;
+            ("memoryview", nbytes.extract()?)
+        } else {
+            return Ok(());
+        };
+        if len > limit {
+            return Err(PyValueError::new_err(format!(
+                "{} of length {} exceeds the limit {}",
+                kind, len, limit
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// `dict` whose keys standing for fields by [`PyObjectDeserializerOptions::field_case`]
+    /// or [`PyObjectDeserializerOptions::keyword_fields`] are replaced by the field names
+    fn rust_fields(
+        &self,
+        dict: &Bound<'py, PyDict>,
+        fields: &'static [&'static str],
+    ) -> Result<Bound<'py, PyDict>> {
+        let options = &self.ctx.options;
+        if !options.renames_fields() {
+            return Ok(dict.clone());
+        }
+        let renamed = PyDict::new(dict.py());
+        for (key, value) in dict {
+            let field = match key.downcast::<PyString>() {
+                Ok(key) => {
+                    let key = key.to_cow()?;
+                    fields
+                        .iter()
+                        .find(|field| options.is_field_key(field, &key))
+                }
+                Err(_) => None,
+            };
+            match field {
+                Some(field) => renamed.set_item(*field, value)?,
+                None => renamed.set_item(key, value)?,
+            }
+        }
+        Ok(renamed)
+    }
+
+    /// Value of `{name: value}` if [`PyObjectDeserializerOptions::wrap_type_names`] is enabled
+    fn type_name_wrapped(&self, name: &str) -> Result<Option<Bound<'py, PyAny>>> {
+        if !self.ctx.options.wrap_type_names {
+            return Ok(None);
+        }
+        let Ok(dict) = self.obj.downcast::<PyDict>() else {
+            return Ok(None);
+        };
+        if dict.len() != 1 {
+            return Ok(None);
+        }
+        Ok(dict.get_item(name)?)
+    }
+
+    /// Reject keys of `dict` which are not fields of the struct
+    /// if [`UnknownKeyPolicy::Error`] is set
+    fn check_unknown_keys(
+        &self,
+        dict: &Bound<'py, PyDict>,
+        fields: &'static [&'static str],
+    ) -> Result<()> {
+        if self.ctx.options.unknown_keys == UnknownKeyPolicy::Ignore {
+            return Ok(());
+        }
+        for key in dict.keys() {
+            let known = match key.downcast::<PyString>() {
+                Ok(key) => fields.contains(&key.to_cow()?.as_ref()),
+                Err(_) => false,
+            };
+            if !known {
+                return Err(de::Error::unknown_field(&key.str()?.to_cow()?, fields));
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of `...` or `NotImplemented` if the object is one of them
+    fn singleton(&self) -> Option<&'static str> {
+        let py = self.obj.py();
+        if self.obj.is(&py.Ellipsis()) {
+            Some("Ellipsis")
+        } else if self.obj.is(&py.NotImplemented()) {
+            Some("NotImplemented")
+        } else {
+            None
+        }
+    }
+
+    /// Integer value of the object by `operator.index`, if it is `int` (except `bool`) or has `__index__`
+    fn index(&self) -> Result<Option<Bound<'py, PyInt>>> {
+        let obj = &self.obj;
+        if obj.is_instance_of::<PyBool>() || !obj.hasattr(intern!(obj.py(), "__index__"))? {
+            return Ok(None);
+        }
+        Ok(Some(index(obj)?))
+    }
+
+    /// Float value of the object by `float()`, if it is `float` or has `__float__` except `bool`
+    fn float(&self) -> Result<Option<f64>> {
+        let obj = &self.obj;
+        if obj.is_instance_of::<PyBool>() {
+            return Ok(None);
+        }
+        if obj.is_instance_of::<PyFloat>() {
+            return Ok(Some(obj.extract()?));
+        }
+        if obj.hasattr(intern!(obj.py(), "__float__"))? {
+            if !self.ctx.options.lossy_decimals && decimal::is_decimal(obj)? {
+                return Err(PyTypeError::new_err(format!(
+                    "{} is not converted into a float, see PyObjectDeserializerOptions::lossy_decimals",
+                    obj.repr()?
+                ))
+                .into());
+            }
+            // `PyFloat_AsDouble` calls `__float__`
+            return Ok(Some(obj.extract()?));
+        }
+        Ok(None)
+    }
+
+    /// Whether the object is `float` of `NaN` or an infinity
+    fn is_non_finite_float(&self) -> bool {
+        match self.obj.downcast::<PyFloat>() {
+            Ok(float) => !float.value().is_finite(),
+            Err(_) => false,
+        }
+    }
+
+    /// Visit a float following [`PyObjectDeserializerOptions::non_finite_floats`]
+    fn visit_float<'de, V: Visitor<'de>>(&self, v: f64, visitor: V) -> Result<V::Value> {
+        if v.is_finite() {
+            return visitor.visit_f64(v);
+        }
+        match self.ctx.options.non_finite_floats {
+            NonFinitePolicy::Allow => visitor.visit_f64(v),
+            NonFinitePolicy::Error => {
+                Err(PyValueError::new_err(format!("non-finite float {} is not allowed", v)).into())
+            }
+            NonFinitePolicy::None => visitor.visit_none(),
+        }
+    }
+
+    /// Variant of the enum `name` registered for the class of the object
+    fn registered_variant(&self, name: &str) -> Result<Option<&'static str>> {
+        let py = self.obj.py();
+        for entry in &self.ctx.options.class_variants {
+            if entry.enum_name == name && self.obj.is_instance(entry.class.bind(py))? {
+                return Ok(Some(entry.variant));
+            }
+        }
+        Ok(None)
+    }
+
+    /// `model_dump` of a pydantic model with [`PyObjectDeserializerOptions::pydantic_by_alias`] and others
+    #[cfg(feature = "pydantic_support")]
+    fn pydantic_dump(&self) -> Result<Option<Bound<'py, PyDict>>> {
+        let py = self.obj.py();
+        let options = &self.ctx.options;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "by_alias"), options.pydantic_by_alias)?;
+        kwargs.set_item(intern!(py, "exclude_unset"), options.pydantic_exclude_unset)?;
+        kwargs.set_item(
+            intern!(py, "exclude_defaults"),
+            options.pydantic_exclude_defaults,
+        )?;
+        convert::pydantic_dump(&self.obj, Some(&kwargs))
+    }
+
+    /// [`convert::object_to_dict`] of the object, or `None` if it cannot be converted
+    ///
+    /// Functions, classes and modules are not converted although they have `__dict__`.
+    fn object_dict(&self) -> Result<Option<Bound<'py, PyDict>>> {
+        if self.obj.is_callable() || self.obj.is_instance_of::<PyModule>() {
+            return Ok(None);
+        }
+        match convert::object_to_dict(&self.obj) {
+            Ok(dict) => Ok(Some(dict)),
+            // `TypeError` stands for objects which cannot be converted
+            Err(err) if err.0.is_instance_of::<PyTypeError>(self.obj.py()) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether the object is not one of the types which `deserialize_any` handles
+    fn is_plain_object(&self) -> Result<bool> {
+        let obj = &self.obj;
+        Ok(!(obj.is_instance_of::<PyDict>()
+            || obj.is_instance_of::<PyList>()
+            || obj.is_instance_of::<PyTuple>()
+            || obj.is_instance_of::<PySet>()
+            || obj.is_instance_of::<PyFrozenSet>()
+            || obj.is_instance_of::<PyString>()
+            || obj.is_instance_of::<PyInt>()
+            || obj.is_instance_of::<PyFloat>()
+            || obj.is_none()
+            || obj.is_instance_of::<PyComplex>()
+            || array::is_array(obj)?
+            || is_user_string(obj)?
+            || ipaddress::is_ipaddress(obj)?
+            || is_uuid(obj)?
+            || path::is_path_like(obj)?
+            || datetime::is_datetime_like(obj)?
+            || obj.downcast::<PyMapping>().is_ok()
+            || obj.downcast::<PySequence>().is_ok()
+            || is_mapping_like(obj)?
+            || is_sequence_like(obj)?))
+    }
 }
 
-struct PyAnyDeserializer<'py>(Bound<'py, PyAny>);
+/// Deserialize an integer from any object which has `__index__`
+macro_rules! deserialize_int {
+    ($f:ident) => {
+        fn $f<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            match self.index()? {
+                Some(int) => visit_int(&int, visitor),
+                None => self.deserialize_any(visitor),
+            }
+        }
+    };
+}
+
+/// Deserialize a float from any object which has `__index__` or `__float__`
+macro_rules! deserialize_float {
+    ($f:ident) => {
+        fn $f<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            if let Some(int) = self.index()? {
+                return visit_int(&int, visitor);
+            }
+            match self.float()? {
+                Some(float) => self.visit_float(float, visitor),
+                None => self.deserialize_any(visitor),
+            }
+        }
+    };
+}
 
 impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
     type Error = Error;
@@ -306,48 +890,265 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
     where
         V: Visitor<'de>,
     {
-        if self.0.is_instance_of::<PyDict>() {
-            return visitor.visit_map(MapDeserializer::new(self.0.downcast()?));
+        self.check_str_len()?;
+        if self.obj.is_instance_of::<PyDict>() {
+            return visitor.visit_map(MapDeserializer::new(self.obj.downcast()?, &self.ctx)?);
+        }
+        if self.obj.is_instance_of::<PyList>() {
+            return visitor.visit_seq(SeqDeserializer::from_list(self.obj.downcast()?, &self.ctx)?);
         }
-        if self.0.is_instance_of::<PyList>() {
-            return visitor.visit_seq(SeqDeserializer::from_list(self.0.downcast()?));
+        if self.obj.is_instance_of::<PyTuple>() {
+            return visitor.visit_seq(SeqDeserializer::from_tuple(
+                self.obj.downcast()?,
+                &self.ctx,
+            )?);
         }
-        if self.0.is_instance_of::<PyTuple>() {
-            return visitor.visit_seq(SeqDeserializer::from_tuple(self.0.downcast()?));
+        if self.obj.is_instance_of::<PySet>() || self.obj.is_instance_of::<PyFrozenSet>() {
+            let mut seq_reversed = self.obj.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+            self.ctx.consume(seq_reversed.len())?;
+            seq_reversed.reverse();
+            return visitor.visit_seq(SeqDeserializer {
+                seq_reversed,
+                index: Some(0),
+                ctx: self.ctx,
+            });
+        }
+        if self.obj.is_instance_of::<PyString>() {
+            return visitor.visit_str(self.obj.extract()?);
         }
-        if self.0.is_instance_of::<PyString>() {
-            return visitor.visit_str(self.0.extract()?);
+        if is_binary(&self.obj) {
+            return self.deserialize_bytes(visitor);
         }
-        if self.0.is_instance_of::<PyBool>() {
+        if self.obj.is_instance_of::<PyBool>() {
             // must be match before PyLong
-            return visitor.visit_bool(self.0.extract()?);
+            return visitor.visit_bool(self.obj.extract()?);
         }
-        if self.0.is_instance_of::<PyInt>() {
-            return visitor.visit_i64(self.0.extract()?);
+        if self.obj.is_instance_of::<PyInt>() {
+            return visit_int(self.obj.downcast()?, visitor);
         }
-        if self.0.is_instance_of::<PyFloat>() {
-            return visitor.visit_f64(self.0.extract()?);
+        if self.obj.is_instance_of::<PyFloat>() {
+            return self.visit_float(self.obj.extract()?, visitor);
         }
-        if self.0.is_none() {
+        if self.obj.is_none() {
             return visitor.visit_none();
         }
-        unreachable!("Unsupported type: {}", self.0.get_type());
+        if let Ok(complex) = self.obj.downcast::<PyComplex>() {
+            let parts = [complex.real(), complex.imag()];
+            let parts = parts.map(|part| PyFloat::new(complex.py(), part).into_any());
+            self.ctx.consume(2)?;
+            return visitor.visit_seq(SeqDeserializer {
+                seq_reversed: parts.into_iter().rev().collect(),
+                index: Some(0),
+                ctx: self.ctx,
+            });
+        }
+        if array::is_array(&self.obj)? {
+            self.ctx.consume(self.obj.len()?)?;
+            return array::visit_array(&self.obj, visitor);
+        }
+        // `collections.UserString` is not a subclass of `str`
+        if is_user_string(&self.obj)? {
+            return visitor.visit_string(self.obj.str()?.extract()?);
+        }
+        if ipaddress::is_ipaddress(&self.obj)? {
+            return visitor.visit_string(self.obj.str()?.extract()?);
+        }
+        // Canonical hyphenated string, which `uuid::Uuid` also parses
+        if is_uuid(&self.obj)? {
+            return visitor.visit_string(self.obj.str()?.extract()?);
+        }
+        if let Some(iso) = datetime::iso_string(&self.obj)? {
+            return visitor.visit_string(iso);
+        }
+        // `pathlib.Path` and other `os.PathLike` objects give `str`, or `bytes` for `bytes` paths
+        if path::is_path_like(&self.obj)? {
+            let fspath =
+                py_module_cache::get(self.obj.py(), "os", "fspath")?.call1((&self.obj,))?;
+            return PyAnyDeserializer::with_context(fspath, &self.ctx)?.deserialize_any(visitor);
+        }
+        // e.g. `numpy.bool_`, which is neither `bool` nor `int`
+        #[cfg(feature = "numpy")]
+        if crate::numpy::is_scalar(&self.obj)? {
+            let item = self.obj.call_method0(intern!(self.obj.py(), "item"))?;
+            return PyAnyDeserializer::with_context(item, &self.ctx)?.deserialize_any(visitor);
+        }
+        #[cfg(feature = "numpy")]
+        if crate::numpy::is_ndarray(&self.obj)? {
+            return match crate::numpy::layout(&self.obj)? {
+                crate::numpy::Layout::Scalar(item) => {
+                    PyAnyDeserializer::with_context(item, &self.ctx)?.deserialize_any(visitor)
+                }
+                crate::numpy::Layout::Numbers(format, len) => {
+                    self.ctx.consume(len)?;
+                    array::visit_numbers(&self.obj, &format, visitor)
+                }
+                crate::numpy::Layout::Elements(elements) => {
+                    self.ctx.consume(elements.len())?;
+                    let mut seq_reversed = elements;
+                    seq_reversed.reverse();
+                    visitor.visit_seq(SeqDeserializer {
+                        seq_reversed,
+                        index: Some(0),
+                        ctx: self.ctx,
+                    })
+                }
+            };
+        }
+        // Data frames as sequences of rows
+        #[cfg(any(feature = "pandas", feature = "polars", feature = "pyarrow"))]
+        if let Some(rows) = crate::dataframe::records(&self.obj)? {
+            return PyAnyDeserializer::with_context(rows, &self.ctx)?.deserialize_any(visitor);
+        }
+        #[cfg(any(feature = "pandas", feature = "polars"))]
+        if let Some(values) = crate::dataframe::series_values(&self.obj)? {
+            return PyAnyDeserializer::with_context(values, &self.ctx)?.deserialize_any(visitor);
+        }
+        #[cfg(feature = "msgspec")]
+        if let Some(builtins) = convert::msgspec_to_builtins(&self.obj)? {
+            return PyAnyDeserializer::with_context(builtins, &self.ctx)?.deserialize_any(visitor);
+        }
+        #[cfg(feature = "pydantic_support")]
+        if let Some(dict) = self.pydantic_dump()? {
+            return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+        }
+        // Other mappings and sequences, e.g. `collections.UserDict` or `collections.UserList`
+        if let Ok(mapping) = self.obj.downcast::<PyMapping>() {
+            return visitor.visit_map(MapDeserializer::from_mapping(mapping, &self.ctx)?);
+        }
+        if let Ok(seq) = self.obj.downcast::<PySequence>() {
+            return visitor.visit_seq(SeqDeserializer::from_list(&seq.to_list()?, &self.ctx)?);
+        }
+        // Objects with `keys()` and `__getitem__` which are not registered as `collections.abc.Mapping`
+        if is_mapping_like(&self.obj)? {
+            return visitor.visit_map(MapDeserializer::from_keys(&self.obj, &self.ctx)?);
+        }
+        // Objects with `__len__` and `__getitem__` which are not registered as `collections.abc.Sequence`
+        if is_sequence_like(&self.obj)? {
+            return visitor.visit_seq(SeqDeserializer::from_list(&to_list(&self.obj)?, &self.ctx)?);
+        }
+        // Scalars which are not subclasses of `int` or `float`, e.g. `numpy.int64` or `Decimal`
+        if let Some(int) = self.index()? {
+            return visit_int(&int, visitor);
+        }
+        if let Some(float) = self.float()? {
+            return self.visit_float(float, visitor);
+        }
+        if let Some(name) = self.singleton() {
+            return match self.ctx.options.singletons {
+                SingletonPolicy::Error => Err(de::Error::custom(format!(
+                    "{name} is not supported, see PyObjectDeserializerOptions::singletons"
+                ))),
+                SingletonPolicy::None => visitor.visit_none(),
+                SingletonPolicy::Marker => visitor.visit_str(name),
+            };
+        }
+        // Other iterables, e.g. generators or `dict.keys()`
+        if let Ok(iter) = self.obj.try_iter() {
+            let mut seq_reversed = iter.collect::<PyResult<Vec<_>>>()?;
+            self.ctx.consume(seq_reversed.len())?;
+            seq_reversed.reverse();
+            return visitor.visit_seq(SeqDeserializer {
+                seq_reversed,
+                index: Some(0),
+                ctx: self.ctx,
+            });
+        }
+        // Other objects by `__dict__` or `__slots__`
+        if let Some(dict) = self.object_dict()? {
+            return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+        }
+        Err(de::Error::custom(format!(
+            "unsupported type: {}",
+            self.obj.get_type().name()?
+        )))
     }
 
     fn deserialize_struct<V: de::Visitor<'de>>(
         self,
         name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
+        // `dict` of exactly the fields by the plan of a `Codec`
+        if let Some(plans) = &self.ctx.options.struct_plans {
+            if let Ok(dict) = self.obj.downcast_exact::<PyDict>() {
+                if !self.ctx.options.renames_fields() && !fields.contains(&name) {
+                    let plan = plans.get(self.obj.py(), name, fields);
+                    if let Some(planned) = PlannedFields::new(dict, fields, &plan, &self.ctx)? {
+                        return visitor.visit_map(planned);
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "pandas")]
+        if let Some(items) = crate::dataframe::series_items(&self.obj)? {
+            return PyAnyDeserializer::with_context(items, &self.ctx)?
+                .deserialize_struct(name, fields, visitor);
+        }
+        // `msgspec.Struct` by its fields, or positionally if it is array-like
+        #[cfg(feature = "msgspec")]
+        if let Some(builtins) = convert::msgspec_to_builtins(&self.obj)? {
+            return PyAnyDeserializer::with_context(builtins, &self.ctx)?
+                .deserialize_struct(name, fields, visitor);
+        }
+        // Pydantic models by `model_dump` with the options, rather than by their attributes
+        #[cfg(feature = "pydantic_support")]
+        if self.ctx.options.dumps_pydantic() {
+            if let Some(dict) = self.pydantic_dump()? {
+                return PyAnyDeserializer::with_context(dict.into_any(), &self.ctx)?
+                    .deserialize_struct(name, fields, visitor);
+            }
+        }
+        // `collections.namedtuple` by the names of fields rather than positionally
+        if let Some(dict) = named_tuple_dict(&self.obj)? {
+            return PyAnyDeserializer::with_context(dict.into_any(), &self.ctx)?
+                .deserialize_struct(name, fields, visitor);
+        }
         // Nested dict `{ "A": { "a": 1, "b": 2 } }` is deserialized as `A { a: 1, b: 2 }`
-        if self.0.is_instance_of::<PyDict>() {
-            let dict: &Bound<PyDict> = self.0.downcast()?;
+        if self.obj.is_instance_of::<PyDict>() {
+            let dict: &Bound<PyDict> = self.obj.downcast()?;
             if let Some(inner) = dict.get_item(name)? {
                 if let Ok(inner) = inner.downcast() {
-                    return visitor.visit_map(MapDeserializer::new(inner));
+                    let inner = self.rust_fields(inner, fields)?;
+                    self.check_unknown_keys(&inner, fields)?;
+                    return visitor.visit_map(MapDeserializer::new(&inner, &self.ctx)?);
                 }
             }
+            let dict = self.rust_fields(dict, fields)?;
+            self.check_unknown_keys(&dict, fields)?;
+            if self.ctx.options.renames_fields() {
+                return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+            }
+        }
+        // `numpy.ndarray` into a struct in the serde format of `ndarray::ArrayBase`
+        #[cfg(feature = "numpy")]
+        if fields == crate::numpy::NDARRAY_FIELDS && crate::numpy::is_ndarray(&self.obj)? {
+            let dict = crate::numpy::ndarray_struct(&self.obj)?;
+            return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+        }
+        // `range` gives its `start`, `stop` and `step` attributes rather than its elements
+        if is_range(&self.obj)? {
+            return visitor.visit_map(AttrDeserializer::new(self.obj, fields, &self.ctx)?);
+        }
+        // Sequences are deserialized positionally in the declared order of fields,
+        // and must have exactly one element for each field if the option requires
+        if self.ctx.options.exact_struct_length
+            && (self.obj.is_instance_of::<PyList>()
+                || self.obj.is_instance_of::<PyTuple>()
+                || ((self.obj.downcast::<PySequence>().is_ok()
+                    || (is_sequence_like(&self.obj)? && !is_mapping_like(&self.obj)?))
+                    && !self.obj.is_instance_of::<PyString>()
+                    && !is_user_string(&self.obj)?))
+        {
+            let len = self.obj.len()?;
+            if len != fields.len() {
+                let expected = format!("struct {} with {} elements", name, fields.len());
+                return Err(de::Error::invalid_length(len, &expected.as_str()));
+            }
+        }
+        // Other objects, e.g. class instances, provide the fields as attributes
+        if self.is_plain_object()? {
+            return visitor.visit_map(AttrDeserializer::new(self.obj, fields, &self.ctx)?);
         }
         // Default to `any` case
         self.deserialize_any(visitor)
@@ -355,16 +1156,95 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
 
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value> {
+        // Markers of `...` and `NotImplemented` accept only themselves, or the strings of `SingletonPolicy::Marker`
+        if name == ELLIPSIS_TOKEN || name == NOT_IMPLEMENTED_TOKEN {
+            return match self.singleton() {
+                Some(found) if name.ends_with(found) => visitor.visit_unit(),
+                _ => self.deserialize_any(visitor),
+            };
+        }
+        if name == shared::TOKEN {
+            return match shared::register(&self.obj) {
+                Some(id) => {
+                    let id = id.into_bound_py_any(self.obj.py())?;
+                    visitor.visit_seq(SeqDeserializer {
+                        seq_reversed: vec![self.obj, id],
+                        index: None,
+                        ctx: self.ctx,
+                    })
+                }
+                None => visitor.visit_newtype_struct(self),
+            };
+        }
+        if datetime::is_token(name) {
+            let obj = match datetime::from_py(name, &self.obj, self.ctx.options.naive_datetimes)? {
+                Some(payload) => payload,
+                None => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        if name == decimal::TOKEN {
+            let obj = match decimal::from_py(&self.obj)? {
+                Some(payload) => payload,
+                None => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        if name == fraction::TOKEN {
+            let obj = match fraction::from_py(&self.obj)? {
+                Some(payload) => payload,
+                None => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        if name == ipaddress::TOKEN {
+            let obj = match ipaddress::is_ipaddress(&self.obj)? {
+                true => self.obj.str()?.into_any(),
+                false => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        if name == path::TOKEN {
+            let obj = match path::from_py(&self.obj)? {
+                Some(payload) => payload,
+                None => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        if name == set::TOKEN {
+            if self.ctx.options.unique_sets {
+                set::check_unique(&self.obj)?;
+            }
+            return visitor.visit_newtype_struct(self);
+        }
+        #[cfg(feature = "uuid")]
+        if name == crate::uuid::TOKEN {
+            let obj = match crate::uuid::from_py(&self.obj)? {
+                Some(payload) => payload,
+                None => self.obj,
+            };
+            return visitor.visit_newtype_struct(PyAnyDeserializer { obj, ctx: self.ctx });
+        }
+        let obj = match self.type_name_wrapped(name)? {
+            Some(inner) => inner,
+            None => self.obj,
+        };
         visitor.visit_seq(SeqDeserializer {
-            seq_reversed: vec![self.0],
+            seq_reversed: vec![obj],
+            index: None,
+            ctx: self.ctx,
         })
     }
 
     fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.0.is_none() {
+        if self.obj.is_none()
+            || (self.ctx.options.singletons == SingletonPolicy::None && self.singleton().is_some())
+            || (self.ctx.options.non_finite_floats == NonFinitePolicy::None
+                && self.is_non_finite_float())
+        {
             visitor.visit_none()
         } else {
             visitor.visit_some(self)
@@ -372,7 +1252,7 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
     }
 
     fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        if self.0.is(&PyTuple::empty(self.0.py())) {
+        if self.obj.is(&PyTuple::empty(self.obj.py())) {
             visitor.visit_unit()
         } else {
             self.deserialize_any(visitor)
@@ -384,7 +1264,7 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
         _name: &'static str,
         visitor: V,
     ) -> Result<V::Value> {
-        if self.0.is(&PyTuple::empty(self.0.py())) {
+        if self.obj.is(&PyTuple::empty(self.obj.py())) {
             visitor.visit_unit()
         } else {
             self.deserialize_any(visitor)
@@ -393,29 +1273,61 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
 
     fn deserialize_enum<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
-        _variants: &'static [&'static str],
+        name: &'static str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        if self.0.is_instance_of::<PyString>() {
-            let variant = self.0.extract()?;
-            let py = self.0.py();
+        // Unit variant wrapped as `{"Enum": "Variant"}`, unless the enum has a variant of the same name
+        if !variants.contains(&name) {
+            if let Some(inner) = self.type_name_wrapped(name)? {
+                if inner.is_instance_of::<PyString>() {
+                    let de = PyAnyDeserializer::with_context(inner, &self.ctx)?;
+                    return de.deserialize_enum(name, variants, visitor);
+                }
+            }
+        }
+        // Instance of a class registered for a variant
+        if let Some(variant) = self.registered_variant(name)? {
+            return visitor.visit_enum(EnumDeserializer {
+                variant: PyString::new(self.obj.py(), variant).into_any(),
+                inner: self.obj,
+                ctx: self.ctx,
+            });
+        }
+        // Unit variant given by its name, or by its index
+        if is_variant(&self.obj) {
+            self.check_str_len()?;
+            let py = self.obj.py();
             let none = py.None().into_bound(py);
             return visitor.visit_enum(EnumDeserializer {
-                variant,
+                variant: self.obj,
                 inner: none,
+                ctx: self.ctx,
             });
         }
-        if self.0.is_instance_of::<PyDict>() {
-            let dict: &Bound<PyDict> = self.0.downcast()?;
+        // Member of `enum.Enum` given by its name
+        let py = self.obj.py();
+        if self
+            .obj
+            .is_instance(&py_module_cache::get(py, "enum", "Enum")?)?
+        {
+            let none = py.None().into_bound(py);
+            return visitor.visit_enum(EnumDeserializer {
+                variant: self.obj.getattr(intern!(py, "name"))?,
+                inner: none,
+                ctx: self.ctx,
+            });
+        }
+        if self.obj.is_instance_of::<PyDict>() {
+            let dict: &Bound<PyDict> = self.obj.downcast()?;
             if dict.len() == 1 {
-                let key = dict.keys().get_item(0).unwrap();
-                let value = dict.values().get_item(0).unwrap();
-                if key.is_instance_of::<PyString>() {
-                    let variant = key.extract()?;
+                let key = dict.keys().get_item(0)?;
+                let value = dict.values().get_item(0)?;
+                if is_variant(&key) {
                     return visitor.visit_enum(EnumDeserializer {
-                        variant,
+                        variant: key,
                         inner: value,
+                        ctx: self.ctx,
                     });
                 }
             }
@@ -423,92 +1335,376 @@ impl<'de> de::Deserializer<'de> for PyAnyDeserializer<'_> {
         self.deserialize_any(visitor)
     }
 
+    fn deserialize_identifier<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // Field names and variant names are compared as borrowed `&str`
+        // without creating owned `String`s.
+        // `visit_borrowed_str` is not possible since `'de` is not tied to the Python object,
+        // and the `&str` is valid only during this call.
+        if let Ok(s) = self.obj.downcast::<PyString>() {
+            self.check_str_len()?;
+            return visitor.visit_str(s.to_str()?);
+        }
+        self.deserialize_any(visitor)
+    }
+
     fn deserialize_tuple_struct<V: de::Visitor<'de>>(
         self,
         name: &'static str,
         _len: usize,
         visitor: V,
     ) -> Result<V::Value> {
-        if self.0.is_instance_of::<PyDict>() {
-            let dict: &Bound<PyDict> = self.0.downcast()?;
+        if self.obj.is_instance_of::<PyDict>() {
+            let dict: &Bound<PyDict> = self.obj.downcast()?;
             if let Some(value) = dict.get_item(name)? {
                 if value.is_instance_of::<PyTuple>() {
                     let tuple: &Bound<PyTuple> = value.downcast()?;
-                    return visitor.visit_seq(SeqDeserializer::from_tuple(tuple));
+                    return visitor.visit_seq(SeqDeserializer::from_tuple(tuple, &self.ctx)?);
                 }
             }
         }
         self.deserialize_any(visitor)
     }
 
+    deserialize_int!(deserialize_i8);
+    deserialize_int!(deserialize_i16);
+    deserialize_int!(deserialize_i32);
+    deserialize_int!(deserialize_i64);
+    deserialize_int!(deserialize_i128);
+    deserialize_int!(deserialize_u8);
+    deserialize_int!(deserialize_u16);
+    deserialize_int!(deserialize_u32);
+    deserialize_int!(deserialize_u64);
+    deserialize_int!(deserialize_u128);
+    deserialize_float!(deserialize_f32);
+    deserialize_float!(deserialize_f64);
+
+    // `bytes`, `bytearray` and `memoryview` for `serde_bytes::ByteBuf`, which are copied at once,
+    // while `list` of `int` is read as a sequence
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.check_str_len()?;
+        if let Ok(bytes) = self.obj.downcast::<PyBytes>() {
+            return visitor.visit_bytes(bytes.as_bytes());
+        }
+        if let Ok(bytes) = self.obj.downcast::<PyByteArray>() {
+            return visitor.visit_byte_buf(bytes.to_vec());
+        }
+        if self.obj.is_instance_of::<PyMemoryView>() {
+            let bytes = self.obj.call_method0(intern!(self.obj.py(), "tobytes"))?;
+            return visitor.visit_bytes(bytes.downcast::<PyBytes>()?.as_bytes());
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // Buffers of numbers, e.g. `bytes` for `Vec<u8>` or `memoryview` cast to `d` for `Vec<f64>`,
+        // are copied at once rather than element by element
+        if is_binary(&self.obj) || self.is_plain_object()? {
+            if let Some((format, len)) = array::number_buffer(&self.obj)? {
+                self.check_str_len()?;
+                self.ctx.consume(len)?;
+                return array::visit_numbers(&self.obj, &format, visitor);
+            }
+        }
+        // Other binary objects are sequences of `int`, while `deserialize_any` gives bytes
+        if is_binary(&self.obj) {
+            self.check_str_len()?;
+            let list = match self.obj.is_instance_of::<PyMemoryView>() {
+                // `tolist` nests the elements of multi-dimensional views
+                true => self
+                    .obj
+                    .call_method0(intern!(self.obj.py(), "tolist"))?
+                    .downcast_into::<PyList>()?,
+                false => self.obj.downcast::<PySequence>()?.to_list()?,
+            };
+            return visitor.visit_seq(SeqDeserializer::from_list(&list, &self.ctx)?);
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // `pandas.Series` from its index to its values, rather than its values
+        #[cfg(feature = "pandas")]
+        if let Some(items) = crate::dataframe::series_items(&self.obj)? {
+            return PyAnyDeserializer::with_context(items, &self.ctx)?.deserialize_any(visitor);
+        }
+        // `collections.namedtuple` from its field names to its values
+        if let Some(dict) = named_tuple_dict(&self.obj)? {
+            return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+        }
+        // Other objects by `convert::object_to_dict` rather than their elements, even if they are iterable
+        if self.is_plain_object()? {
+            if let Some(dict) = self.object_dict()? {
+                return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
     forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf seq tuple
-        map identifier ignored_any
+        bool char str string
+        ignored_any
+    }
+}
+
+/// `operator.index(obj)`
+pub(crate) fn index<'py>(obj: &Bound<'py, PyAny>) -> Result<Bound<'py, PyInt>> {
+    if let Ok(int) = obj.downcast::<PyInt>() {
+        return Ok(int.clone());
+    }
+    let index = py_module_cache::get(obj.py(), "operator", "index")?;
+    Ok(index.call1((obj,))?.downcast_into()?)
+}
+
+fn is_user_string(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    match py_module_cache::get_imported(obj.py(), "collections", "UserString")? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
+    }
+}
+
+/// Whether the class of `obj` has `keys` and `__getitem__`, as `dict(obj)` requires
+fn is_mapping_like(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let py = obj.py();
+    let class = obj.get_type();
+    Ok(class.hasattr(intern!(py, "keys"))? && class.hasattr(intern!(py, "__getitem__"))?)
+}
+
+/// Whether the class of `obj` has `__len__` and `__getitem__`, the old sequence protocol
+fn is_sequence_like(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let py = obj.py();
+    let class = obj.get_type();
+    Ok(class.hasattr(intern!(py, "__len__"))? && class.hasattr(intern!(py, "__getitem__"))?)
+}
+
+/// `list(obj)`, which iterates with `__getitem__` unless `obj` has `__iter__`
+fn to_list<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyList>> {
+    Ok(py_module_cache::get(obj.py(), "builtins", "list")?
+        .call1((obj,))?
+        .downcast_into()?)
+}
+
+/// `obj._asdict()` if `obj` is an instance of a class created by `collections.namedtuple` or `typing.NamedTuple`
+fn named_tuple_dict<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let py = obj.py();
+    if !obj.is_instance_of::<PyTuple>() {
+        return Ok(None);
+    }
+    let class = obj.get_type();
+    if !(class.hasattr(intern!(py, "_fields"))? && class.hasattr(intern!(py, "_asdict"))?) {
+        return Ok(None);
+    }
+    let dict = obj.call_method0(intern!(py, "_asdict"))?;
+    Ok(Some(dict.downcast_into()?))
+}
+
+fn is_binary(obj: &Bound<'_, PyAny>) -> bool {
+    obj.is_instance_of::<PyBytes>()
+        || obj.is_instance_of::<PyByteArray>()
+        || obj.is_instance_of::<PyMemoryView>()
+}
+
+fn is_range(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    obj.is_instance(&py_module_cache::get(obj.py(), "builtins", "range")?)
+}
+
+fn is_uuid(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    match py_module_cache::get_imported(obj.py(), "uuid", "UUID")? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
     }
 }
 
-struct SeqDeserializer<'py> {
+/// Visit an `int` by the smallest type which holds the value,
+/// so that the visitor reports overflow in the usual serde manner
+pub(crate) fn visit_int<'de, V: Visitor<'de>>(
+    int: &Bound<'_, PyInt>,
+    visitor: V,
+) -> Result<V::Value> {
+    if let Ok(v) = int.extract::<i64>() {
+        return visitor.visit_i64(v);
+    }
+    if let Ok(v) = int.extract::<u64>() {
+        return visitor.visit_u64(v);
+    }
+    if let Ok(v) = int.extract::<i128>() {
+        return visitor.visit_i128(v);
+    }
+    visitor.visit_u128(int.extract()?)
+}
+
+impl fmt::Debug for PyAnyDeserializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("PyAnyDeserializer").field(&self.obj).finish()
+    }
+}
+
+/// [`SeqAccess`] over the elements of a Python sequence, see [`PyAnyDeserializer::seq_access`]
+pub struct SeqDeserializer<'py> {
     seq_reversed: Vec<Bound<'py, PyAny>>,
+    /// Index of the next element, or `None` if the elements do not come from a Python sequence
+    index: Option<usize>,
+    ctx: Rc<Context>,
 }
 
 impl<'py> SeqDeserializer<'py> {
-    fn from_list(list: &Bound<'py, PyList>) -> Self {
+    fn from_list(list: &Bound<'py, PyList>, ctx: &Rc<Context>) -> Result<Self> {
+        ctx.consume(list.len())?;
         let mut seq_reversed = Vec::new();
         for item in list.iter().rev() {
             seq_reversed.push(item);
         }
-        Self { seq_reversed }
+        Ok(Self {
+            seq_reversed,
+            index: Some(0),
+            ctx: ctx.clone(),
+        })
     }
 
-    fn from_tuple(tuple: &Bound<'py, PyTuple>) -> Self {
+    fn from_tuple(tuple: &Bound<'py, PyTuple>, ctx: &Rc<Context>) -> Result<Self> {
+        ctx.consume(tuple.len())?;
         let mut seq_reversed = Vec::new();
         for item in tuple.iter().rev() {
             seq_reversed.push(item);
         }
-        Self { seq_reversed }
+        Ok(Self {
+            seq_reversed,
+            index: Some(0),
+            ctx: ctx.clone(),
+        })
     }
 }
 
 impl<'de> SeqAccess<'de> for SeqDeserializer<'_> {
     type Error = Error;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.seq_reversed.len())
+    }
+
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
     where
         T: de::DeserializeSeed<'de>,
     {
         self.seq_reversed.pop().map_or(Ok(None), |value| {
-            let value = seed.deserialize(PyAnyDeserializer(value))?;
+            let value = self.ctx.nested(|| {
+                PyAnyDeserializer::with_context(value, &self.ctx)
+                    .and_then(|de| seed.deserialize(de))
+            });
+            let value = match self.index {
+                Some(index) => {
+                    self.index = Some(index + 1);
+                    self.ctx.trace(value, || format!("[{}]", index))?
+                }
+                None => value?,
+            };
             Ok(Some(value))
         })
     }
 }
 
-struct MapDeserializer<'py> {
+impl fmt::Debug for SeqDeserializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SeqDeserializer")
+            .field("remaining", &self.seq_reversed.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`MapAccess`] over the entries of a Python mapping, see [`PyAnyDeserializer::map_access`]
+pub struct MapDeserializer<'py> {
     keys: Vec<Bound<'py, PyAny>>,
     values: Vec<Bound<'py, PyAny>>,
+    /// Key returned by the last `next_key_seed`
+    key: Option<Bound<'py, PyAny>>,
+    ctx: Rc<Context>,
 }
 
 impl<'py> MapDeserializer<'py> {
-    fn new(dict: &Bound<'py, PyDict>) -> Self {
+    fn new(dict: &Bound<'py, PyDict>, ctx: &Rc<Context>) -> Result<Self> {
+        ctx.consume(dict.len())?;
         let mut keys = Vec::new();
         let mut values = Vec::new();
         for (key, value) in dict.iter() {
             keys.push(key);
             values.push(value);
         }
-        Self { keys, values }
+        Ok(Self {
+            keys,
+            values,
+            key: None,
+            ctx: ctx.clone(),
+        })
+    }
+
+    fn from_mapping(mapping: &Bound<'py, PyMapping>, ctx: &Rc<Context>) -> Result<Self> {
+        let items = mapping.items()?;
+        ctx.consume(items.len())?;
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+        for item in items.iter() {
+            let (key, value) = item.extract()?;
+            keys.push(key);
+            values.push(value);
+        }
+        Ok(Self {
+            keys,
+            values,
+            key: None,
+            ctx: ctx.clone(),
+        })
+    }
+
+    /// Entries of `obj` from `keys()` and `__getitem__`
+    fn from_keys(obj: &Bound<'py, PyAny>, ctx: &Rc<Context>) -> Result<Self> {
+        let keys = obj
+            .call_method0(intern!(obj.py(), "keys"))?
+            .try_iter()?
+            .collect::<PyResult<Vec<_>>>()?;
+        ctx.consume(keys.len())?;
+        let values = keys
+            .iter()
+            .map(|key| obj.get_item(key))
+            .collect::<PyResult<_>>()?;
+        Ok(Self {
+            keys,
+            values,
+            key: None,
+            ctx: ctx.clone(),
+        })
+    }
+}
+
+impl fmt::Debug for MapDeserializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapDeserializer")
+            .field("remaining", &self.keys.len())
+            .finish_non_exhaustive()
     }
 }
 
 impl<'de> MapAccess<'de> for MapDeserializer<'_> {
     type Error = Error;
 
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
     where
         K: de::DeserializeSeed<'de>,
     {
         if let Some(key) = self.keys.pop() {
-            let key = seed.deserialize(PyAnyDeserializer(key))?;
+            self.key = Some(key.clone());
+            let key = PyAnyDeserializer::with_context(key, &self.ctx)?;
+            let key = seed.deserialize(MapKeyDeserializer(key))?;
             Ok(Some(key))
         } else {
             Ok(None)
@@ -520,18 +1716,247 @@ impl<'de> MapAccess<'de> for MapDeserializer<'_> {
         V: de::DeserializeSeed<'de>,
     {
         if let Some(value) = self.values.pop() {
-            let value = seed.deserialize(PyAnyDeserializer(value))?;
-            Ok(value)
+            let value = self.ctx.nested(|| {
+                PyAnyDeserializer::with_context(value, &self.ctx)
+                    .and_then(|de| seed.deserialize(de))
+            });
+            self.ctx
+                .trace(value, || match self.key.as_ref().map(|key| key.repr()) {
+                    Some(Ok(repr)) => format!("[{}]", repr),
+                    _ => "[?]".to_string(),
+                })
         } else {
             unreachable!()
         }
     }
 }
 
-// this lifetime is technically no longer 'py
+/// Fields of a struct read from `dict` by the keys planned by a [`Codec`](crate::Codec)
+struct PlannedFields<'py> {
+    /// Fields found in the `dict` and their values, in the reversed order
+    entries_reversed: Vec<(&'static str, Bound<'py, PyAny>)>,
+    /// Value of the field returned by the last `next_key_seed`
+    value: Option<Bound<'py, PyAny>>,
+    field: &'static str,
+    ctx: Rc<Context>,
+}
+
+impl<'py> PlannedFields<'py> {
+    /// Look up the keys of `plan` in `dict`, or `None` if `dict` has other keys
+    fn new(
+        dict: &Bound<'py, PyDict>,
+        fields: &'static [&'static str],
+        plan: &[Py<PyString>],
+        ctx: &Rc<Context>,
+    ) -> Result<Option<Self>> {
+        let py = dict.py();
+        let mut entries_reversed = Vec::with_capacity(fields.len());
+        for (field, key) in fields.iter().zip(plan).rev() {
+            if let Some(value) = dict.get_item(key.bind(py))? {
+                entries_reversed.push((*field, value));
+            }
+        }
+        // Other keys are left to the usual way, e.g. to be rejected by `#[serde(deny_unknown_fields)]`
+        if entries_reversed.len() != dict.len() {
+            return Ok(None);
+        }
+        ctx.consume(entries_reversed.len())?;
+        Ok(Some(Self {
+            entries_reversed,
+            value: None,
+            field: "",
+            ctx: ctx.clone(),
+        }))
+    }
+}
+
+impl<'de> MapAccess<'de> for PlannedFields<'_> {
+    type Error = Error;
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries_reversed.len())
+    }
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let Some((field, value)) = self.entries_reversed.pop() else {
+            return Ok(None);
+        };
+        self.field = field;
+        self.value = Some(value);
+        seed.deserialize(StrDeserializer::<Error>::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let Some(value) = self.value.take() else {
+            return Err(de::Error::custom(
+                "next_value_seed is called before next_key_seed",
+            ));
+        };
+        let value = self.ctx.nested(|| {
+            PyAnyDeserializer::with_context(value, &self.ctx).and_then(|de| seed.deserialize(de))
+        });
+        self.ctx.trace(value, || format!("['{}']", self.field))
+    }
+}
+
+/// Deserializer of a key of `dict`, which coerces keys for the target key type
+struct MapKeyDeserializer<'py>(PyAnyDeserializer<'py>);
+
+impl MapKeyDeserializer<'_> {
+    /// Stringify `bool`, `int`, `float` and `None` as `json.dumps` does,
+    /// if [`PyObjectDeserializerOptions::stringify_keys`] is enabled
+    fn stringify(&self) -> Result<Option<String>> {
+        let key = &self.0.obj;
+        if !self.0.ctx.options.stringify_keys
+            || !(key.is_instance_of::<PyInt>() || key.is_instance_of::<PyFloat>() || key.is_none())
+        {
+            return Ok(None);
+        }
+        let dumps = py_module_cache::get(key.py(), "json", "dumps")?;
+        Ok(Some(dumps.call1((key,))?.extract()?))
+    }
+}
+
+macro_rules! forward_to_inner {
+    ($($f:ident($($arg:ident: $t:ty),*))*) => {
+        $(
+            fn $f<V: Visitor<'de>>(self, $($arg: $t,)* visitor: V) -> Result<V::Value> {
+                self.0.$f($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for MapKeyDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.stringify()? {
+            Some(key) => visitor.visit_string(key),
+            None => self.0.deserialize_str(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.stringify()? {
+            Some(key) => visitor.visit_string(key),
+            None => self.0.deserialize_string(visitor),
+        }
+    }
+
+    forward_to_inner! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_i128() deserialize_u8()
+        deserialize_u16() deserialize_u32() deserialize_u64() deserialize_u128()
+        deserialize_f32() deserialize_f64() deserialize_char()
+        deserialize_bytes() deserialize_byte_buf() deserialize_option() deserialize_unit()
+        deserialize_seq() deserialize_map() deserialize_identifier()
+        deserialize_ignored_any()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+}
+
+/// Map of the attributes of an object, which are looked up by the field names of the target struct
+struct AttrDeserializer<'py> {
+    obj: Bound<'py, PyAny>,
+    fields: std::slice::Iter<'static, &'static str>,
+    /// Field returned by the last `next_key_seed` and its value
+    value: Option<(&'static str, Bound<'py, PyAny>)>,
+    ctx: Rc<Context>,
+}
+
+impl<'py> AttrDeserializer<'py> {
+    fn new(
+        obj: Bound<'py, PyAny>,
+        fields: &'static [&'static str],
+        ctx: &Rc<Context>,
+    ) -> Result<Self> {
+        ctx.consume(fields.len())?;
+        Ok(Self {
+            obj,
+            fields: fields.iter(),
+            value: None,
+            ctx: ctx.clone(),
+        })
+    }
+
+    /// Attribute of the field, or of its name by [`PyObjectDeserializerOptions::field_case`]
+    /// or [`PyObjectDeserializerOptions::keyword_fields`]
+    fn getattr(&self, field: &'static str) -> Result<Option<Bound<'py, PyAny>>> {
+        let py = self.obj.py();
+        let aliases = match self.ctx.options.renames_fields() {
+            true => self.ctx.options.field_aliases(field),
+            false => Vec::new(),
+        };
+        for name in std::iter::once(field).chain(aliases.iter().map(|alias| alias.as_ref())) {
+            match self.obj.getattr(name) {
+                Ok(value) => return Ok(Some(value)),
+                Err(err) if err.is_instance_of::<PyAttributeError>(py) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'de> MapAccess<'de> for AttrDeserializer<'_> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        while let Some(field) = self.fields.next() {
+            // Missing attributes are left to serde, which reports a missing field or uses the default
+            if let Some(value) = self.getattr(field)? {
+                self.value = Some((field, value));
+                return seed
+                    .deserialize(StrDeserializer::<Error>::new(field))
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let Some((field, value)) = self.value.take() else {
+            return Err(de::Error::custom(
+                "next_value_seed is called before next_key_seed",
+            ));
+        };
+        let value = self.ctx.nested(|| {
+            PyAnyDeserializer::with_context(value, &self.ctx).and_then(|de| seed.deserialize(de))
+        });
+        self.ctx.trace(value, || format!(".{}", field))
+    }
+}
+
+/// Whether the object can identify a variant, i.e. `str` of its name or `int` of its index
+fn is_variant(obj: &Bound<'_, PyAny>) -> bool {
+    obj.is_instance_of::<PyString>()
+        || (obj.is_instance_of::<PyInt>() && !obj.is_instance_of::<PyBool>())
+}
+
 struct EnumDeserializer<'py> {
-    variant: &'py str,
+    /// `str` or `int` which satisfies [`is_variant`]
+    variant: Bound<'py, PyAny>,
     inner: Bound<'py, PyAny>,
+    ctx: Rc<Context>,
 }
 
 impl<'de> de::EnumAccess<'de> for EnumDeserializer<'_> {
@@ -542,10 +1967,14 @@ impl<'de> de::EnumAccess<'de> for EnumDeserializer<'_> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        Ok((
-            seed.deserialize(StrDeserializer::<Error>::new(self.variant))?,
-            self,
-        ))
+        let variant = match self.variant.downcast::<PyString>() {
+            Ok(name) => seed.deserialize(StrDeserializer::<Error>::new(name.to_str()?))?,
+            Err(_) => {
+                let index: u32 = self.variant.extract()?;
+                seed.deserialize(U32Deserializer::<Error>::new(index))?
+            }
+        };
+        Ok((variant, self))
     }
 }
 
@@ -560,20 +1989,39 @@ impl<'de> de::VariantAccess<'de> for EnumDeserializer<'_> {
     where
         T: de::DeserializeSeed<'de>,
     {
-        seed.deserialize(PyAnyDeserializer(self.inner))
+        let value = self.ctx.nested(|| {
+            PyAnyDeserializer::with_context(self.inner, &self.ctx)
+                .and_then(|de| seed.deserialize(de))
+        });
+        self.ctx.trace(value, || format!(".{}", self.variant))
     }
 
     fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        PyAnyDeserializer(self.inner).deserialize_seq(visitor)
+        let value = self.ctx.nested(|| {
+            PyAnyDeserializer::with_context(self.inner, &self.ctx)
+                .and_then(|inner| inner.deserialize_seq(visitor))
+        });
+        self.ctx.trace(value, || format!(".{}", self.variant))
     }
 
-    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        PyAnyDeserializer(self.inner).deserialize_map(visitor)
+        let value = self.ctx.nested(|| {
+            let inner = PyAnyDeserializer::with_context(self.inner, &self.ctx)?;
+            if inner.is_plain_object()? {
+                return visitor.visit_map(AttrDeserializer::new(inner.obj, fields, &self.ctx)?);
+            }
+            if let Ok(dict) = inner.obj.downcast::<PyDict>() {
+                let dict = inner.rust_fields(dict, fields)?;
+                return visitor.visit_map(MapDeserializer::new(&dict, &self.ctx)?);
+            }
+            inner.deserialize_map(visitor)
+        });
+        self.ctx.trace(value, || format!(".{}", self.variant))
     }
 }
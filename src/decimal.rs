@@ -0,0 +1,185 @@
+//! Python [`decimal.Decimal`](https://docs.python.org/3/library/decimal.html) support
+//!
+//! Decimal types of Rust crates serialize themselves as strings or floats.
+//! Use this module with `#[serde(with = "serde_pyobject::decimal")]` on a field
+//! to serialize it into a `decimal.Decimal` object without losing digits,
+//! and to deserialize it back from a `decimal.Decimal` object, its string, or an `int` or `float`.
+//! Other serde formats see the decimal string.
+//!
+//! The types are enabled by the features of the crates:
+//!
+//! | Feature        | Rust type                |
+//! |----------------|--------------------------|
+//! | `rust_decimal` | `rust_decimal::Decimal`  |
+//! | `bigdecimal`   | `bigdecimal::BigDecimal` |
+//!
+//! ```
+//! # #[cfg(feature = "rust_decimal")] {
+//! use rust_decimal::Decimal;
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Price {
+//!     #[serde(with = "serde_pyobject::decimal")]
+//!     amount: Decimal,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let price = Price { amount: Decimal::new(1050, 2) };
+//!     let obj = to_pyobject(py, &price).unwrap();
+//!     assert_eq!(obj.repr().unwrap().to_string(), "{'amount': Decimal('10.50')}");
+//!
+//!     let reverted: Price = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, price);
+//! });
+//! # }
+//! ```
+
+use crate::{error::Result, py_module_cache};
+use pyo3::{
+    prelude::*,
+    types::{PyBool, PyInt},
+};
+use serde::{de, Deserializer, Serializer};
+use std::{fmt, marker::PhantomData};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is the decimal string
+pub(crate) const TOKEN: &str = "$serde_pyobject::decimal";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Decimal types which this module converts into `decimal.Decimal`
+pub trait DecimalNumber: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    fn to_decimal_string(&self) -> String;
+    #[doc(hidden)]
+    fn from_decimal_str(s: &str) -> std::result::Result<Self, String>;
+}
+
+/// Serialize a decimal number as `decimal.Decimal`
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: DecimalNumber,
+{
+    serializer.serialize_newtype_struct(TOKEN, &value.to_decimal_string())
+}
+
+/// Deserialize a decimal number from `decimal.Decimal`, its string, `int` or `float`
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DecimalNumber,
+{
+    deserializer.deserialize_newtype_struct(TOKEN, DecimalVisitor(PhantomData))
+}
+
+struct DecimalVisitor<T>(PhantomData<T>);
+
+impl<'de, T: DecimalNumber> de::Visitor<'de> for DecimalVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<T, E> {
+        T::from_decimal_str(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<T, E> {
+        // The shortest digits which round-trip, as `repr(float)` in Python
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<T, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// Create `decimal.Decimal` from the serialized decimal string
+pub(crate) fn to_py<'py>(payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let class = py_module_cache::get(payload.py(), "decimal", "Decimal")?;
+    Ok(class.call1((payload,))?)
+}
+
+/// Whether `obj` is `decimal.Decimal`
+pub(crate) fn is_decimal(obj: &Bound<'_, PyAny>) -> Result<bool> {
+    let class = py_module_cache::get(obj.py(), "decimal", "Decimal")?;
+    Ok(obj.is_instance(&class)?)
+}
+
+/// String of `obj` if it is `decimal.Decimal` or `int`, which may exceed 128 bits
+pub(crate) fn from_py<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    let class = py_module_cache::get(obj.py(), "decimal", "Decimal")?;
+    let is_int = obj.is_instance_of::<PyInt>() && !obj.is_instance_of::<PyBool>();
+    if !is_int && !obj.is_instance(&class)? {
+        return Ok(None);
+    }
+    Ok(Some(obj.str()?.into_any()))
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_impl {
+    use super::{sealed::Sealed, DecimalNumber};
+    use rust_decimal::Decimal;
+
+    impl Sealed for Decimal {}
+
+    impl DecimalNumber for Decimal {
+        fn to_decimal_string(&self) -> String {
+            self.to_string()
+        }
+
+        fn from_decimal_str(s: &str) -> Result<Self, String> {
+            // `str(Decimal)` uses the exponent notation for large or small exponents, e.g. `1E-7`
+            let decimal = match s.contains(['e', 'E']) {
+                true => Decimal::from_scientific(s),
+                false => Decimal::from_str_exact(s),
+            };
+            decimal.map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal_impl {
+    use super::{sealed::Sealed, DecimalNumber};
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
+
+    impl Sealed for BigDecimal {}
+
+    impl DecimalNumber for BigDecimal {
+        fn to_decimal_string(&self) -> String {
+            self.to_string()
+        }
+
+        fn from_decimal_str(s: &str) -> Result<Self, String> {
+            BigDecimal::from_str(s).map_err(|e| e.to_string())
+        }
+    }
+}
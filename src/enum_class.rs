@@ -0,0 +1,108 @@
+//! Python `enum.Enum` classes generated for Rust enums
+
+use crate::{error::Result, py_module_cache};
+use pyo3::{exceptions::PyTypeError, prelude::*, types::*};
+use serde::de::{self, value, DeserializeOwned, Deserializer, Visitor};
+use std::{any::type_name, collections::HashMap, sync::Mutex};
+
+/// Name and variants of a Rust enum -> `enum.Enum` class of them
+type Classes = HashMap<(&'static str, &'static [&'static str]), Py<PyType>>;
+
+static CLASSES: Mutex<Option<Classes>> = Mutex::new(None);
+
+/// Name and variants of a Rust enum registered by
+/// [`PyObjectSerializerOptions::python_enum`](crate::PyObjectSerializerOptions::python_enum)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PythonEnum {
+    pub(crate) name: &'static str,
+    pub(crate) variants: &'static [&'static str],
+}
+
+impl PythonEnum {
+    /// Trace the name and variants of `T` through its `Deserialize`
+    ///
+    /// Fails with a `TypeError` if `T` is not deserialized as an enum.
+    pub(crate) fn of<T: DeserializeOwned>() -> Result<Self> {
+        let mut traced = None;
+        let _ = T::deserialize(EnumTracer(&mut traced));
+        match traced {
+            Some((name, variants)) => Ok(PythonEnum { name, variants }),
+            None => {
+                Err(PyTypeError::new_err(format!("{} is not an enum", type_name::<T>())).into())
+            }
+        }
+    }
+
+    /// Member of the `enum.Enum` class for `variant`, creating the class at the first call
+    ///
+    /// Members have the variant names as their names and values.
+    /// The class is kept until [`clear_caches`](crate::clear_caches) is called.
+    pub(crate) fn member<'py>(&self, py: Python<'py>, variant: &str) -> Result<Bound<'py, PyAny>> {
+        Ok(self.class(py)?.get_item(variant)?)
+    }
+
+    fn class<'py>(&self, py: Python<'py>) -> Result<Bound<'py, PyType>> {
+        let key = (self.name, self.variants);
+        if let Some(class) = lookup(py, key) {
+            return Ok(class);
+        }
+        let members = PyList::new(py, self.variants.iter().map(|v| (*v, *v)))?;
+        let class = py_module_cache::get(py, "enum", "Enum")?
+            .call1((self.name, members))?
+            .downcast_into::<PyType>()?;
+
+        // Another thread may have created the class meanwhile, and the first one wins
+        let mut classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+        let class = classes
+            .get_or_insert_with(HashMap::new)
+            .entry(key)
+            .or_insert_with(|| class.unbind());
+        Ok(class.bind(py).clone())
+    }
+}
+
+/// Drop the classes created by [`PythonEnum::member`]
+pub(crate) fn clear() {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    // Release Python references outside of the lock
+    drop(classes);
+}
+
+fn lookup<'py>(
+    py: Python<'py>,
+    key: (&'static str, &'static [&'static str]),
+) -> Option<Bound<'py, PyType>> {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+    let class = classes.as_ref()?.get(&key)?;
+    Some(class.bind(py).clone())
+}
+
+/// Deserializer which records the name and variants of the enum and aborts
+struct EnumTracer<'a>(&'a mut Option<(&'static str, &'static [&'static str])>);
+
+impl<'de> Deserializer<'de> for EnumTracer<'_> {
+    type Error = value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> std::result::Result<V::Value, value::Error> {
+        Err(de::Error::custom("not an enum"))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> std::result::Result<V::Value, value::Error> {
+        *self.0 = Some((name, variants));
+        Err(de::Error::custom("traced"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -1,4 +1,7 @@
-use pyo3::{exceptions::PyRuntimeError, DowncastError, PyErr};
+use pyo3::{
+    exceptions::PyRuntimeError, types::PyAnyMethods, DowncastError, DowncastIntoError, PyErr,
+    Python,
+};
 use serde::{de, ser};
 use std::fmt::{self, Display};
 
@@ -6,6 +9,19 @@ use std::fmt::{self, Display};
 #[derive(Debug)]
 pub struct Error(pub PyErr);
 
+impl Error {
+    /// Prepend `context` to the error message, keeping the Python exception type
+    pub(crate) fn context(self, py: Python<'_>, context: impl Display) -> Self {
+        let message = match self.0.value(py).str() {
+            Ok(message) => format!("{}: {}", context, message),
+            Err(_) => context.to_string(),
+        };
+        let err = PyErr::from_type(self.0.get_type(py), message);
+        err.set_cause(py, Some(self.0));
+        Error(err)
+    }
+}
+
 impl From<PyErr> for Error {
     fn from(err: PyErr) -> Self {
         Error(err)
@@ -19,6 +35,13 @@ impl From<DowncastError<'_, '_>> for Error {
     }
 }
 
+impl From<DowncastIntoError<'_>> for Error {
+    fn from(err: DowncastIntoError) -> Self {
+        let err: PyErr = err.into();
+        Error(err)
+    }
+}
+
 impl From<Error> for PyErr {
     fn from(err: Error) -> Self {
         err.0
@@ -0,0 +1,171 @@
+//! Python [`fractions.Fraction`](https://docs.python.org/3/library/fractions.html) support
+//!
+//! Use this module with `#[serde(with = "serde_pyobject::fraction")]` on a `num_rational::Ratio` field
+//! of a primitive integer type, e.g. `Rational64`, to serialize it into an exact `fractions.Fraction` object,
+//! and to deserialize it back from a `Fraction`, an `int`, a `(numerator, denominator)` tuple or a string like `"3/4"`.
+//! Other serde formats see the `(numerator, denominator)` tuple.
+//! This module is enabled by the `num-rational` feature.
+//!
+//! ```
+//! # #[cfg(feature = "num-rational")] {
+//! use num_rational::Rational64;
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Recipe {
+//!     #[serde(with = "serde_pyobject::fraction")]
+//!     cups: Rational64,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let recipe = Recipe { cups: Rational64::new(6, 8) };
+//!     let obj = to_pyobject(py, &recipe).unwrap();
+//!     assert_eq!(obj.repr().unwrap().to_string(), "{'cups': Fraction(3, 4)}");
+//!
+//!     let reverted: Recipe = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, recipe);
+//! });
+//! # }
+//! ```
+
+use crate::{error::Result, py_module_cache};
+use pyo3::{
+    prelude::*,
+    types::{PyBool, PyInt},
+    IntoPyObjectExt,
+};
+use serde::{de, de::DeserializeOwned, Deserializer, Serialize, Serializer};
+use std::{fmt, marker::PhantomData};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is `(numerator, denominator)`
+pub(crate) const TOKEN: &str = "$serde_pyobject::fraction";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Rational number types which this module converts into `fractions.Fraction`
+pub trait RationalNumber: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    type Integer: Serialize + DeserializeOwned;
+    #[doc(hidden)]
+    fn to_parts(&self) -> (&Self::Integer, &Self::Integer);
+    #[doc(hidden)]
+    fn from_parts(
+        numerator: Self::Integer,
+        denominator: Self::Integer,
+    ) -> std::result::Result<Self, String>;
+    #[doc(hidden)]
+    fn from_ratio_str(s: &str) -> std::result::Result<Self, String>;
+}
+
+/// Serialize a rational number as `fractions.Fraction`
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: RationalNumber,
+{
+    serializer.serialize_newtype_struct(TOKEN, &value.to_parts())
+}
+
+/// Deserialize a rational number from `fractions.Fraction`, `int`, `(numerator, denominator)` or its string
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: RationalNumber,
+{
+    deserializer.deserialize_newtype_struct(TOKEN, RationalVisitor(PhantomData))
+}
+
+struct RationalVisitor<T>(PhantomData<T>);
+
+impl<'de, T: RationalNumber> de::Visitor<'de> for RationalVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a rational number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<T, E> {
+        T::from_ratio_str(v).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<T, E> {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<T, A::Error> {
+        let numerator = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let denominator = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        T::from_parts(numerator, denominator).map_err(de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<T, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+#[cfg(feature = "num-rational")]
+mod num_rational_impl {
+    use super::{sealed::Sealed, RationalNumber};
+    use num_rational::Ratio;
+
+    macro_rules! impl_ratio {
+        ($($t:ty),*) => {$(
+            impl Sealed for Ratio<$t> {}
+
+            impl RationalNumber for Ratio<$t> {
+                type Integer = $t;
+
+                fn to_parts(&self) -> (&$t, &$t) {
+                    (self.numer(), self.denom())
+                }
+
+                fn from_parts(numerator: $t, denominator: $t) -> Result<Self, String> {
+                    if denominator == 0 {
+                        return Err("denominator is zero".to_string());
+                    }
+                    Ok(Ratio::new(numerator, denominator))
+                }
+
+                fn from_ratio_str(s: &str) -> Result<Self, String> {
+                    s.trim().parse().map_err(|e: num_rational::ParseRatioError| e.to_string())
+                }
+            }
+        )*};
+    }
+
+    impl_ratio!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+}
+
+/// Create `fractions.Fraction` from the serialized `(numerator, denominator)`
+pub(crate) fn to_py<'py>(payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let fraction = py_module_cache::get(payload.py(), "fractions", "Fraction")?;
+    Ok(fraction.call1((payload.get_item(0)?, payload.get_item(1)?))?)
+}
+
+/// `(numerator, denominator)` of `obj` if it is `fractions.Fraction` or `int`
+pub(crate) fn from_py<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    if obj.is_instance_of::<PyInt>() && !obj.is_instance_of::<PyBool>() {
+        return Ok(Some((obj, 1).into_bound_py_any(py)?));
+    }
+    if !obj.is_instance(&py_module_cache::get(py, "fractions", "Fraction")?)? {
+        return Ok(None);
+    }
+    let parts = (obj.getattr("numerator")?, obj.getattr("denominator")?);
+    Ok(Some(parts.into_bound_py_any(py)?))
+}
@@ -0,0 +1,257 @@
+//! Python [`ipaddress`](https://docs.python.org/3/library/ipaddress.html) support
+//!
+//! `std::net` types serialize themselves as strings.
+//! Use this module with `#[serde(with = "serde_pyobject::ipaddress")]` on a field
+//! to serialize `IpAddr`, `Ipv4Addr` or `Ipv6Addr` into an `ipaddress.IPv4Address` or `ipaddress.IPv6Address` object,
+//! and `SocketAddr`, `SocketAddrV4` or `SocketAddrV6` into a tuple `(address, port)` as the `socket` module uses.
+//! They are deserialized back from such objects or the strings of the addresses.
+//! Other serde formats see the strings of the addresses.
+//! Use [`option`] for `Option<T>` fields.
+//!
+//! Objects of the `ipaddress` module are also accepted as their strings by [`from_pyobject`](crate::from_pyobject),
+//! e.g. for `IpAddr` fields without this module.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//! use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Peer {
+//!     #[serde(with = "serde_pyobject::ipaddress")]
+//!     ip: IpAddr,
+//!     #[serde(with = "serde_pyobject::ipaddress")]
+//!     listen: SocketAddr,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let peer = Peer {
+//!         ip: IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+//!         listen: "[::1]:8080".parse().unwrap(),
+//!     };
+//!     let obj = to_pyobject(py, &peer).unwrap();
+//!     assert_eq!(
+//!         obj.repr().unwrap().to_string(),
+//!         "{'ip': IPv4Address('192.168.0.1'), 'listen': (IPv6Address('::1'), 8080)}"
+//!     );
+//!
+//!     let reverted: Peer = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, peer);
+//! });
+//! ```
+
+use crate::{error::Result, py_module_cache};
+use pyo3::prelude::*;
+use serde::{de, ser::SerializeTuple, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt,
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    str::FromStr,
+};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is the string of the address
+pub(crate) const TOKEN: &str = "$serde_pyobject::ipaddress";
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Address types which this module converts into `ipaddress` objects
+pub trait NetAddress: sealed::Sealed + Sized {
+    #[doc(hidden)]
+    fn serialize_address<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>;
+    #[doc(hidden)]
+    fn deserialize_address<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error>;
+}
+
+/// Serialize an IP address as an `ipaddress` object, or a socket address as `(address, port)`
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: NetAddress,
+{
+    value.serialize_address(serializer)
+}
+
+/// Deserialize an IP address from an `ipaddress` object or its string, or a socket address from `(address, port)`
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: NetAddress,
+{
+    T::deserialize_address(deserializer)
+}
+
+/// `#[serde(with = "serde_pyobject::ipaddress::option")]` for `Option<T>` fields, where `None` is `None`
+pub mod option {
+    use super::*;
+
+    /// Serialize an optional address as in [`serialize`](super::serialize) or `None`
+    pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: NetAddress,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(&Address(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize an optional address as in [`deserialize`](super::deserialize) or from `None`
+    pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: NetAddress,
+    {
+        let value: Option<Owned<T>> = Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+}
+
+/// Serialize through [`NetAddress`]
+struct Address<'a, T>(&'a T);
+
+impl<T: NetAddress> Serialize for Address<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.0.serialize_address(serializer)
+    }
+}
+
+/// Deserialize through [`NetAddress`]
+struct Owned<T>(T);
+
+impl<'de, T: NetAddress> Deserialize<'de> for Owned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        T::deserialize_address(deserializer).map(Owned)
+    }
+}
+
+/// Parses the string of an IP address in the newtype struct
+struct IpVisitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for IpVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an IP address")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<T, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<T, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+macro_rules! impl_ip {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl NetAddress for $t {
+            fn serialize_address<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_newtype_struct(TOKEN, &self.to_string())
+            }
+
+            fn deserialize_address<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                deserializer.deserialize_newtype_struct(TOKEN, IpVisitor(PhantomData))
+            }
+        }
+    )*};
+}
+
+impl_ip!(IpAddr, Ipv4Addr, Ipv6Addr);
+
+/// Accepts `(address, port)`
+struct SocketVisitor<A>(PhantomData<A>);
+
+impl<'de, A: NetAddress> de::Visitor<'de> for SocketVisitor<A> {
+    type Value = (A, u16);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a tuple of an IP address and a port")
+    }
+
+    fn visit_seq<S: de::SeqAccess<'de>>(
+        self,
+        mut seq: S,
+    ) -> std::result::Result<(A, u16), S::Error> {
+        let Owned(ip) = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let port = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok((ip, port))
+    }
+}
+
+macro_rules! impl_socket {
+    ($($t:ty => $ip:ty, $new:expr;)*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl NetAddress for $t {
+            fn serialize_address<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                let mut tuple = serializer.serialize_tuple(2)?;
+                let ip: $ip = self.ip().to_owned();
+                tuple.serialize_element(&Address(&ip))?;
+                tuple.serialize_element(&self.port())?;
+                tuple.end()
+            }
+
+            fn deserialize_address<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                let (ip, port): ($ip, u16) =
+                    deserializer.deserialize_tuple(2, SocketVisitor(PhantomData))?;
+                Ok($new(ip, port))
+            }
+        }
+    )*};
+}
+
+impl_socket! {
+    SocketAddr => IpAddr, SocketAddr::new;
+    SocketAddrV4 => Ipv4Addr, SocketAddrV4::new;
+    // Python has no flow information and scope ID in the tuple
+    SocketAddrV6 => Ipv6Addr, |ip, port| SocketAddrV6::new(ip, port, 0, 0);
+}
+
+/// Create an `ipaddress` object from the serialized string of the address
+pub(crate) fn to_py<'py>(payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let ip_address = py_module_cache::get(payload.py(), "ipaddress", "ip_address")?;
+    Ok(ip_address.call1((payload,))?)
+}
+
+/// Whether `obj` is an object of the `ipaddress` module, e.g. `IPv4Address` or `IPv6Network`
+pub(crate) fn is_ipaddress(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    // `_IPAddressBase` is the common base of the addresses, interfaces and networks
+    match py_module_cache::get_imported(obj.py(), "ipaddress", "_IPAddressBase")? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
+    }
+}
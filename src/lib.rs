@@ -4,17 +4,95 @@
 //! to Python objects.
 //!
 
+mod codec;
+mod columns;
+#[cfg(feature = "dataclass_support")]
+mod dataclass;
+#[cfg(any(feature = "pandas", feature = "polars", feature = "pyarrow"))]
+mod dataframe;
 mod de;
+mod enum_class;
 mod error;
+#[cfg(feature = "numpy")]
+mod numpy;
+mod options;
+mod py_module_cache;
 mod pylit;
+mod scratch;
 mod ser;
+mod singleton;
+// Pyodide has no threads to run an event loop in the background
+#[cfg(all(feature = "async", not(target_os = "emscripten")))]
+mod stream;
+mod struct_class;
+#[cfg(any(feature = "json", feature = "rmp", feature = "cbor", feature = "bson"))]
+mod transcode;
+mod typed;
+mod visitor;
+
+pub mod array;
+pub mod complex;
+pub mod convert;
+pub mod datetime;
+pub mod decimal;
+pub mod fraction;
+pub mod ipaddress;
+pub mod path;
+pub mod set;
+pub mod shared;
+#[cfg(feature = "uuid")]
+pub mod uuid;
+pub mod value;
+
+/// Number of converted nodes between [`Python::check_signals`](pyo3::Python::check_signals) calls,
+/// so that Ctrl-C interrupts a long conversion
+///
+/// This is a power of two, so that the counters are checked with a mask
+/// instead of `usize::is_multiple_of`, which requires Rust 1.87.
+const SIGNAL_CHECK_INTERVAL: usize = 1024;
+const _: () = assert!(SIGNAL_CHECK_INTERVAL.is_power_of_two());
+
+/// Prefix of the names of newtype and unit structs which the serializer and the deserializer recognize,
+/// e.g. for [`array`] and [`set`]
+const TOKEN_NAMESPACE: &str = "$serde_pyobject::";
 
 /// Re-export of `pyo3` crate.
 pub use pyo3;
 
-pub use de::from_pyobject;
+pub use codec::Codec;
+pub use columns::{from_columns, to_columns, to_columns_with};
+#[cfg(feature = "dataclass_support")]
+pub use dataclass::create_dataclass;
+#[cfg(any(feature = "pandas", feature = "polars", feature = "pyarrow"))]
+pub use dataframe::from_dataframe;
+pub use de::{
+    from_pyobject, from_pyobject_with, MapDeserializer, PyAnyDeserializer, SeqDeserializer,
+};
 pub use error::Error;
-pub use ser::to_pyobject;
+#[cfg(feature = "erased-serde")]
+pub use options::TypeAdapterFn;
+pub use options::{
+    BytesRepr, DuplicateKeyPolicy, FieldCase, FieldOverrideFn, MapKeyEncoder, MapKeyFn,
+    NaiveDateTimePolicy, NonFinitePolicy, PostHookFn, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions, SingletonPolicy, SortKeys, StructOutput, UnknownKeyPolicy,
+};
+pub use py_module_cache::clear_caches;
+#[cfg(feature = "pydantic_support")]
+pub use ser::to_pydantic;
+pub use ser::{to_py_instance, to_pyobject, to_pyobject_with};
+pub use singleton::{Ellipsis, NotImplemented};
+#[cfg(all(feature = "async", not(target_os = "emscripten")))]
+pub use stream::TypedStream;
+#[cfg(feature = "json")]
+pub use transcode::json_reader_to_pyobject;
+#[cfg(feature = "bson")]
+pub use transcode::{bson_to_pyobject, pyobject_to_bson};
+#[cfg(feature = "cbor")]
+pub use transcode::{cbor_to_pyobject, pyobject_to_cbor};
+#[cfg(feature = "rmp")]
+pub use transcode::{msgpack_to_pyobject, pyobject_to_msgpack};
+pub use typed::{eval_typed, from_py_attr, TypedCallable, TypedIter};
+pub use visitor::PyObjectVisitor;
 
 #[cfg_attr(doc, doc = include_str!("../README.md"))]
 mod readme {}
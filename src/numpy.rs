@@ -0,0 +1,74 @@
+//! `numpy.ndarray` support of [`from_pyobject`](crate::from_pyobject)
+//!
+//! Arrays are detected without importing numpy, and deserialized as nested sequences
+//! with one level for each dimension. One-dimensional arrays of numbers are copied from their buffer at once.
+//! Scalars, e.g. `numpy.int64` or `numpy.bool_`, are deserialized as the Python objects given by `item()`.
+
+use crate::{array, error::Result, py_module_cache};
+use pyo3::{
+    intern,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+
+/// Fields of `ndarray::ArrayBase` in its serde format
+pub(crate) const NDARRAY_FIELDS: &[&str] = &["v", "dim", "data"];
+
+/// Whether `obj` is an instance of `numpy.ndarray`
+pub(crate) fn is_ndarray(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    is_instance(obj, "ndarray")
+}
+
+/// Whether `obj` is a numpy scalar, e.g. `numpy.int64`, `numpy.float32` or `numpy.bool_`
+pub(crate) fn is_scalar(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    is_instance(obj, "generic")
+}
+
+fn is_instance(obj: &Bound<'_, PyAny>, class: &'static str) -> PyResult<bool> {
+    match py_module_cache::get_imported(obj.py(), "numpy", class)? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
+    }
+}
+
+/// How the elements of an array are visited
+pub(crate) enum Layout<'py> {
+    /// Zero-dimensional array holding a single Python scalar
+    Scalar(Bound<'py, PyAny>),
+    /// One-dimensional buffer of numbers, whose format and length are given
+    Numbers(String, usize),
+    /// Sub-arrays along the first axis, or Python objects of a one-dimensional array
+    Elements(Vec<Bound<'py, PyAny>>),
+}
+
+/// Layout of `array`
+pub(crate) fn layout<'py>(array: &Bound<'py, PyAny>) -> Result<Layout<'py>> {
+    let py = array.py();
+    let ndim: usize = array.getattr(intern!(py, "ndim"))?.extract()?;
+    if ndim == 0 {
+        return Ok(Layout::Scalar(array.call_method0(intern!(py, "item"))?));
+    }
+    if ndim > 1 {
+        let rows = array.try_iter()?.collect::<PyResult<_>>()?;
+        return Ok(Layout::Elements(rows));
+    }
+    if let Some((format, len)) = array::number_buffer(array)? {
+        return Ok(Layout::Numbers(format, len));
+    }
+    // e.g. `bool`, `object`, `str` or `datetime64` arrays
+    let list = array.call_method0(intern!(py, "tolist"))?;
+    Ok(Layout::Elements(
+        list.downcast_into::<PyList>()?.iter().collect(),
+    ))
+}
+
+/// `{"v": 1, "dim": shape, "data": flattened array}` as `ndarray::ArrayBase` is serialized
+pub(crate) fn ndarray_struct<'py>(array: &Bound<'py, PyAny>) -> Result<Bound<'py, PyDict>> {
+    let py = array.py();
+    let dict = PyDict::new(py);
+    dict.set_item("v", 1)?;
+    dict.set_item("dim", array.getattr(intern!(py, "shape"))?)?;
+    // `ndarray` stores elements in the row-major order
+    dict.set_item("data", array.call_method0(intern!(py, "ravel"))?)?;
+    Ok(dict)
+}
@@ -0,0 +1,1591 @@
+use crate::{enum_class::PythonEnum, error::Result, py_module_cache};
+use pyo3::{
+    prelude::*,
+    types::{PyString, PyType},
+};
+use serde::de::DeserializeOwned;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// How to handle a key which is emitted twice into the same Python `dict`
+///
+/// This happens when a `Serialize` implementation emits duplicate keys,
+/// or when distinct Rust keys collide after being converted into Python objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// The last value overwrites the previous one, as `dict.__setitem__` does
+    #[default]
+    Overwrite,
+    /// The first value is kept and later ones are ignored
+    FirstWins,
+    /// Serialization fails with an error which contains the path to the map
+    Error,
+}
+
+/// Conversion applied to every map key after it is serialized into a Python object
+///
+/// This does not apply to field names of structs.
+#[derive(Clone)]
+pub enum MapKeyEncoder {
+    /// Convert keys by `str(key)`
+    Str,
+    /// Convert keys by `json.dumps(key)`
+    JsonDumps,
+    /// Convert keys by a user-defined function
+    Custom(Arc<MapKeyFn>),
+}
+
+/// Function type used in [`MapKeyEncoder::Custom`]
+pub type MapKeyFn = dyn for<'py> Fn(Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> + Send + Sync;
+
+/// Function type used in [`PyObjectSerializerOptions::type_adapter`]
+#[cfg(feature = "erased-serde")]
+pub type TypeAdapterFn = dyn for<'py> Fn(Python<'py>, &dyn erased_serde::Serialize) -> PyResult<Bound<'py, PyAny>>
+    + Send
+    + Sync;
+
+/// Function type used in [`PyObjectSerializerOptions::field_override`]
+pub type FieldOverrideFn =
+    dyn for<'py> Fn(Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> + Send + Sync;
+
+/// Conversion of a field registered by [`PyObjectSerializerOptions::field_override`]
+#[derive(Clone)]
+pub(crate) struct FieldOverride {
+    pub(crate) path: String,
+    pub(crate) convert: Arc<FieldOverrideFn>,
+}
+
+impl fmt::Debug for FieldOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldOverride")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Function type used in [`PyObjectSerializerOptions::post_hook`]
+pub type PostHookFn =
+    dyn for<'py> Fn(Bound<'py, PyAny>, &str) -> PyResult<Bound<'py, PyAny>> + Send + Sync;
+
+/// Function registered by [`PyObjectSerializerOptions::post_hook`]
+#[derive(Clone)]
+pub(crate) struct PostHook(pub(crate) Arc<PostHookFn>);
+
+impl fmt::Debug for PostHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PostHook(..)")
+    }
+}
+
+/// Conversion of a newtype struct registered by [`PyObjectSerializerOptions::type_adapter`]
+#[cfg(feature = "erased-serde")]
+#[derive(Clone)]
+pub(crate) struct TypeAdapter {
+    pub(crate) name: String,
+    pub(crate) convert: Arc<TypeAdapterFn>,
+}
+
+#[cfg(feature = "erased-serde")]
+impl fmt::Debug for TypeAdapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeAdapter")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MapKeyEncoder {
+    pub(crate) fn encode<'py>(&self, key: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            MapKeyEncoder::Str => Ok(key.str()?.into_any()),
+            MapKeyEncoder::JsonDumps => {
+                py_module_cache::get(key.py(), "json", "dumps")?.call1((key,))
+            }
+            MapKeyEncoder::Custom(f) => f(key),
+        }
+    }
+}
+
+impl fmt::Debug for MapKeyEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapKeyEncoder::Str => f.write_str("Str"),
+            MapKeyEncoder::JsonDumps => f.write_str("JsonDumps"),
+            MapKeyEncoder::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Python objects which structs are serialized into,
+/// set by [`PyObjectSerializerOptions::struct_output`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StructOutput {
+    /// `dict` of the fields
+    #[default]
+    Dict,
+    /// Instance of a dataclass created by `dataclasses.make_dataclass`.
+    /// This requires the `dataclass_support` feature.
+    #[cfg(feature = "dataclass_support")]
+    Dataclass,
+    /// Instance of a class created by `collections.namedtuple`
+    NamedTuple,
+    /// `types.SimpleNamespace` whose attributes are the fields.
+    /// This also applies to struct variants, whose fields become a namespace in the `dict` of the variant.
+    Namespace,
+    /// `tuple` of the field values in the declared order, which drops the keys for compact records.
+    /// This also applies to struct variants, whose fields become a `tuple` in the `dict` of the variant.
+    /// [`from_pyobject`](crate::from_pyobject) reads structs from sequences positionally,
+    /// so fields must not be skipped, e.g. by `#[serde(skip_serializing_if)]`,
+    /// and [`PyObjectSerializerOptions::skip_none_fields`] does not apply.
+    Tuple,
+}
+
+/// Which `dict`s have their keys sorted, set by [`PyObjectSerializerOptions::sort_keys`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKeys {
+    /// Keys are kept in the order the `Serialize` implementation emits them
+    #[default]
+    Unsorted,
+    /// Only the `dict` of the root value is sorted
+    TopLevel,
+    /// Every `dict` is sorted
+    Recursive,
+}
+
+/// Case of struct field names in Python, set by [`PyObjectSerializerOptions::field_case`]
+/// and [`PyObjectDeserializerOptions::field_case`]
+///
+/// Names are split into words at `_`, `-` and case boundaries,
+/// e.g. `user_id`, `userId` and `user-id` have the words `user` and `id`,
+/// and `HTTPVersion` has `http` and `version`.
+/// Leading underscores are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldCase {
+    /// Names are used as they are
+    #[default]
+    Unchanged,
+    /// `snake_case`
+    Snake,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `kebab-case`
+    Kebab,
+}
+
+impl FieldCase {
+    /// `name` converted into this case
+    pub(crate) fn convert(self, name: &str) -> Cow<'_, str> {
+        if self == FieldCase::Unchanged {
+            return Cow::Borrowed(name);
+        }
+        let body = name.trim_start_matches('_');
+        let mut out = name[..name.len() - body.len()].to_string();
+        for (i, word) in words(body).enumerate() {
+            match self {
+                FieldCase::Snake | FieldCase::Kebab => {
+                    if i > 0 {
+                        out.push(if self == FieldCase::Snake { '_' } else { '-' });
+                    }
+                    out.extend(word.chars().flat_map(char::to_lowercase));
+                }
+                FieldCase::Camel if i == 0 => out.extend(word.chars().flat_map(char::to_lowercase)),
+                _ => {
+                    let mut chars = word.chars();
+                    out.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                    out.extend(chars.flat_map(char::to_lowercase));
+                }
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Words of a field name separated by `_`, `-` or case boundaries
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut chars = name.char_indices().peekable();
+    let mut prev: Option<char> = None;
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+        if c == '_' || c == '-' {
+            words.push(&name[start..i]);
+            start = i + c.len_utf8();
+        } else if c.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    // The last letter of an acronym starts a new word, e.g. `HTTPVersion`
+                    || (p.is_uppercase() && next.is_some_and(char::is_lowercase))
+            })
+        {
+            words.push(&name[start..i]);
+            start = i;
+        }
+        prev = Some(c);
+    }
+    words.push(&name[start..]);
+    words.into_iter().filter(|word| !word.is_empty())
+}
+
+/// Keywords and soft keywords of Python, which [`PyObjectSerializerOptions::keyword_fields`] recognizes
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "case", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+    "import", "in", "is", "lambda", "match", "nonlocal", "not", "or", "pass", "raise", "return",
+    "try", "type", "while", "with", "yield",
+];
+
+/// `name` without its trailing underscore if the rest is a Python keyword, e.g. `class_` to `class`
+fn strip_keyword(name: &str) -> &str {
+    match name.strip_suffix('_') {
+        Some(keyword) if PYTHON_KEYWORDS.contains(&keyword) => keyword,
+        _ => name,
+    }
+}
+
+/// Python objects which bytes, i.e. values serialized by `serialize_bytes`, are serialized into,
+/// set by [`PyObjectSerializerOptions::bytes_repr`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BytesRepr {
+    /// `bytes`
+    #[default]
+    Bytes,
+    /// `bytearray`
+    ByteArray,
+    /// `list` of `int`
+    List,
+}
+
+/// How `NaN` and infinities are handled, set by [`PyObjectSerializerOptions::non_finite_floats`]
+/// and [`PyObjectDeserializerOptions::non_finite_floats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Passed through as they are
+    #[default]
+    Allow,
+    /// Conversion fails with `ValueError`
+    Error,
+    /// Converted into `None`
+    None,
+}
+
+/// How naive `datetime` objects are deserialized into aware date and time types of [`datetime`](crate::datetime),
+/// set by [`PyObjectDeserializerOptions::naive_datetimes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NaiveDateTimePolicy {
+    /// Deserialization fails with `ValueError`
+    #[default]
+    Error,
+    /// Regarded as UTC
+    AssumeUtc,
+    /// Regarded as the local time of the system, as `datetime.astimezone` does
+    AssumeLocal,
+}
+
+/// Options for [`to_pyobject_with`](crate::to_pyobject_with)
+///
+/// The options are built by chaining builder methods on [`PyObjectSerializerOptions::new`],
+/// each of which takes and returns the options.
+///
+/// ```
+/// use serde_pyobject::{PyObjectSerializerOptions, DuplicateKeyPolicy};
+///
+/// let options = PyObjectSerializerOptions::new()
+///     .duplicate_keys(DuplicateKeyPolicy::Error);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PyObjectSerializerOptions {
+    pub(crate) duplicate_keys: DuplicateKeyPolicy,
+    pub(crate) map_key_encoder: Option<MapKeyEncoder>,
+    pub(crate) intern_strings: bool,
+    pub(crate) class_variants: Vec<ClassVariant>,
+    pub(crate) python_enums: Vec<PythonEnum>,
+    pub(crate) struct_output: StructOutput,
+    pub(crate) json_compatible: bool,
+    pub(crate) skip_none_fields: bool,
+    pub(crate) sort_keys: SortKeys,
+    pub(crate) immutable: bool,
+    pub(crate) bytes_repr: BytesRepr,
+    pub(crate) field_case: FieldCase,
+    pub(crate) keyword_fields: bool,
+    pub(crate) wrap_type_names: bool,
+    pub(crate) non_finite_floats: NonFinitePolicy,
+    #[cfg(feature = "erased-serde")]
+    pub(crate) type_adapters: Vec<TypeAdapter>,
+    pub(crate) post_hook: Option<PostHook>,
+    pub(crate) field_overrides: Vec<FieldOverride>,
+    /// Field names kept across calls by [`Codec`](crate::Codec)
+    pub(crate) field_names: Option<Arc<FieldNames>>,
+}
+
+impl PyObjectSerializerOptions {
+    /// Default options, which [`to_pyobject`](crate::to_pyobject) uses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how duplicated keys in a map are handled
+    pub fn duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Set a conversion applied to every map key
+    ///
+    /// ```
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, pydict, PyObjectSerializerOptions, MapKeyEncoder};
+    /// use std::collections::BTreeMap;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let map = BTreeMap::from([((1, 2), "a"), ((3, 4), "b")]);
+    ///     let options = PyObjectSerializerOptions::new().map_key_encoder(MapKeyEncoder::Str);
+    ///     let obj = to_pyobject_with(py, &map, &options).unwrap();
+    ///     assert!(obj.eq(pydict! { py, "(1, 2)" => "a", "(3, 4)" => "b" }.unwrap()).unwrap());
+    /// });
+    /// ```
+    pub fn map_key_encoder(mut self, encoder: MapKeyEncoder) -> Self {
+        self.map_key_encoder = Some(encoder);
+        self
+    }
+
+    /// Set a Rust function applied to every map key, a shorthand of [`MapKeyEncoder::Custom`]
+    pub fn map_key_fn<F>(self, f: F) -> Self
+    where
+        F: for<'py> Fn(Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> + Send + Sync + 'static,
+    {
+        self.map_key_encoder(MapKeyEncoder::Custom(Arc::new(f)))
+    }
+
+    /// Share one Python `str` object for each distinct string value
+    ///
+    /// Strings are deduplicated within a single serialization,
+    /// which saves memory when the same label appears in many records.
+    ///
+    /// ```
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let labels = vec!["cat".to_string(), "cat".to_string()];
+    ///     let options = PyObjectSerializerOptions::new().intern_strings(true);
+    ///     let obj = to_pyobject_with(py, &labels, &options).unwrap();
+    ///     assert!(obj.get_item(0).unwrap().is(&obj.get_item(1).unwrap()));
+    /// });
+    /// ```
+    pub fn intern_strings(mut self, intern: bool) -> Self {
+        self.intern_strings = intern;
+        self
+    }
+
+    /// Serialize unit variants of the Rust enum `T` into members of an `enum.Enum` class
+    ///
+    /// The class has the name of `T` and a member for each variant,
+    /// whose name and value are the variant name after `#[serde(rename)]`.
+    /// It is created at the first use, and kept until [`clear_caches`](crate::clear_caches) is called,
+    /// so that members can be compared by identity.
+    /// The variants of `T` are traced through its [`Deserialize`](serde::Deserialize) implementation.
+    /// Members are deserialized back into the variants of their names.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TypeError` if `T` is not deserialized as an enum.
+    ///
+    /// ```
+    /// use serde::{Deserialize, Serialize};
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{from_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// enum Color {
+    ///     Red,
+    ///     Green,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().python_enum::<Color>().unwrap();
+    ///     let red = to_pyobject_with(py, &Color::Red, &options).unwrap();
+    ///     assert_eq!(red.repr().unwrap(), "<Color.Red: 'Red'>");
+    ///     assert!(red.is(&to_pyobject_with(py, &Color::Red, &options).unwrap()));
+    ///     assert_eq!(from_pyobject::<Color, _>(red).unwrap(), Color::Red);
+    /// });
+    /// ```
+    pub fn python_enum<T: DeserializeOwned>(mut self) -> Result<Self> {
+        self.python_enums.push(PythonEnum::of::<T>()?);
+        Ok(self)
+    }
+
+    /// Make the output equal to `json.loads(serde_json::to_string(value))`
+    ///
+    /// - unit and unit structs become `None` instead of `()`
+    /// - tuples, tuple structs and the contents of tuple variants become `list` instead of `tuple`
+    /// - bytes become `list` of `int`, and [`array`](crate::array) values become `list` instead of `array.array`
+    /// - `NaN` and infinities become `None`
+    /// - keys of maps become `str` as `serde_json` stringifies them, i.e. `bool`, `int` and `float`
+    ///   are formatted, and other keys are rejected, unless [`Self::map_key_encoder`] is set
+    ///
+    /// Other options, e.g. [`Self::struct_output`] or [`Self::class_variant`], still apply.
+    ///
+    /// ```
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    /// use std::collections::BTreeMap;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let value = BTreeMap::from([(1, ((), (2.5, f64::NAN)))]);
+    ///     let options = PyObjectSerializerOptions::new().json_compatible(true);
+    ///     let obj = to_pyobject_with(py, &value, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "{'1': [None, [2.5, None]]}");
+    /// });
+    /// ```
+    pub fn json_compatible(mut self, enable: bool) -> Self {
+        self.json_compatible = enable;
+        self
+    }
+
+    /// Omit fields of structs and struct variants, and entries of maps, whose values are `None`
+    ///
+    /// This applies to every value serialized into `None`, e.g. `Option::None`,
+    /// as `#[serde(skip_serializing_if = "Option::is_none")]` on every field would do.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct User {
+    ///     name: String,
+    ///     email: Option<String>,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let user = User { name: "alice".to_string(), email: None };
+    ///     let options = PyObjectSerializerOptions::new().skip_none_fields(true);
+    ///     let obj = to_pyobject_with(py, &user, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "{'name': 'alice'}");
+    /// });
+    /// ```
+    pub fn skip_none_fields(mut self, skip: bool) -> Self {
+        self.skip_none_fields = skip;
+        self
+    }
+
+    /// Sort keys of `dict`s serialized from maps, structs and struct variants
+    ///
+    /// Keys are compared by Python `<` as `sorted` does, and keys which cannot be compared,
+    /// e.g. `str` and `int` in the same map, raise `TypeError`.
+    /// This is useful for deterministic output when it is hashed, diffed, or printed.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions, SortKeys};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     y: i32,
+    ///     x: i32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().sort_keys(SortKeys::Recursive);
+    ///     let obj = to_pyobject_with(py, &Point { y: 2, x: 1 }, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "{'x': 1, 'y': 2}");
+    /// });
+    /// ```
+    pub fn sort_keys(mut self, sort: SortKeys) -> Self {
+        self.sort_keys = sort;
+        self
+    }
+
+    /// Produce deeply immutable Python objects, which can be shared safely, e.g. across threads
+    ///
+    /// - sequences become `tuple` instead of `list`, and [`array`](crate::array) values
+    ///   become `tuple` instead of `array.array`
+    /// - maps, structs and the `dict`s wrapping enum variants become `types.MappingProxyType`
+    ///   of the `dict`
+    /// - [`set`](crate::set) values become `frozenset` instead of `set`
+    ///
+    /// This takes precedence over `list` and `dict` of [`Self::json_compatible`].
+    /// Objects created by [`Self::struct_output`] and [`Self::class_variant`] are kept as they are.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     hosts: Vec<&'static str>,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().immutable(true);
+    ///     let obj = to_pyobject_with(py, &Config { hosts: vec!["a", "b"] }, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "mappingproxy({'hosts': ('a', 'b')})");
+    ///     assert!(obj.set_item("hosts", ()).is_err());
+    /// });
+    /// ```
+    pub fn immutable(mut self, enable: bool) -> Self {
+        self.immutable = enable;
+        self
+    }
+
+    /// Convert field names of structs and struct variants into `case`
+    ///
+    /// Names set by `#[serde(rename)]` are converted as well, while keys of maps are not.
+    /// Use [`PyObjectDeserializerOptions::field_case`] to read them back.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, FieldCase, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct User {
+    ///     user_id: u32,
+    ///     display_name: &'static str,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().field_case(FieldCase::Camel);
+    ///     let obj = to_pyobject_with(py, &User { user_id: 1, display_name: "Alice" }, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "{'userId': 1, 'displayName': 'Alice'}");
+    /// });
+    /// ```
+    pub fn field_case(mut self, case: FieldCase) -> Self {
+        self.field_case = case;
+        self
+    }
+
+    /// Strip the trailing underscore of field names whose rest is a Python keyword,
+    /// e.g. `class_`, `from_` or `type_`, which Rust code names to avoid keywords
+    ///
+    /// This applies before [`Self::field_case`], but not to the fields of classes made by
+    /// [`StructOutput::Dataclass`] and [`StructOutput::NamedTuple`], which cannot be keywords.
+    /// Use [`PyObjectDeserializerOptions::keyword_fields`] to read them back.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Element {
+    ///     class_: &'static str,
+    ///     id_: u32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().keyword_fields(true);
+    ///     let obj = to_pyobject_with(py, &Element { class_: "button", id_: 1 }, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "{'class': 'button', 'id_': 1}");
+    /// });
+    /// ```
+    pub fn keyword_fields(mut self, enable: bool) -> Self {
+        self.keyword_fields = enable;
+        self
+    }
+
+    /// Wrap structs, newtype structs and unit variants in `dict` whose key is the name of their type,
+    /// for compatibility with data produced by older versions
+    ///
+    /// - structs become `{"Struct": {...}}`, whatever [`Self::struct_output`] produces is wrapped
+    /// - newtype structs become `{"Newtype": value}`
+    /// - unit variants become `{"Enum": "Variant"}`
+    ///
+    /// Use [`PyObjectDeserializerOptions::wrap_type_names`] to read them back.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Meters(f64);
+    ///
+    /// #[derive(Serialize)]
+    /// enum Unit {
+    ///     Metric,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Length {
+    ///     value: Meters,
+    ///     unit: Unit,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().wrap_type_names(true);
+    ///     let length = Length { value: Meters(1.5), unit: Unit::Metric };
+    ///     let obj = to_pyobject_with(py, &length, &options).unwrap();
+    ///     assert_eq!(
+    ///         obj.repr().unwrap(),
+    ///         "{'Length': {'value': {'Meters': 1.5}, 'unit': {'Unit': 'Metric'}}}"
+    ///     );
+    /// });
+    /// ```
+    pub fn wrap_type_names(mut self, enable: bool) -> Self {
+        self.wrap_type_names = enable;
+        self
+    }
+
+    /// Set how `NaN` and infinities of `f32` and `f64` are serialized
+    ///
+    /// [`Self::json_compatible`] serializes them into `None` unless [`NonFinitePolicy::Error`] is set.
+    ///
+    /// ```
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, NonFinitePolicy, PyObjectSerializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().non_finite_floats(NonFinitePolicy::None);
+    ///     let obj = to_pyobject_with(py, &[1.0, f64::NAN], &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "(1.0, None)");
+    ///
+    ///     let options = PyObjectSerializerOptions::new().non_finite_floats(NonFinitePolicy::Error);
+    ///     let err = to_pyobject_with(py, &[1.0, f64::INFINITY], &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "ValueError: non-finite float inf at $[1]");
+    /// });
+    /// ```
+    pub fn non_finite_floats(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite_floats = policy;
+        self
+    }
+
+    /// Convert newtype structs named `name`, e.g. `struct Meters(f64)`, by a Rust function
+    ///
+    /// The function receives the inner value as [`erased_serde::Serialize`], and returns the Python object used instead,
+    /// so that downstream crates can produce their domain objects without a `#[serde(with)]` helper on the type.
+    /// The value is not serialized beforehand, so that the function serializes it in its own way,
+    /// e.g. by [`to_pyobject`](crate::to_pyobject), or into another format.
+    /// Registering the same name again replaces the previous function.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Meters(f64);
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().type_adapter("Meters", |py, value| {
+    ///         let value = to_pyobject(py, value)?;
+    ///         py.import("fractions")?.getattr("Fraction")?.call1((value,))
+    ///     });
+    ///     let obj = to_pyobject_with(py, &vec![Meters(0.5)], &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "[Fraction(1, 2)]");
+    /// });
+    /// ```
+    #[cfg(feature = "erased-serde")]
+    pub fn type_adapter<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: for<'py> Fn(Python<'py>, &dyn erased_serde::Serialize) -> PyResult<Bound<'py, PyAny>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let name = name.into();
+        self.type_adapters.retain(|adapter| adapter.name != name);
+        self.type_adapters.push(TypeAdapter {
+            name,
+            convert: Arc::new(f),
+        });
+        self
+    }
+
+    /// Call a Rust function on every serialized value before it is inserted into its parent container
+    ///
+    /// The function receives the value and its path in the same format as error messages, e.g. `$.items[0]`,
+    /// and returns the object inserted instead, e.g. to intern, wrap or replace values.
+    /// It is called after the children of the value, and not for the root object.
+    ///
+    /// ```
+    /// use pyo3::{Python, types::PyAnyMethods, IntoPyObjectExt};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    /// use std::collections::BTreeMap;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let map = BTreeMap::from([("a", vec![1, -1]), ("b", vec![])]);
+    ///     // Replace negative numbers by their paths
+    ///     let options = PyObjectSerializerOptions::new().post_hook(|value, path| {
+    ///         if value.extract::<i64>().is_ok_and(|n| n < 0) {
+    ///             return path.into_bound_py_any(value.py());
+    ///         }
+    ///         Ok(value)
+    ///     });
+    ///     let obj = to_pyobject_with(py, &map, &options).unwrap();
+    ///     assert_eq!(obj.get_item("a").unwrap().get_item(1).unwrap().to_string(), "$['a'][1]");
+    /// });
+    /// ```
+    pub fn post_hook<F>(mut self, f: F) -> Self
+    where
+        F: for<'py> Fn(Bound<'py, PyAny>, &str) -> PyResult<Bound<'py, PyAny>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.post_hook = Some(PostHook(Arc::new(f)));
+        self
+    }
+
+    /// Convert the values at the dotted field path `path`, e.g. `"config.timeout"`, by a Rust function
+    ///
+    /// The path consists of the Rust names of struct fields and the `str` of map keys.
+    /// Indices of sequences are skipped, so that `"items.price"` matches the field `price` of every element of `items`.
+    /// The function receives the value serialized as usual, and returns the Python object used instead,
+    /// which avoids `#[serde(with)]` helpers on fields of third-party types.
+    /// Registering the same path again replaces the previous function.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Config {
+    ///     timeout: u64,
+    ///     retries: u64,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct App {
+    ///     config: Config,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let app = App { config: Config { timeout: 30, retries: 3 } };
+    ///     let options = PyObjectSerializerOptions::new().field_override("config.timeout", |value| {
+    ///         let timedelta = value.py().import("datetime")?.getattr("timedelta")?;
+    ///         timedelta.call1((0, value))
+    ///     });
+    ///     let obj = to_pyobject_with(py, &app, &options).unwrap();
+    ///     assert_eq!(
+    ///         obj.repr().unwrap(),
+    ///         "{'config': {'timeout': datetime.timedelta(seconds=30), 'retries': 3}}"
+    ///     );
+    /// });
+    /// ```
+    pub fn field_override<F>(mut self, path: impl Into<String>, f: F) -> Self
+    where
+        F: for<'py> Fn(Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> + Send + Sync + 'static,
+    {
+        let path = path.into();
+        self.field_overrides.retain(|entry| entry.path != path);
+        self.field_overrides.push(FieldOverride {
+            path,
+            convert: Arc::new(f),
+        });
+        self
+    }
+
+    /// Function registered by [`Self::type_adapter`] for the newtype struct `name`
+    #[cfg(feature = "erased-serde")]
+    pub(crate) fn type_adapter_of(&self, name: &str) -> Option<Arc<TypeAdapterFn>> {
+        self.type_adapters
+            .iter()
+            .find(|adapter| adapter.name == name)
+            .map(|adapter| adapter.convert.clone())
+    }
+
+    /// Name of the field `name` in Python following [`Self::keyword_fields`] and [`Self::field_case`]
+    pub(crate) fn python_field_name(&self, name: &'static str) -> Cow<'static, str> {
+        let name = if self.keyword_fields {
+            strip_keyword(name)
+        } else {
+            name
+        };
+        self.field_case.convert(name)
+    }
+
+    /// Set the Python objects which bytes are serialized into
+    ///
+    /// This applies to values serialized by `serialize_bytes`, e.g. `serde_bytes::ByteBuf`,
+    /// but not to `Vec<u8>`, which is serialized as a sequence.
+    /// [`Self::json_compatible`] always gives `list`, and [`Self::immutable`] gives `bytes`
+    /// instead of `bytearray`, and `tuple` instead of `list`.
+    /// [`from_pyobject`](crate::from_pyobject) accepts all of them.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, BytesRepr, PyObjectSerializerOptions};
+    ///
+    /// struct Blob(&'static [u8]);
+    ///
+    /// impl Serialize for Blob {
+    ///     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    ///         serializer.serialize_bytes(self.0)
+    ///     }
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().bytes_repr(BytesRepr::ByteArray);
+    ///     let obj = to_pyobject_with(py, &Blob(b"ab"), &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "bytearray(b'ab')");
+    ///
+    ///     let options = PyObjectSerializerOptions::new().bytes_repr(BytesRepr::List);
+    ///     let obj = to_pyobject_with(py, &Blob(b"ab"), &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "[97, 98]");
+    /// });
+    /// ```
+    pub fn bytes_repr(mut self, repr: BytesRepr) -> Self {
+        self.bytes_repr = repr;
+        self
+    }
+
+    /// Set the Python objects which structs are serialized into
+    ///
+    /// Classes of [`StructOutput::Dataclass`] and [`StructOutput::NamedTuple`] are created
+    /// with the name of the struct and its serialized fields, only for structs but not for struct variants,
+    /// and kept until [`clear_caches`](crate::clear_caches) is called.
+    /// Values of the same struct whose fields are skipped differently,
+    /// e.g. by `#[serde(skip_serializing_if)]`, become instances of different classes.
+    /// Field names must be valid Python identifiers, otherwise the class cannot be created.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, types::PyAnyMethods};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions, StructOutput};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectSerializerOptions::new().struct_output(StructOutput::NamedTuple);
+    ///     let obj = to_pyobject_with(py, &Point { x: 1, y: 2 }, &options).unwrap();
+    ///     assert_eq!(obj.repr().unwrap(), "Point(x=1, y=2)");
+    ///     assert_eq!(obj.get_item(1).unwrap().extract::<i32>().unwrap(), 2);
+    /// });
+    /// ```
+    pub fn struct_output(mut self, output: StructOutput) -> Self {
+        self.struct_output = output;
+        self
+    }
+
+    /// Serialize a variant of a Rust enum into an instance of a Python class
+    ///
+    /// This is the counterpart of [`PyObjectDeserializerOptions::class_variant`],
+    /// and takes the same arguments.
+    /// The class is called with the fields of a struct variant as keyword arguments,
+    /// and with the fields of a tuple variant as positional arguments.
+    /// A newtype variant passes the keyword arguments if its content is serialized into a `dict`,
+    /// or the content itself otherwise.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use pyo3::{Python, ffi::c_str, types::{PyDict, PyAnyMethods}};
+    /// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+    ///
+    /// #[derive(Serialize)]
+    /// enum Shape {
+    ///     Circle { r: f64 },
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let globals = PyDict::new(py);
+    ///     py.run(c_str!(r#"
+    /// from dataclasses import dataclass
+    ///
+    /// @dataclass
+    /// class Circle:
+    ///     r: float
+    /// "#), Some(&globals), None).unwrap();
+    ///
+    ///     let circle = globals.get_item("Circle").unwrap();
+    ///     let options = PyObjectSerializerOptions::new()
+    ///         .class_variant("Shape", circle.downcast().unwrap(), "Circle");
+    ///     let obj = to_pyobject_with(py, &Shape::Circle { r: 1.0 }, &options).unwrap();
+    ///     assert!(obj.eq(circle.call1((1.0,)).unwrap()).unwrap());
+    /// });
+    /// ```
+    pub fn class_variant(
+        mut self,
+        enum_name: &'static str,
+        class: &Bound<'_, PyType>,
+        variant: &'static str,
+    ) -> Self {
+        self.class_variants.push(ClassVariant {
+            enum_name,
+            variant,
+            class: Arc::new(class.clone().unbind()),
+        });
+        self
+    }
+}
+
+/// How `...` (`Ellipsis`) and `NotImplemented` are deserialized
+/// into a type which accepts any value, e.g. `serde_json::Value`
+///
+/// The markers [`Ellipsis`](crate::Ellipsis) and [`NotImplemented`](crate::NotImplemented)
+/// accept the corresponding singleton regardless of this policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SingletonPolicy {
+    /// Deserialization fails
+    #[default]
+    Error,
+    /// Deserialized as `None`
+    None,
+    /// Deserialized as the strings `"Ellipsis"` and `"NotImplemented"`,
+    /// which the markers also accept, e.g. in `#[serde(untagged)]` enums
+    Marker,
+}
+
+/// How keys of `dict` which are not fields of the target struct are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownKeyPolicy {
+    /// Unknown keys are ignored, as serde does without `#[serde(deny_unknown_fields)]`
+    #[default]
+    Ignore,
+    /// Deserialization fails with the first unknown key
+    Error,
+}
+
+/// Options for [`from_pyobject_with`](crate::from_pyobject_with)
+///
+/// ```
+/// use serde_pyobject::PyObjectDeserializerOptions;
+///
+/// let options = PyObjectDeserializerOptions::new().max_str_len(1024);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PyObjectDeserializerOptions {
+    pub(crate) max_str_len: Option<usize>,
+    pub(crate) max_elements: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) stringify_keys: bool,
+    pub(crate) class_variants: Vec<ClassVariant>,
+    pub(crate) singletons: SingletonPolicy,
+    pub(crate) unknown_keys: UnknownKeyPolicy,
+    pub(crate) field_case: FieldCase,
+    pub(crate) keyword_fields: bool,
+    pub(crate) wrap_type_names: bool,
+    pub(crate) non_finite_floats: NonFinitePolicy,
+    pub(crate) unique_sets: bool,
+    pub(crate) exact_struct_length: bool,
+    pub(crate) lossy_decimals: bool,
+    pub(crate) naive_datetimes: NaiveDateTimePolicy,
+    #[cfg(feature = "pydantic_support")]
+    pub(crate) pydantic_by_alias: bool,
+    #[cfg(feature = "pydantic_support")]
+    pub(crate) pydantic_exclude_unset: bool,
+    #[cfg(feature = "pydantic_support")]
+    pub(crate) pydantic_exclude_defaults: bool,
+    /// Plans of structs kept across calls by [`Codec`](crate::Codec)
+    pub(crate) struct_plans: Option<Arc<StructPlans>>,
+}
+
+/// Python class associated with a variant of a Rust enum
+#[derive(Debug, Clone)]
+pub(crate) struct ClassVariant {
+    pub(crate) enum_name: &'static str,
+    pub(crate) variant: &'static str,
+    pub(crate) class: Arc<Py<PyType>>,
+}
+
+impl Default for PyObjectDeserializerOptions {
+    fn default() -> Self {
+        PyObjectDeserializerOptions {
+            max_str_len: None,
+            max_elements: None,
+            max_depth: None,
+            stringify_keys: false,
+            class_variants: Vec::new(),
+            singletons: SingletonPolicy::default(),
+            unknown_keys: UnknownKeyPolicy::default(),
+            field_case: FieldCase::default(),
+            keyword_fields: false,
+            wrap_type_names: false,
+            non_finite_floats: NonFinitePolicy::default(),
+            unique_sets: false,
+            exact_struct_length: false,
+            lossy_decimals: true,
+            naive_datetimes: NaiveDateTimePolicy::default(),
+            #[cfg(feature = "pydantic_support")]
+            pydantic_by_alias: false,
+            #[cfg(feature = "pydantic_support")]
+            pydantic_exclude_unset: false,
+            #[cfg(feature = "pydantic_support")]
+            pydantic_exclude_defaults: false,
+            struct_plans: None,
+        }
+    }
+}
+
+impl PyObjectDeserializerOptions {
+    /// Default options, which [`from_pyobject`](crate::from_pyobject) uses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject `str`, `bytes`, `bytearray` and `memoryview` whose `len()`, or `nbytes` of `memoryview`,
+    /// exceeds `len` with `ValueError`
+    ///
+    /// This protects services which deserialize payloads from untrusted users.
+    /// The length is checked before the contents are copied into Rust.
+    pub fn max_str_len(mut self, len: usize) -> Self {
+        self.max_str_len = Some(len);
+        self
+    }
+
+    /// Reject input which has more than `n` elements in total with `ValueError`
+    ///
+    /// Every element of sequences and every entry of dicts in the whole input counts.
+    /// The length of each container is counted before its contents are visited,
+    /// so that an adversarially large input fails early.
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pylist, PyObjectDeserializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().max_elements(3);
+    ///     let list = pylist![py; (1, 2), (3, 4)].unwrap();
+    ///     let err = from_pyobject_with::<Vec<(i32, i32)>, _>(list, &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "ValueError: total number of elements exceeds the limit 3");
+    /// });
+    /// ```
+    pub fn max_elements(mut self, n: usize) -> Self {
+        self.max_elements = Some(n);
+        self
+    }
+
+    /// Stringify `bool`, `int`, `float` and `None` keys of `dict` as `json.dumps` does,
+    /// when the target key type is a string
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pydict, PyObjectDeserializerOptions};
+    /// use std::collections::BTreeMap;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let dict = pydict! { py, 1 => "a", false => "b", 0.5 => "c" }.unwrap();
+    ///     let options = PyObjectDeserializerOptions::new().stringify_keys(true);
+    ///     let map: BTreeMap<String, String> = from_pyobject_with(dict, &options).unwrap();
+    ///     assert_eq!(map["1"], "a");
+    ///     assert_eq!(map["false"], "b");
+    ///     assert_eq!(map["0.5"], "c");
+    /// });
+    /// ```
+    pub fn stringify_keys(mut self, stringify: bool) -> Self {
+        self.stringify_keys = stringify;
+        self
+    }
+
+    /// Reject `list` and `tuple` with duplicate elements with `ValueError`
+    /// when they are deserialized into a field using [`set`](crate::set)
+    ///
+    /// `HashSet<T>` and `BTreeSet<T>` silently drop duplicates otherwise.
+    /// Elements are compared by Python equality, and unhashable elements are not checked.
+    /// `set` and `frozenset` are unique by construction.
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde::Deserialize;
+    /// use serde_pyobject::{from_pyobject_with, pydict, pylist, PyObjectDeserializerOptions};
+    /// use std::collections::BTreeSet;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Post {
+    ///     #[serde(with = "serde_pyobject::set")]
+    ///     tags: BTreeSet<String>,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().unique_sets(true);
+    ///     let tags = pylist![py; "rust", "rust"].unwrap();
+    ///     let dict = pydict! { py, "tags" => tags }.unwrap();
+    ///     let err = from_pyobject_with::<Post, _>(dict, &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "ValueError: duplicate element 'rust' in a set");
+    /// });
+    /// ```
+    pub fn unique_sets(mut self, enable: bool) -> Self {
+        self.unique_sets = enable;
+        self
+    }
+
+    /// Reject sequences deserialized positionally into structs unless they have exactly one element for each field
+    ///
+    /// By default, surplus elements are ignored, and missing trailing fields take their `#[serde(default)]`.
+    ///
+    /// ```
+    /// use pyo3::{Python, IntoPyObject};
+    /// use serde::Deserialize;
+    /// use serde_pyobject::{from_pyobject, from_pyobject_with, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Row {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let row = (1, "alice", true).into_pyobject(py).unwrap();
+    ///     let lenient: Row = from_pyobject(row.clone()).unwrap();
+    ///     assert_eq!(lenient, Row { id: 1, name: "alice".to_string() });
+    ///
+    ///     let options = PyObjectDeserializerOptions::new().exact_struct_length(true);
+    ///     let err = from_pyobject_with::<Row, _>(row, &options).unwrap_err();
+    ///     assert_eq!(
+    ///         err.to_string(),
+    ///         "RuntimeError: invalid length 3, expected struct Row with 2 elements"
+    ///     );
+    /// });
+    /// ```
+    pub fn exact_struct_length(mut self, enable: bool) -> Self {
+        self.exact_struct_length = enable;
+        self
+    }
+
+    /// Convert `decimal.Decimal` into floats, e.g. `f64` or a float chosen by `serde_json::Value`, by `float()`
+    ///
+    /// This is enabled by default, although the conversion may lose precision.
+    /// When disabled, `decimal.Decimal` for a float raises `TypeError`,
+    /// and exact values are read by [`decimal`](crate::decimal) instead.
+    ///
+    /// ```
+    /// use pyo3::{Python, ffi::c_str};
+    /// use serde_pyobject::{from_pyobject, from_pyobject_with, PyObjectDeserializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let obj = py.eval(c_str!("__import__('decimal').Decimal('0.1')"), None, None).unwrap();
+    ///     let x: f64 = from_pyobject(obj.clone()).unwrap();
+    ///     assert_eq!(x, 0.1);
+    ///
+    ///     let options = PyObjectDeserializerOptions::new().lossy_decimals(false);
+    ///     let err = from_pyobject_with::<f64, _>(obj, &options).unwrap_err();
+    ///     assert_eq!(
+    ///         err.to_string(),
+    ///         "TypeError: Decimal('0.1') is not converted into a float, see PyObjectDeserializerOptions::lossy_decimals"
+    ///     );
+    /// });
+    /// ```
+    pub fn lossy_decimals(mut self, enable: bool) -> Self {
+        self.lossy_decimals = enable;
+        self
+    }
+
+    /// Set how naive `datetime` objects are deserialized into aware types, e.g. `DateTime<Utc>` or `SystemTime`,
+    /// by [`datetime`](crate::datetime)
+    ///
+    /// ```
+    /// # #[cfg(feature = "chrono")] {
+    /// use chrono::{DateTime, Utc};
+    /// use pyo3::{Python, ffi::c_str};
+    /// use serde::Deserialize;
+    /// use serde_pyobject::{from_pyobject_with, NaiveDateTimePolicy, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Event {
+    ///     #[serde(with = "serde_pyobject::datetime")]
+    ///     at: DateTime<Utc>,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let obj = py
+    ///         .eval(c_str!("{'at': __import__('datetime').datetime(2024, 1, 1)}"), None, None)
+    ///         .unwrap();
+    ///     let err = from_pyobject_with::<Event, _>(obj.clone(), &PyObjectDeserializerOptions::new())
+    ///         .unwrap_err();
+    ///     assert_eq!(
+    ///         err.to_string(),
+    ///         "ValueError: naive datetime 2024-01-01 00:00:00 is not allowed, see PyObjectDeserializerOptions::naive_datetimes"
+    ///     );
+    ///
+    ///     let options = PyObjectDeserializerOptions::new().naive_datetimes(NaiveDateTimePolicy::AssumeUtc);
+    ///     let event: Event = from_pyobject_with(obj, &options).unwrap();
+    ///     assert_eq!(event.at, DateTime::from_timestamp(1_704_067_200, 0).unwrap());
+    /// });
+    /// # }
+    /// ```
+    pub fn naive_datetimes(mut self, policy: NaiveDateTimePolicy) -> Self {
+        self.naive_datetimes = policy;
+        self
+    }
+
+    /// Read pydantic models by `model_dump(by_alias=True)`, so that the keys are the aliases of the fields
+    ///
+    /// Models are read by `model_dump`, or `dict` of pydantic v1, rather than by their attributes
+    /// when any of this, [`Self::pydantic_exclude_unset`] or [`Self::pydantic_exclude_defaults`] is enabled.
+    /// Otherwise `model_dump()` is used only for targets which are not structs, e.g. `serde_json::Value`.
+    /// This requires the `pydantic_support` feature.
+    ///
+    /// ```
+    /// use pyo3::{prelude::*, ffi::c_str};
+    /// use serde::Deserialize;
+    /// use serde_pyobject::{from_pyobject_with, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct User {
+    ///     #[serde(rename = "userName")]
+    ///     user_name: String,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    /// #   // pydantic is not a dependency of this crate
+    /// #   if py.import("pydantic").is_err() {
+    /// #       return;
+    /// #   }
+    ///     let globals = pyo3::types::PyDict::new(py);
+    ///     py.run(c_str!(r#"
+    /// from pydantic import BaseModel, Field
+    ///
+    /// class User(BaseModel):
+    ///     user_name: str = Field(alias="userName")
+    ///
+    /// user = User(userName="alice")
+    /// "#), Some(&globals), None).unwrap();
+    ///     let options = PyObjectDeserializerOptions::new().pydantic_by_alias(true);
+    ///     let user: User = from_pyobject_with(globals.get_item("user").unwrap().unwrap(), &options).unwrap();
+    ///     assert_eq!(user.user_name, "alice");
+    /// });
+    /// ```
+    #[cfg(feature = "pydantic_support")]
+    pub fn pydantic_by_alias(mut self, enable: bool) -> Self {
+        self.pydantic_by_alias = enable;
+        self
+    }
+
+    /// Read pydantic models by `model_dump(exclude_unset=True)`, so that fields which are not set explicitly
+    /// are missing, e.g. to fall back to `#[serde(default)]`
+    ///
+    /// See [`Self::pydantic_by_alias`] for how models are read.
+    /// This requires the `pydantic_support` feature.
+    #[cfg(feature = "pydantic_support")]
+    pub fn pydantic_exclude_unset(mut self, enable: bool) -> Self {
+        self.pydantic_exclude_unset = enable;
+        self
+    }
+
+    /// Read pydantic models by `model_dump(exclude_defaults=True)`, so that fields equal to their defaults are missing
+    ///
+    /// See [`Self::pydantic_by_alias`] for how models are read.
+    /// This requires the `pydantic_support` feature.
+    #[cfg(feature = "pydantic_support")]
+    pub fn pydantic_exclude_defaults(mut self, enable: bool) -> Self {
+        self.pydantic_exclude_defaults = enable;
+        self
+    }
+
+    /// Reject input whose containers are nested deeper than `depth` with `ValueError`
+    ///
+    /// Elements of sequences, values of dicts, attributes of objects
+    /// and the contents of enum variants are one level deeper than their container.
+    /// The top-level object is at depth 0.
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pylist, PyObjectDeserializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().max_depth(1);
+    ///     let flat = pylist![py; 1, 2].unwrap();
+    ///     assert!(from_pyobject_with::<Vec<i32>, _>(flat, &options).is_ok());
+    ///
+    ///     let nested = pylist![py; pylist![py; 1].unwrap()].unwrap();
+    ///     let err = from_pyobject_with::<Vec<Vec<i32>>, _>(nested, &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "ValueError: nesting depth exceeds the limit 1");
+    /// });
+    /// ```
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Set how keys of `dict` which are not fields of the target struct are handled
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde::Deserialize;
+    /// use serde_pyobject::{from_pyobject_with, pydict, PyObjectDeserializerOptions, UnknownKeyPolicy};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let dict = pydict! { py, "x" => 1, "y" => 2, "z" => 3 }.unwrap();
+    ///     let options = PyObjectDeserializerOptions::new().unknown_keys(UnknownKeyPolicy::Error);
+    ///     let err = from_pyobject_with::<Point, _>(dict, &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "RuntimeError: unknown field `z`, expected `x` or `y`");
+    /// });
+    /// ```
+    pub fn unknown_keys(mut self, policy: UnknownKeyPolicy) -> Self {
+        self.unknown_keys = policy;
+        self
+    }
+
+    /// Read field names of structs and struct variants from keys of `dict` in `case`
+    ///
+    /// A key matches a field if it equals the field name converted into `case`,
+    /// so that values serialized with [`PyObjectSerializerOptions::field_case`] are read back.
+    /// Attributes of objects, e.g. dataclasses made by [`StructOutput::Dataclass`], are read by either name.
+    /// This does not apply to keys of maps.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pydict, FieldCase, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct User {
+    ///     user_id: u32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().field_case(FieldCase::Kebab);
+    ///     let dict = pydict! { py, "user-id" => 1 }.unwrap();
+    ///     let user: User = from_pyobject_with(dict, &options).unwrap();
+    ///     assert_eq!(user, User { user_id: 1 });
+    /// });
+    /// ```
+    pub fn field_case(mut self, case: FieldCase) -> Self {
+        self.field_case = case;
+        self
+    }
+
+    /// Accept keys of `dict` without the trailing underscore of field names whose rest is a Python keyword,
+    /// e.g. `"class"` for `class_`, as well as the field names themselves
+    ///
+    /// This is the counterpart of [`PyObjectSerializerOptions::keyword_fields`].
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pydict, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Range {
+    ///     from_: u32,
+    ///     to: u32,
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().keyword_fields(true);
+    ///     let dict = pydict! { py, "from" => 1, "to" => 2 }.unwrap();
+    ///     let range: Range = from_pyobject_with(dict, &options).unwrap();
+    ///     assert_eq!(range, Range { from_: 1, to: 2 });
+    /// });
+    /// ```
+    pub fn keyword_fields(mut self, enable: bool) -> Self {
+        self.keyword_fields = enable;
+        self
+    }
+
+    /// Accept newtype structs and unit variants wrapped in `dict` whose key is the name of their type,
+    /// which [`PyObjectSerializerOptions::wrap_type_names`] produces
+    ///
+    /// Structs wrapped in this way are always accepted.
+    /// Unwrapped values are accepted as well.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, pydict, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Meters(f64);
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().wrap_type_names(true);
+    ///     let dict = pydict! { py, "Meters" => 1.5 }.unwrap();
+    ///     let meters: Meters = from_pyobject_with(dict, &options).unwrap();
+    ///     assert_eq!(meters, Meters(1.5));
+    /// });
+    /// ```
+    pub fn wrap_type_names(mut self, enable: bool) -> Self {
+        self.wrap_type_names = enable;
+        self
+    }
+
+    /// Set how `float('nan')` and infinities are deserialized
+    ///
+    /// With [`NonFinitePolicy::None`], they are deserialized as `None`,
+    /// e.g. `Option<f64>` becomes `None` and `serde_json::Value` becomes `Null`.
+    ///
+    /// ```
+    /// use pyo3::{Python, ffi::c_str};
+    /// use serde_pyobject::{from_pyobject_with, NonFinitePolicy, PyObjectDeserializerOptions};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let obj = py.eval(c_str!("[1.0, float('nan')]"), None, None).unwrap();
+    ///
+    ///     let options = PyObjectDeserializerOptions::new().non_finite_floats(NonFinitePolicy::None);
+    ///     let values: Vec<Option<f64>> = from_pyobject_with(obj.clone(), &options).unwrap();
+    ///     assert_eq!(values, vec![Some(1.0), None]);
+    ///
+    ///     let options = PyObjectDeserializerOptions::new().non_finite_floats(NonFinitePolicy::Error);
+    ///     let err = from_pyobject_with::<Vec<f64>, _>(obj, &options).unwrap_err();
+    ///     assert_eq!(err.to_string(), "ValueError: non-finite float NaN is not allowed");
+    /// });
+    /// ```
+    pub fn non_finite_floats(mut self, policy: NonFinitePolicy) -> Self {
+        self.non_finite_floats = policy;
+        self
+    }
+
+    /// Whether keys of `dict` are matched with field names by [`Self::field_case`] or [`Self::keyword_fields`]
+    pub(crate) fn renames_fields(&self) -> bool {
+        self.field_case != FieldCase::Unchanged || self.keyword_fields
+    }
+
+    /// Whether any of the options of `model_dump` for pydantic models is enabled
+    #[cfg(feature = "pydantic_support")]
+    pub(crate) fn dumps_pydantic(&self) -> bool {
+        self.pydantic_by_alias || self.pydantic_exclude_unset || self.pydantic_exclude_defaults
+    }
+
+    /// Names standing for the field `name` by [`Self::field_case`] and [`Self::keyword_fields`], other than `name` itself
+    pub(crate) fn field_aliases(&self, name: &'static str) -> Vec<Cow<'static, str>> {
+        let mut aliases = vec![self.field_case.convert(name)];
+        if self.keyword_fields {
+            aliases.push(self.field_case.convert(strip_keyword(name)));
+        }
+        aliases.retain(|alias| alias != name);
+        aliases.dedup();
+        aliases
+    }
+
+    /// Whether `key` of `dict` stands for the field `name`
+    pub(crate) fn is_field_key(&self, name: &str, key: &str) -> bool {
+        if self.field_case.convert(name) == key {
+            return true;
+        }
+        self.keyword_fields && self.field_case.convert(strip_keyword(name)) == key
+    }
+
+    /// Set how `...` and `NotImplemented` are deserialized into a type which accepts any value
+    ///
+    /// ```
+    /// use pyo3::Python;
+    /// use serde_pyobject::{from_pyobject_with, PyObjectDeserializerOptions, SingletonPolicy};
+    ///
+    /// Python::with_gil(|py| {
+    ///     let options = PyObjectDeserializerOptions::new().singletons(SingletonPolicy::None);
+    ///     let value: serde_json::Value = from_pyobject_with(py.Ellipsis().into_bound(py), &options).unwrap();
+    ///     assert_eq!(value, serde_json::Value::Null);
+    /// });
+    /// ```
+    pub fn singletons(mut self, policy: SingletonPolicy) -> Self {
+        self.singletons = policy;
+        self
+    }
+
+    /// Deserialize instances of a Python class into a variant of a Rust enum
+    ///
+    /// `enum_name` is the name of the enum which serde sees, i.e. the Rust name
+    /// unless it is renamed by `#[serde(rename = "...")]`.
+    /// An object of the enum is deserialized into the variant registered for the first class
+    /// which the object is an instance of, and the fields of the variant are taken from its attributes.
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use pyo3::{Python, ffi::c_str, types::{PyDict, PyAnyMethods}};
+    /// use serde_pyobject::{from_pyobject_with, PyObjectDeserializerOptions};
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// enum Shape {
+    ///     Circle { r: f64 },
+    ///     Square { a: f64 },
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let globals = PyDict::new(py);
+    ///     py.run(c_str!(r#"
+    /// from dataclasses import dataclass
+    ///
+    /// @dataclass
+    /// class Circle:
+    ///     r: float
+    ///
+    /// @dataclass
+    /// class Square:
+    ///     a: float
+    ///
+    /// shapes = [Circle(1.0), Square(2.0)]
+    /// "#), Some(&globals), None).unwrap();
+    ///
+    ///     let options = PyObjectDeserializerOptions::new()
+    ///         .class_variant("Shape", globals.get_item("Circle").unwrap().downcast().unwrap(), "Circle")
+    ///         .class_variant("Shape", globals.get_item("Square").unwrap().downcast().unwrap(), "Square");
+    ///     let shapes: Vec<Shape> = from_pyobject_with(globals.get_item("shapes").unwrap(), &options).unwrap();
+    ///     assert_eq!(shapes, vec![Shape::Circle { r: 1.0 }, Shape::Square { a: 2.0 }]);
+    /// });
+    /// ```
+    pub fn class_variant(
+        mut self,
+        enum_name: &'static str,
+        class: &Bound<'_, PyType>,
+        variant: &'static str,
+    ) -> Self {
+        self.class_variants.push(ClassVariant {
+            enum_name,
+            variant,
+            class: Arc::new(class.clone().unbind()),
+        });
+        self
+    }
+}
+
+/// Interned Python strings of struct field names, shared by the calls of a [`Codec`](crate::Codec)
+#[derive(Debug, Default)]
+pub(crate) struct FieldNames(Mutex<HashMap<&'static str, Py<PyString>>>);
+
+impl FieldNames {
+    /// Interned string of the field `name` in Python, which is created at the first call
+    pub(crate) fn get<'py>(
+        &self,
+        py: Python<'py>,
+        name: &'static str,
+        options: &PyObjectSerializerOptions,
+    ) -> Bound<'py, PyString> {
+        let mut names = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        names
+            .entry(name)
+            .or_insert_with(|| PyString::intern(py, &options.python_field_name(name)).unbind())
+            .bind(py)
+            .clone()
+    }
+}
+
+/// Keys of the fields of a struct as interned Python strings, in the declared order of the fields
+pub(crate) type StructPlan = Arc<[Py<PyString>]>;
+
+/// Plans of the structs deserialized by a [`Codec`](crate::Codec), which are made at the first use of each struct
+#[derive(Debug, Default)]
+pub(crate) struct StructPlans(Mutex<HashMap<(&'static str, &'static [&'static str]), StructPlan>>);
+
+impl StructPlans {
+    /// Plan of the struct `name` having `fields`
+    pub(crate) fn get(
+        &self,
+        py: Python<'_>,
+        name: &'static str,
+        fields: &'static [&'static str],
+    ) -> StructPlan {
+        let mut plans = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        plans
+            .entry((name, fields))
+            .or_insert_with(|| {
+                fields
+                    .iter()
+                    .map(|field| PyString::intern(py, field).unbind())
+                    .collect()
+            })
+            .clone()
+    }
+}
@@ -0,0 +1,232 @@
+//! Python [`pathlib.Path`](https://docs.python.org/3/library/pathlib.html) support
+//!
+//! `PathBuf` serializes itself as a string, and fails for paths which are not valid UTF-8.
+//! Use this module with `#[serde(with = "serde_pyobject::path")]` on a `PathBuf`, `&Path` or `OsString` field
+//! to serialize it into a `pathlib.Path` object, decoded by `os.fsdecode` so that any path on Unix survives the round trip,
+//! and to deserialize it back from `str`, `bytes` or any `os.PathLike` object through `os.fsencode`.
+//! [`PyObjectSerializerOptions::json_compatible`](crate::PyObjectSerializerOptions::json_compatible) gives the `str` instead of `pathlib.Path`.
+//! Other serde formats see the bytes of the path on Unix, and the string of the path elsewhere.
+//! Use [`option`] for `Option<T>` fields.
+//!
+//! `os.PathLike` objects are also accepted as their `os.fspath` by [`from_pyobject`](crate::from_pyobject),
+//! e.g. for `PathBuf` or `String` fields without this module.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//! use std::path::PathBuf;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "serde_pyobject::path")]
+//!     root: PathBuf,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let config = Config { root: PathBuf::from("/var/lib") };
+//!     let obj = to_pyobject(py, &config).unwrap();
+//!     let path = obj.get_item("root").unwrap();
+//!     assert_eq!(path.getattr("name").unwrap().to_string(), "lib");
+//!
+//!     let reverted: Config = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, config);
+//! });
+//! ```
+
+use crate::{error::Result, py_module_cache};
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyString},
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is the bytes of the path on Unix,
+/// and the string of the path elsewhere
+pub(crate) const TOKEN: &str = "$serde_pyobject::path";
+
+/// Serialize a path as `pathlib.Path`
+pub fn serialize<S, P>(value: &P, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    P: ?Sized + AsRef<Path>,
+{
+    serializer.serialize_newtype_struct(TOKEN, &Payload(value.as_ref()))
+}
+
+/// Deserialize a path from `str`, `bytes` or `os.PathLike`
+pub fn deserialize<'de, D, P>(deserializer: D) -> std::result::Result<P, D::Error>
+where
+    D: Deserializer<'de>,
+    P: From<PathBuf>,
+{
+    let path = deserializer.deserialize_newtype_struct(TOKEN, PathVisitor)?;
+    Ok(P::from(path))
+}
+
+/// `#[serde(with = "serde_pyobject::path::option")]` for `Option<T>` fields, where `None` is `None`
+pub mod option {
+    use super::*;
+
+    /// Serialize an optional path as `pathlib.Path` or `None`
+    pub fn serialize<S, P>(value: &Option<P>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        P: AsRef<Path>,
+    {
+        match value {
+            Some(value) => serializer.serialize_some(&Wrap(value.as_ref())),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize an optional path from `str`, `bytes`, `os.PathLike` or `None`
+    pub fn deserialize<'de, D, P>(deserializer: D) -> std::result::Result<Option<P>, D::Error>
+    where
+        D: Deserializer<'de>,
+        P: From<PathBuf>,
+    {
+        let value: Option<Owned> = Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|value| P::from(value.0)))
+    }
+
+    struct Wrap<'a>(&'a Path);
+
+    impl Serialize for Wrap<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            super::serialize(self.0, serializer)
+        }
+    }
+
+    struct Owned(PathBuf);
+
+    impl<'de> Deserialize<'de> for Owned {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            super::deserialize(deserializer).map(Owned)
+        }
+    }
+}
+
+/// The bytes of the path on Unix, and the string of the path elsewhere
+struct Payload<'a>(&'a Path);
+
+impl Serialize for Payload<'_> {
+    #[cfg(unix)]
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use std::os::unix::ffi::OsStrExt;
+        serializer.serialize_bytes(self.0.as_os_str().as_bytes())
+    }
+
+    #[cfg(not(unix))]
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.0.to_str() {
+            Some(path) => serializer.serialize_str(path),
+            None => Err(serde::ser::Error::custom(
+                "path contains invalid UTF-8 characters",
+            )),
+        }
+    }
+}
+
+struct PathVisitor;
+
+impl<'de> de::Visitor<'de> for PathVisitor {
+    type Value = PathBuf;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a path")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<PathBuf, E> {
+        Ok(PathBuf::from(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<PathBuf, E> {
+        Ok(PathBuf::from(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<PathBuf, E> {
+        self.visit_byte_buf(v.to_vec())
+    }
+
+    #[cfg(unix)]
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<PathBuf, E> {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+        Ok(PathBuf::from(OsString::from_vec(v)))
+    }
+
+    #[cfg(not(unix))]
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<PathBuf, E> {
+        String::from_utf8(v)
+            .map(PathBuf::from)
+            .map_err(|_| E::custom("path contains invalid UTF-8 characters"))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> std::result::Result<PathBuf, A::Error> {
+        // Bytes in a sequence of integers, e.g. from JSON
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_byte_buf(bytes)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<PathBuf, D::Error> {
+        deserializer.deserialize_byte_buf(self)
+    }
+}
+
+/// Create `pathlib.Path`, or `str` if `json_compatible`, from the serialized path
+pub(crate) fn to_py<'py>(
+    payload: Bound<'py, PyAny>,
+    json_compatible: bool,
+) -> Result<Bound<'py, PyAny>> {
+    let py = payload.py();
+    let path = match payload.downcast::<PyString>() {
+        Ok(path) => path.clone().into_any(),
+        // `bytearray` and lists of integers as well as `bytes` by `PyObjectSerializerOptions::bytes_repr`
+        Err(_) => {
+            let bytes: Vec<u8> = payload.extract()?;
+            let fsdecode = py_module_cache::get(py, "os", "fsdecode")?;
+            fsdecode.call1((PyBytes::new(py, &bytes),))?
+        }
+    };
+    if json_compatible {
+        return Ok(path);
+    }
+    Ok(py_module_cache::get(py, "pathlib", "Path")?.call1((path,))?)
+}
+
+/// The path of `obj` as `bytes` on Unix and `str` elsewhere, if it is `str`, `bytes` or `os.PathLike`
+pub(crate) fn from_py<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    let py = obj.py();
+    let is_path =
+        obj.is_instance_of::<PyString>() || obj.is_instance_of::<PyBytes>() || is_path_like(obj)?;
+    if !is_path {
+        return Ok(None);
+    }
+    let convert = if cfg!(unix) { "fsencode" } else { "fsdecode" };
+    Ok(Some(
+        py_module_cache::get(py, "os", convert)?.call1((obj,))?,
+    ))
+}
+
+/// Whether `obj` implements `os.PathLike`, e.g. `pathlib.Path`
+pub(crate) fn is_path_like(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    match py_module_cache::get_imported(obj.py(), "os", "PathLike")? {
+        Some(class) => obj.is_instance(&class),
+        None => Ok(false),
+    }
+}
@@ -0,0 +1,83 @@
+//! Cache of Python objects imported from modules, e.g. `json.dumps`
+
+use pyo3::prelude::*;
+use std::{collections::HashMap, sync::Mutex};
+
+/// `(module, name) -> object`
+type Cache = HashMap<(&'static str, &'static str), Py<PyAny>>;
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+/// Get `module.name`, importing the module at the first call
+pub(crate) fn get<'py>(
+    py: Python<'py>,
+    module: &'static str,
+    name: &'static str,
+) -> PyResult<Bound<'py, PyAny>> {
+    if let Some(obj) = lookup(py, module, name) {
+        return Ok(obj);
+    }
+    // Import without holding the lock since importing may run arbitrary Python code
+    let obj = py.import(module)?.getattr(name)?;
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get_or_insert_with(HashMap::new)
+        .insert((module, name), obj.clone().unbind());
+    Ok(obj)
+}
+
+/// Get `module.name` only if the module is imported already, e.g. `numpy.ndarray` or `pandas.DataFrame`
+///
+/// Objects of a module, whether third-party or standard, exist only after the module is imported,
+/// so this detects them without the cost of importing, or failing to import, the module.
+pub(crate) fn get_imported<'py>(
+    py: Python<'py>,
+    module: &'static str,
+    name: &'static str,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    if let Some(obj) = lookup(py, module, name) {
+        return Ok(Some(obj));
+    }
+    // `sys.modules` itself is cached, and looked up without raising `KeyError` on a miss
+    let modules = get(py, "sys", "modules")?;
+    let Some(imported) = modules
+        .downcast::<pyo3::types::PyDict>()?
+        .get_item(module)?
+    else {
+        return Ok(None);
+    };
+    let obj = imported.getattr(name)?;
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .get_or_insert_with(HashMap::new)
+        .insert((module, name), obj.clone().unbind());
+    Ok(Some(obj))
+}
+
+/// Clear the objects which this crate has imported from Python modules or created
+///
+/// The objects, e.g. `json.dumps` used by [`MapKeyEncoder::JsonDumps`](crate::MapKeyEncoder::JsonDumps)
+/// or `array.array`, are imported at the first use and kept afterwards.
+/// Classes created by `create_dataclass`, or for `PyObjectSerializerOptions::struct_output`
+/// and `PyObjectSerializerOptions::python_enum`, are kept as well.
+/// Call this function after reloading such modules, or before finalizing the interpreter,
+/// so that they are imported again at the next use.
+pub fn clear_caches() {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    // Release Python references outside of the lock
+    drop(cache);
+    crate::enum_class::clear();
+    crate::struct_class::clear();
+    #[cfg(feature = "dataclass_support")]
+    crate::dataclass::clear();
+}
+
+fn lookup<'py>(
+    py: Python<'py>,
+    module: &'static str,
+    name: &'static str,
+) -> Option<Bound<'py, PyAny>> {
+    let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let obj = cache.as_ref()?.get(&(module, name))?;
+    Some(obj.bind(py).clone())
+}
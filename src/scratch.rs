@@ -0,0 +1,112 @@
+//! Thread-local scratch buffers reused across calls
+//!
+//! A buffer is taken from the pool of the current thread, and returned to it when dropped,
+//! cleared but keeping its capacity, so that repeated calls do not allocate again.
+
+use pyo3::prelude::*;
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+};
+
+/// Buffers larger than this are freed instead of being kept in the pool
+const MAX_RETAINED_CAPACITY: usize = 1 << 20;
+
+/// Number of buffers kept in the pool of each thread
+const MAX_POOLED: usize = 8;
+
+thread_local! {
+    static STRINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static BYTES: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    static OBJECTS: RefCell<Vec<Vec<Py<PyAny>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Buffer types which can be kept in the pool
+pub(crate) trait Reusable: Default {
+    /// Run `f` with the pool of the current thread, unless it is unavailable
+    /// while the thread is being torn down
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Self>) -> R) -> Option<R>;
+    fn clear(&mut self);
+    fn capacity(&self) -> usize;
+}
+
+impl Reusable for String {
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Self>) -> R) -> Option<R> {
+        STRINGS.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+    }
+    fn clear(&mut self) {
+        String::clear(self)
+    }
+    fn capacity(&self) -> usize {
+        String::capacity(self)
+    }
+}
+
+impl Reusable for Vec<u8> {
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Self>) -> R) -> Option<R> {
+        BYTES.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+    }
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+
+/// Elements of a sequence or a tuple being serialized
+///
+/// Buffers are cleared, releasing the references to the objects, while the GIL is held by the serializer.
+impl Reusable for Vec<Py<PyAny>> {
+    fn with_pool<R>(f: impl FnOnce(&mut Vec<Self>) -> R) -> Option<R> {
+        OBJECTS.try_with(|pool| f(&mut pool.borrow_mut())).ok()
+    }
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+
+/// Empty buffer taken from the pool, which goes back to the pool when dropped
+#[derive(Debug, Default)]
+pub(crate) struct Scratch<T: Reusable>(T);
+
+impl<T: Reusable> Scratch<T> {
+    pub(crate) fn take() -> Self {
+        Scratch(
+            T::with_pool(|pool| pool.pop())
+                .flatten()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl<T: Reusable> Deref for Scratch<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Reusable> DerefMut for Scratch<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Reusable> Drop for Scratch<T> {
+    fn drop(&mut self) {
+        let mut buffer = std::mem::take(&mut self.0);
+        if buffer.capacity() == 0 || buffer.capacity() > MAX_RETAINED_CAPACITY {
+            return;
+        }
+        buffer.clear();
+        T::with_pool(|pool| {
+            if pool.len() < MAX_POOLED {
+                pool.push(buffer);
+            }
+        });
+    }
+}
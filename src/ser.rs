@@ -1,6 +1,33 @@
-use crate::error::{Error, Result};
-use pyo3::{prelude::*, types::*, IntoPyObjectExt};
+use crate::{
+    array, complex, datetime, decimal,
+    error::{Error, Result},
+    fraction, ipaddress,
+    options::{
+        BytesRepr, DuplicateKeyPolicy, FieldOverrideFn, NonFinitePolicy, PyObjectSerializerOptions,
+        SortKeys, StructOutput,
+    },
+    path, py_module_cache,
+    scratch::Scratch,
+    set,
+    singleton::{ELLIPSIS_TOKEN, NOT_IMPLEMENTED_TOKEN},
+    struct_class, SIGNAL_CHECK_INTERVAL, TOKEN_NAMESPACE,
+};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    intern,
+    prelude::*,
+    types::*,
+    IntoPyObjectExt,
+};
 use serde::{ser, Serialize};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt::{self, Write},
+    rc::Rc,
+    sync::Arc,
+};
 
 /// Serialize `T: Serialize` into a [`pyo3::PyAny`] value.
 ///
@@ -220,6 +247,9 @@ use serde::{ser, Serialize};
 ///
 /// ## map
 ///
+/// Keys serialized into `list` of `int` in `range(256)`, e.g. `Vec<u8>`, become `bytes`
+/// since `list` cannot be a key of `dict`, unless [`PyObjectSerializerOptions::map_key_encoder`] is set.
+///
 /// ```
 /// use pyo3::{Python, types::PyAnyMethods};
 /// use serde_pyobject::{to_pyobject, pydict};
@@ -289,12 +319,528 @@ pub fn to_pyobject<'py, T>(py: Python<'py>, value: &T) -> Result<Bound<'py, PyAn
 where
     T: Serialize + ?Sized,
 {
-    let serializer = PyAnySerializer { py };
+    to_pyobject_with(py, value, &PyObjectSerializerOptions::default())
+}
+
+/// Serialize `T: Serialize` into a [`pyo3::PyAny`] value with [`PyObjectSerializerOptions`].
+///
+/// [`to_pyobject`] is this function with the default options.
+///
+/// # Examples
+///
+/// ## default options
+///
+/// ```
+/// use serde::Serialize;
+/// use pyo3::{Python, types::PyAnyMethods};
+/// use serde_pyobject::{to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+///
+/// #[derive(Serialize)]
+/// struct A {
+///     a: u32,
+///     b: Vec<&'static str>,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let a = A { a: 1, b: vec!["x"] };
+///     let options = PyObjectSerializerOptions::new();
+///     let obj = to_pyobject_with(py, &a, &options).unwrap();
+///     assert!(obj.eq(to_pyobject(py, &a).unwrap()).unwrap());
+/// });
+/// ```
+///
+/// ## duplicate keys
+///
+/// ```
+/// use serde::{Serialize, Serializer};
+/// use pyo3::{Python, types::PyAnyMethods};
+/// use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions, DuplicateKeyPolicy};
+///
+/// // A map which may contain the same key twice
+/// struct Pairs(Vec<(&'static str, i32)>);
+///
+/// impl Serialize for Pairs {
+///     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+///         serializer.collect_map(self.0.iter().cloned())
+///     }
+/// }
+///
+/// Python::with_gil(|py| {
+///     let pairs = Pairs(vec![("a", 1), ("a", 2)]);
+///
+///     let options = PyObjectSerializerOptions::new().duplicate_keys(DuplicateKeyPolicy::FirstWins);
+///     let obj = to_pyobject_with(py, &pairs, &options).unwrap();
+///     assert_eq!(obj.get_item("a").unwrap().extract::<i32>().unwrap(), 1);
+///
+///     let options = PyObjectSerializerOptions::new().duplicate_keys(DuplicateKeyPolicy::Error);
+///     let err = to_pyobject_with(py, &pairs, &options).unwrap_err();
+///     assert_eq!(err.to_string(), "RuntimeError: duplicate key 'a' in map at $");
+/// });
+/// ```
+pub fn to_pyobject_with<'py, T>(
+    py: Python<'py>,
+    value: &T,
+    options: &PyObjectSerializerOptions,
+) -> Result<Bound<'py, PyAny>>
+where
+    T: Serialize + ?Sized,
+{
+    let serializer = PyAnySerializer::new(py, options);
     value.serialize(serializer)
 }
 
+/// Serialize a Rust struct into an instance of the Python class `cls`
+///
+/// The struct is serialized into `dict` as [`to_pyobject`] does, and the instance is created
+/// by `cls(**dict)`. If `cls` does not define `__init__`, the instance is created by `cls()`
+/// and the fields are set as its attributes instead.
+/// Values which are not serialized into `dict` raise `TypeError`.
+///
+/// ```
+/// use serde::Serialize;
+/// use pyo3::{prelude::*, ffi::c_str, types::{PyDict, PyType}};
+/// use serde_pyobject::to_py_instance;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let globals = PyDict::new(py);
+///     py.run(c_str!(r#"
+/// class User:
+///     def __init__(self, name, age):
+///         self.name = name
+///         self.age = age
+///
+///     def greet(self):
+///         return f"Hello, {self.name}"
+/// "#), Some(&globals), None).unwrap();
+///     let cls = globals.get_item("User").unwrap().unwrap();
+///
+///     let user = User { name: "alice".to_string(), age: 20 };
+///     let obj = to_py_instance(py, &user, cls.downcast::<PyType>().unwrap()).unwrap();
+///     assert_eq!(obj.call_method0("greet").unwrap().extract::<String>().unwrap(), "Hello, alice");
+/// });
+/// ```
+pub fn to_py_instance<'py, T>(
+    py: Python<'py>,
+    value: &T,
+    cls: &Bound<'py, PyType>,
+) -> Result<Bound<'py, PyAny>>
+where
+    T: Serialize + ?Sized,
+{
+    let obj = to_pyobject(py, value)?;
+    let Ok(fields) = obj.downcast::<PyDict>() else {
+        return Err(PyTypeError::new_err(format!(
+            "'{}' object cannot be the fields of '{}'",
+            obj.get_type().name()?,
+            cls.name()?
+        ))
+        .into());
+    };
+    let init = cls.getattr(intern!(py, "__init__"))?;
+    let object_init = py.get_type::<PyAny>().getattr(intern!(py, "__init__"))?;
+    if !init.is(&object_init) {
+        return Ok(cls.call((), Some(fields))?);
+    }
+    let instance = cls.call0()?;
+    for (key, value) in fields {
+        instance.setattr(key.downcast::<PyString>()?, value)?;
+    }
+    Ok(instance)
+}
+
+/// Construct and validate a pydantic model `model_cls` from a Rust value
+///
+/// The value is serialized as [`to_pyobject`] does, and passed to `model_cls.model_validate`,
+/// or `model_cls.parse_obj` for pydantic v1 models, so that the validation and coercion
+/// of the model apply. Validation errors are raised as `pydantic.ValidationError`.
+/// This requires the `pydantic_support` feature.
+///
+/// ```
+/// use serde::Serialize;
+/// use pyo3::{prelude::*, ffi::c_str, types::{PyDict, PyType}};
+/// use serde_pyobject::to_pydantic;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// Python::with_gil(|py| {
+/// #   // pydantic is not a dependency of this crate
+/// #   if py.import("pydantic").is_err() {
+/// #       return;
+/// #   }
+///     let globals = PyDict::new(py);
+///     py.run(c_str!(r#"
+/// from pydantic import BaseModel
+///
+/// class User(BaseModel):
+///     name: str
+///     age: int
+/// "#), Some(&globals), None).unwrap();
+///     let cls = globals.get_item("User").unwrap().unwrap();
+///
+///     let user = User { name: "alice".to_string(), age: 20 };
+///     let model = to_pydantic(py, &user, cls.downcast::<PyType>().unwrap()).unwrap();
+///     assert_eq!(model.getattr("age").unwrap().extract::<u32>().unwrap(), 20);
+/// });
+/// ```
+#[cfg(feature = "pydantic_support")]
+pub fn to_pydantic<'py, T>(
+    py: Python<'py>,
+    value: &T,
+    model_cls: &Bound<'py, PyType>,
+) -> Result<Bound<'py, PyAny>>
+where
+    T: Serialize + ?Sized,
+{
+    let obj = to_pyobject(py, value)?;
+    if model_cls.hasattr(intern!(py, "model_validate"))? {
+        return Ok(model_cls.call_method1(intern!(py, "model_validate"), (obj,))?);
+    }
+    if model_cls.hasattr(intern!(py, "parse_obj"))? {
+        return Ok(model_cls.call_method1(intern!(py, "parse_obj"), (obj,))?);
+    }
+    Err(PyTypeError::new_err(format!(
+        "'{}' is not a pydantic model, which has model_validate or parse_obj",
+        model_cls.name()?
+    ))
+    .into())
+}
+
+/// A segment of the path from the root value to the value being serialized
+enum PathSegment<'py> {
+    Field(&'static str),
+    Index(usize),
+    Key(Bound<'py, PyAny>),
+}
+
+/// State shared by all serializers during a single [`to_pyobject_with`] call
+struct Context<'py> {
+    options: PyObjectSerializerOptions,
+    /// Path to the current value, recorded only if [`Self::tracks_path`]
+    path: RefCell<Vec<PathSegment<'py>>>,
+    /// Whether any option reads the path, e.g. in errors or hooks
+    tracks_path: bool,
+    /// Number of the values enclosing the current value
+    depth: Cell<usize>,
+    /// Strings created so far, used when [`PyObjectSerializerOptions::intern_strings`] is enabled
+    strings: RefCell<HashMap<String, Bound<'py, PyString>>>,
+    /// Number of nodes serialized so far
+    nodes: Cell<usize>,
+    /// Buffer reused by `collect_str` to format values
+    buffer: RefCell<Scratch<String>>,
+}
+
+impl<'py> Context<'py> {
+    fn new(options: &PyObjectSerializerOptions) -> Self {
+        let tracks_path = options.duplicate_keys == DuplicateKeyPolicy::Error
+            || options.non_finite_floats == NonFinitePolicy::Error
+            || options.post_hook.is_some()
+            || !options.field_overrides.is_empty();
+        Context {
+            options: options.clone(),
+            path: RefCell::new(Vec::new()),
+            tracks_path,
+            depth: Cell::new(0),
+            strings: RefCell::new(HashMap::new()),
+            nodes: Cell::new(0),
+            buffer: RefCell::new(Scratch::take()),
+        }
+    }
+
+    /// Enter a child of the current value, whose path segment is created only if the path is tracked
+    fn enter(&self, segment: impl FnOnce() -> PathSegment<'py>) {
+        self.depth.set(self.depth.get() + 1);
+        if self.tracks_path {
+            self.path.borrow_mut().push(segment());
+        }
+    }
+
+    /// Leave the child entered by [`Self::enter`]
+    fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+        if self.tracks_path {
+            self.path.borrow_mut().pop();
+        }
+    }
+
+    /// Render the current path like `$.a[0]['key']`
+    fn path(&self) -> String {
+        let mut out = "$".to_string();
+        for segment in self.path.borrow().iter() {
+            match segment {
+                PathSegment::Field(name) => {
+                    out.push('.');
+                    out.push_str(name);
+                }
+                PathSegment::Index(index) => out += &format!("[{}]", index),
+                PathSegment::Key(key) => match key.repr() {
+                    Ok(repr) => out += &format!("[{}]", repr),
+                    Err(_) => out += "[?]",
+                },
+            }
+        }
+        out
+    }
+
+    /// Function of [`PyObjectSerializerOptions::field_override`] whose path matches the current one
+    fn field_override(&self) -> Option<Arc<FieldOverrideFn>> {
+        let path = self.path.borrow();
+        if matches!(path.last(), None | Some(PathSegment::Index(_))) {
+            return None;
+        }
+        let names: Vec<Cow<str>> = path
+            .iter()
+            .filter_map(|segment| match segment {
+                PathSegment::Field(name) => Some(Cow::Borrowed(*name)),
+                PathSegment::Index(_) => None,
+                PathSegment::Key(key) => Some(match key.str() {
+                    Ok(key) => Cow::Owned(key.to_string()),
+                    Err(_) => Cow::Borrowed("?"),
+                }),
+            })
+            .collect();
+        self.options
+            .field_overrides
+            .iter()
+            .find(|entry| entry.path.split('.').eq(names.iter().map(|name| &**name)))
+            .map(|entry| entry.convert.clone())
+    }
+}
+
+#[derive(Clone)]
 pub struct PyAnySerializer<'py> {
     py: Python<'py>,
+    ctx: Rc<Context<'py>>,
+}
+
+impl<'py> PyAnySerializer<'py> {
+    pub(crate) fn new(py: Python<'py>, options: &PyObjectSerializerOptions) -> Self {
+        PyAnySerializer {
+            py,
+            ctx: Rc::new(Context::new(options)),
+        }
+    }
+
+    pub(crate) fn py(&self) -> Python<'py> {
+        self.py
+    }
+
+    /// Create a Python string, sharing the same object if interning is enabled
+    fn string(&self, v: &str) -> Bound<'py, PyString> {
+        if !self.ctx.options.intern_strings {
+            return PyString::new(self.py, v);
+        }
+        let mut strings = self.ctx.strings.borrow_mut();
+        if let Some(s) = strings.get(v) {
+            return s.clone();
+        }
+        let s = PyString::new(self.py, v);
+        strings.insert(v.to_owned(), s.clone());
+        s
+    }
+
+    /// Create a Python string of a struct field name, which a [`Codec`](crate::Codec) keeps across calls
+    fn field_name(&self, name: &'static str) -> Bound<'py, PyString> {
+        let options = &self.ctx.options;
+        match &options.field_names {
+            Some(names) => names.get(self.py, name, options),
+            None => self.string(&options.python_field_name(name)),
+        }
+    }
+
+    /// Count a serialized node, and check Python signals periodically
+    pub(crate) fn count_node(&self) -> Result<()> {
+        let nodes = self.ctx.nodes.get().wrapping_add(1);
+        self.ctx.nodes.set(nodes);
+        if nodes & (SIGNAL_CHECK_INTERVAL - 1) == 0 {
+            self.py.check_signals()?;
+        }
+        Ok(())
+    }
+
+    /// Python class registered for the variant by [`PyObjectSerializerOptions::class_variant`]
+    fn variant_class(&self, name: &str, variant: &str) -> Option<Bound<'py, PyType>> {
+        self.ctx
+            .options
+            .class_variants
+            .iter()
+            .find(|entry| entry.enum_name == name && entry.variant == variant)
+            .map(|entry| entry.class.bind(self.py).clone())
+    }
+
+    /// Whether the value of an entry is omitted by [`PyObjectSerializerOptions::skip_none_fields`]
+    fn skips(&self, value: &Bound<'py, PyAny>) -> bool {
+        self.ctx.options.skip_none_fields && value.is_none()
+    }
+
+    /// Whether the value of a field is omitted, which keeps the positions of [`StructOutput::Tuple`]
+    fn skips_field(&self, value: &Bound<'py, PyAny>) -> bool {
+        self.ctx.options.struct_output != StructOutput::Tuple && self.skips(value)
+    }
+
+    /// `dict` with the keys sorted following [`PyObjectSerializerOptions::sort_keys`]
+    fn sort_keys(&self, dict: Bound<'py, PyDict>) -> Result<Bound<'py, PyDict>> {
+        let sort = match self.ctx.options.sort_keys {
+            SortKeys::Unsorted => false,
+            // The path is empty while the root value, or the fields of the root variant, are completed
+            SortKeys::TopLevel => self.ctx.depth.get() == 0,
+            SortKeys::Recursive => true,
+        };
+        if !sort || dict.len() < 2 {
+            return Ok(dict);
+        }
+        let keys = dict.keys();
+        keys.sort()?;
+        let sorted = PyDict::new(self.py);
+        for key in keys {
+            let value = dict
+                .get_item(&key)?
+                .expect("key is taken from the same dict");
+            sorted.set_item(key, value)?;
+        }
+        Ok(sorted)
+    }
+
+    /// `list` of the elements, or `tuple` if [`PyObjectSerializerOptions::immutable`] is enabled
+    fn list(&self, elements: &[Py<PyAny>]) -> Result<Bound<'py, PyAny>> {
+        if self.ctx.options.immutable {
+            return Ok(PyTuple::new(self.py, elements)?.into_any());
+        }
+        Ok(PyList::new(self.py, elements)?.into_any())
+    }
+
+    /// `tuple` of the elements, or `list` if [`PyObjectSerializerOptions::json_compatible`] is enabled
+    fn tuple(&self, elements: &[Py<PyAny>]) -> Result<Bound<'py, PyAny>> {
+        if self.ctx.options.json_compatible && !self.ctx.options.immutable {
+            return Ok(PyList::new(self.py, elements)?.into_any());
+        }
+        Ok(PyTuple::new(self.py, elements)?.into_any())
+    }
+
+    /// Key of `dict` stringified as `serde_json` does for keys of JSON objects
+    fn json_key(&self, key: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+        if key.is_instance_of::<PyString>() {
+            return Ok(key);
+        }
+        let s = if let Ok(b) = key.downcast::<PyBool>() {
+            if b.is_true() { "true" } else { "false" }.to_string()
+        } else if key.is_instance_of::<PyInt>() {
+            key.str()?.to_string()
+        } else if let Ok(f) = key.downcast::<PyFloat>() {
+            let f = f.value();
+            if !f.is_finite() {
+                return Err(ser::Error::custom(
+                    "float key must be finite (got NaN or +/-inf)",
+                ));
+            }
+            format!("{:?}", f)
+        } else {
+            return Err(ser::Error::custom("key must be a string"));
+        };
+        Ok(self.string(&s).into_any())
+    }
+
+    /// `dict` as it is, or wrapped in `types.MappingProxyType` if [`PyObjectSerializerOptions::immutable`] is enabled
+    fn mapping(&self, dict: Bound<'py, PyDict>) -> Result<Bound<'py, PyAny>> {
+        if !self.ctx.options.immutable {
+            return Ok(dict.into_any());
+        }
+        let proxy = py_module_cache::get(self.py, "types", "MappingProxyType")?;
+        Ok(proxy.call1((dict,))?)
+    }
+
+    /// `{name: value}` if [`PyObjectSerializerOptions::wrap_type_names`] is enabled, or `value` as it is
+    fn wrap_type_name(
+        &self,
+        name: &'static str,
+        value: Bound<'py, PyAny>,
+    ) -> Result<Bound<'py, PyAny>> {
+        if !self.ctx.options.wrap_type_names {
+            return Ok(value);
+        }
+        let dict = PyDict::new(self.py);
+        dict.set_item(self.string(name), value)?;
+        self.mapping(dict)
+    }
+
+    /// Fields of a struct or a struct variant as `dict`, or as `types.SimpleNamespace`
+    /// or `tuple` following [`StructOutput`]
+    fn struct_fields(&self, fields: Fields<'py>) -> Result<Bound<'py, PyAny>> {
+        let fields = match fields {
+            Fields::Positional(values) => return self.tuple(&values),
+            Fields::Keyed(fields) => fields,
+        };
+        match self.ctx.options.struct_output {
+            StructOutput::Namespace => {
+                let namespace = py_module_cache::get(self.py, "types", "SimpleNamespace")?;
+                Ok(namespace.call((), Some(&self.sort_keys(fields)?))?)
+            }
+            _ => self.mapping(self.sort_keys(fields)?),
+        }
+    }
+
+    /// Serialize a child value with the path segment pushed
+    fn serialize_child<T>(
+        &self,
+        segment: impl FnOnce() -> PathSegment<'py>,
+        value: &T,
+    ) -> Result<Bound<'py, PyAny>>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.count_node()?;
+        self.ctx.enter(segment);
+        let out = value.serialize(self.clone()).and_then(|mut obj| {
+            if !self.ctx.options.field_overrides.is_empty() {
+                if let Some(convert) = self.ctx.field_override() {
+                    obj = convert(obj)?;
+                }
+            }
+            match &self.ctx.options.post_hook {
+                Some(hook) => Ok((hook.0)(obj, &self.ctx.path())?),
+                None => Ok(obj),
+            }
+        });
+        self.ctx.leave();
+        out
+    }
+
+    /// Insert an item into a dict following [`DuplicateKeyPolicy`]
+    fn set_item(
+        &self,
+        dict: &Bound<'py, PyDict>,
+        key: Bound<'py, PyAny>,
+        value: Bound<'py, PyAny>,
+    ) -> Result<()> {
+        match self.ctx.options.duplicate_keys {
+            DuplicateKeyPolicy::Overwrite => {}
+            DuplicateKeyPolicy::FirstWins => {
+                if dict.contains(&key)? {
+                    return Ok(());
+                }
+            }
+            DuplicateKeyPolicy::Error => {
+                if dict.contains(&key)? {
+                    return Err(ser::Error::custom(format_args!(
+                        "duplicate key {} in map at {}",
+                        key.repr()?,
+                        self.ctx.path()
+                    )));
+                }
+            }
+        }
+        dict.set_item(key, value)?;
+        Ok(())
+    }
 }
 
 macro_rules! serialize_impl {
@@ -323,15 +869,85 @@ impl<'py> ser::Serializer for PyAnySerializer<'py> {
     serialize_impl!(serialize_i16, i16);
     serialize_impl!(serialize_i32, i32);
     serialize_impl!(serialize_i64, i64);
+    serialize_impl!(serialize_i128, i128);
     serialize_impl!(serialize_u8, u8);
     serialize_impl!(serialize_u16, u16);
     serialize_impl!(serialize_u32, u32);
     serialize_impl!(serialize_u64, u64);
-    serialize_impl!(serialize_f32, f32);
-    serialize_impl!(serialize_f64, f64);
+    serialize_impl!(serialize_u128, u128);
     serialize_impl!(serialize_char, char);
-    serialize_impl!(serialize_str, &str);
-    serialize_impl!(serialize_bytes, &[u8]);
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        if self.ctx.options.json_compatible && v.is_finite() {
+            // `serde_json` writes the shortest representation of `f32`, e.g. `0.1` instead of `0.10000000149011612`
+            let v: f64 = v
+                .to_string()
+                .parse()
+                .expect("f32 is formatted as a valid f64");
+            return self.serialize_f64(v);
+        }
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        if !v.is_finite() {
+            match self.ctx.options.non_finite_floats {
+                NonFinitePolicy::Error => {
+                    return Err(PyValueError::new_err(format!(
+                        "non-finite float {} at {}",
+                        v,
+                        self.ctx.path()
+                    ))
+                    .into());
+                }
+                NonFinitePolicy::None => return Ok(self.py.None().into_bound(self.py)),
+                NonFinitePolicy::Allow if self.ctx.options.json_compatible => {
+                    return Ok(self.py.None().into_bound(self.py));
+                }
+                NonFinitePolicy::Allow => {}
+            }
+        }
+        Ok(v.into_bound_py_any(self.py)?)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        let repr = if self.ctx.options.json_compatible {
+            BytesRepr::List
+        } else {
+            self.ctx.options.bytes_repr
+        };
+        match repr {
+            BytesRepr::ByteArray if !self.ctx.options.immutable => {
+                Ok(PyByteArray::new(self.py, v).into_any())
+            }
+            BytesRepr::Bytes | BytesRepr::ByteArray => Ok(PyBytes::new(self.py, v).into_any()),
+            BytesRepr::List => {
+                let mut ints = Scratch::<Vec<Py<PyAny>>>::take();
+                for b in v {
+                    ints.push(b.into_py_any(self.py)?);
+                }
+                self.list(&ints)
+            }
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(self.string(v).into_any())
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + fmt::Display,
+    {
+        // Format into the shared buffer, which is reused across calls as well,
+        // instead of allocating a `String` for each value
+        let mut buffer = self.ctx.buffer.take();
+        buffer.clear();
+        let result = write!(buffer, "{}", value);
+        let out = result.map(|_| self.string(&buffer).into_any());
+        self.ctx.buffer.replace(buffer);
+        out.map_err(|_| ser::Error::custom("a Display implementation returned an error"))
+    }
 
     fn serialize_none(self) -> Result<Self::Ok> {
         Ok(self.py.None().into_bound(self.py))
@@ -345,32 +961,102 @@ impl<'py> ser::Serializer for PyAnySerializer<'py> {
     }
 
     fn serialize_unit(self) -> Result<Self::Ok> {
+        if self.ctx.options.json_compatible {
+            return Ok(self.py.None().into_bound(self.py));
+        }
         Ok(PyTuple::empty(self.py).into_any())
     }
 
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok> {
+        if self.ctx.options.json_compatible {
+            return Ok(self.py.None().into_bound(self.py));
+        }
+        match name {
+            ELLIPSIS_TOKEN => return Ok(self.py.Ellipsis().into_bound(self.py)),
+            NOT_IMPLEMENTED_TOKEN => return Ok(self.py.NotImplemented().into_bound(self.py)),
+            _ => {}
+        }
         Ok(PyTuple::empty(self.py).into_any())
     }
 
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok> {
-        Ok(PyString::new(self.py, variant).into_any())
+        if let Some(class) = self.variant_class(name, variant) {
+            return Ok(class.call0()?);
+        }
+        if let Some(python_enum) = self
+            .ctx
+            .options
+            .python_enums
+            .iter()
+            .find(|entry| entry.name == name)
+        {
+            return python_enum.member(self.py, variant);
+        }
+        self.wrap_type_name(name, self.string(variant).into_any())
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        if let Some(typecode) = name.strip_prefix(array::TOKEN_PREFIX) {
+            if !self.ctx.options.json_compatible && !self.ctx.options.immutable {
+                return array::to_array(self.py, typecode, value);
+            }
+        }
+        if name == set::TOKEN && !self.ctx.options.json_compatible {
+            let frozen = self.ctx.options.immutable;
+            return set::to_set(self, frozen, value);
+        }
+        if name == complex::TOKEN && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return complex::to_py(payload);
+        }
+        if datetime::is_token(name) && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return datetime::to_py(name, payload);
+        }
+        if name == decimal::TOKEN && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return decimal::to_py(payload);
+        }
+        if name == fraction::TOKEN && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return fraction::to_py(payload);
+        }
+        if name == ipaddress::TOKEN && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return ipaddress::to_py(payload);
+        }
+        if name == path::TOKEN {
+            let json_compatible = self.ctx.options.json_compatible;
+            let payload = value.serialize(self)?;
+            return path::to_py(payload, json_compatible);
+        }
+        #[cfg(feature = "uuid")]
+        if name == crate::uuid::TOKEN && !self.ctx.options.json_compatible {
+            let payload = value.serialize(self)?;
+            return crate::uuid::to_py(payload);
+        }
+        if name.starts_with(TOKEN_NAMESPACE) {
+            return value.serialize(self);
+        }
+        #[cfg(feature = "erased-serde")]
+        if let Some(adapter) = self.ctx.options.type_adapter_of(name) {
+            return Ok(adapter(self.py, &Erased(value))?);
+        }
+        let ser = self.clone();
+        ser.wrap_type_name(name, value.serialize(self)?)
     }
 
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         value: &T,
@@ -378,22 +1064,30 @@ impl<'py> ser::Serializer for PyAnySerializer<'py> {
     where
         T: ?Sized + Serialize,
     {
-        let dict = PyDict::new(self.py).into_any();
-        dict.set_item(variant, value.serialize(self)?)?;
-        Ok(dict)
+        let value = self.serialize_child(|| PathSegment::Field(variant), value)?;
+        if let Some(class) = self.variant_class(name, variant) {
+            // A struct in the variant gives the keyword arguments
+            return Ok(match value.downcast::<PyDict>() {
+                Ok(kwargs) => class.call((), Some(kwargs))?,
+                Err(_) => class.call1((value,))?,
+            });
+        }
+        let dict = PyDict::new(self.py);
+        dict.set_item(variant, value)?;
+        self.mapping(dict)
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
         Ok(Seq {
-            py: self.py,
-            seq: Vec::new(),
+            ser: self,
+            seq: Scratch::take(),
         })
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
         Ok(Seq {
-            py: self.py,
-            seq: Vec::new(),
+            ser: self,
+            seq: Scratch::take(),
         })
     }
 
@@ -403,58 +1097,101 @@ impl<'py> ser::Serializer for PyAnySerializer<'py> {
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
         Ok(TupleStruct {
-            py: self.py,
-            fields: Vec::new(),
+            ser: self,
+            fields: Scratch::take(),
         })
     }
 
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
         Ok(TupleVariant {
-            py: self.py,
+            ser: self,
+            name,
             variant,
-            fields: Vec::new(),
+            fields: Scratch::take(),
         })
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
         Ok(Map {
-            py: self.py,
             map: PyDict::new(self.py),
+            ser: self,
             key: None,
         })
     }
 
-    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        let class = match self.ctx.options.struct_output {
+            StructOutput::Dict | StructOutput::Namespace | StructOutput::Tuple => None,
+            _ => Some((name, Vec::with_capacity(len))),
+        };
+        let fields = match self.ctx.options.struct_output {
+            StructOutput::Tuple => Fields::Positional(Scratch::take()),
+            _ => Fields::Keyed(PyDict::new(self.py)),
+        };
         Ok(Struct {
-            py: self.py,
-            fields: PyDict::new(self.py),
+            fields,
+            ser: self,
+            name,
+            class,
         })
     }
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
+        // Classes of variants take the fields by their names
+        let fields = match self.ctx.options.struct_output {
+            StructOutput::Tuple if self.variant_class(name, variant).is_none() => {
+                Fields::Positional(Scratch::take())
+            }
+            _ => Fields::Keyed(PyDict::new(self.py)),
+        };
         Ok(StructVariant {
-            py: self.py,
+            fields,
+            ser: self,
+            name,
             variant,
-            fields: PyDict::new(self.py),
         })
     }
 }
 
+/// Sized reference to a value, which coerces into `&dyn erased_serde::Serialize` for type adapters
+#[cfg(feature = "erased-serde")]
+struct Erased<'a, T: ?Sized>(&'a T);
+
+#[cfg(feature = "erased-serde")]
+impl<T: ?Sized + Serialize> Serialize for Erased<'_, T> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// `bytes` of a `list` whose elements are all `int` in `range(256)`
+fn byte_list<'py>(obj: &Bound<'py, PyAny>) -> Option<Bound<'py, PyBytes>> {
+    let list = obj.downcast::<PyList>().ok()?;
+    let mut bytes = Vec::with_capacity(list.len());
+    for item in list {
+        if !item.is_exact_instance_of::<PyInt>() {
+            return None;
+        }
+        bytes.push(item.extract::<u8>().ok()?);
+    }
+    Some(PyBytes::new(obj.py(), &bytes))
+}
+
 pub struct Seq<'py> {
-    py: Python<'py>,
-    seq: Vec<Bound<'py, PyAny>>,
+    ser: PyAnySerializer<'py>,
+    seq: Scratch<Vec<Py<PyAny>>>,
 }
 
 impl<'py> ser::SerializeSeq for Seq<'py> {
@@ -465,13 +1202,17 @@ impl<'py> ser::SerializeSeq for Seq<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.seq
-            .push(value.serialize(PyAnySerializer { py: self.py })?);
+        let index = self.seq.len();
+        self.seq.push(
+            self.ser
+                .serialize_child(|| PathSegment::Index(index), value)?
+                .unbind(),
+        );
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok> {
-        Ok(PyList::new(self.py, self.seq)?.into_any())
+        self.ser.list(&self.seq)
     }
 }
 
@@ -483,19 +1224,23 @@ impl<'py> ser::SerializeTuple for Seq<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.seq
-            .push(value.serialize(PyAnySerializer { py: self.py })?);
+        let index = self.seq.len();
+        self.seq.push(
+            self.ser
+                .serialize_child(|| PathSegment::Index(index), value)?
+                .unbind(),
+        );
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok> {
-        Ok(PyTuple::new(self.py, self.seq)?.into_any())
+        self.ser.tuple(&self.seq)
     }
 }
 
 pub struct TupleStruct<'py> {
-    py: Python<'py>,
-    fields: Vec<Bound<'py, PyAny>>,
+    ser: PyAnySerializer<'py>,
+    fields: Scratch<Vec<Py<PyAny>>>,
 }
 
 impl<'py> ser::SerializeTupleStruct for TupleStruct<'py> {
@@ -506,20 +1251,25 @@ impl<'py> ser::SerializeTupleStruct for TupleStruct<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.fields
-            .push(value.serialize(PyAnySerializer { py: self.py })?);
+        let index = self.fields.len();
+        self.fields.push(
+            self.ser
+                .serialize_child(|| PathSegment::Index(index), value)?
+                .unbind(),
+        );
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok> {
-        Ok(PyTuple::new(self.py, self.fields)?.into_any())
+        self.ser.tuple(&self.fields)
     }
 }
 
 pub struct TupleVariant<'py> {
-    py: Python<'py>,
+    ser: PyAnySerializer<'py>,
+    name: &'static str,
     variant: &'static str,
-    fields: Vec<Bound<'py, PyAny>>,
+    fields: Scratch<Vec<Py<PyAny>>>,
 }
 
 impl<'py> ser::SerializeTupleVariant for TupleVariant<'py> {
@@ -530,20 +1280,29 @@ impl<'py> ser::SerializeTupleVariant for TupleVariant<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.fields
-            .push(value.serialize(PyAnySerializer { py: self.py })?);
+        let variant = self.variant;
+        self.ser.ctx.enter(|| PathSegment::Field(variant));
+        let index = self.fields.len();
+        let value = self
+            .ser
+            .serialize_child(|| PathSegment::Index(index), value);
+        self.ser.ctx.leave();
+        self.fields.push(value?.unbind());
         Ok(())
     }
 
     fn end(self) -> Result<Self::Ok> {
-        let dict = PyDict::new(self.py);
-        dict.set_item(self.variant, PyTuple::new(self.py, self.fields)?)?;
-        Ok(dict.into_any())
+        if let Some(class) = self.ser.variant_class(self.name, self.variant) {
+            return Ok(class.call1(PyTuple::new(self.ser.py, self.fields.iter())?)?);
+        }
+        let dict = PyDict::new(self.ser.py);
+        dict.set_item(self.variant, self.ser.tuple(&self.fields)?)?;
+        self.ser.mapping(dict)
     }
 }
 
 pub struct Map<'py> {
-    py: Python<'py>,
+    ser: PyAnySerializer<'py>,
     map: Bound<'py, PyDict>,
     key: Option<Bound<'py, PyAny>>,
 }
@@ -556,7 +1315,18 @@ impl<'py> ser::SerializeMap for Map<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.key = Some(key.serialize(PyAnySerializer { py: self.py })?);
+        let mut key = key.serialize(self.ser.clone())?;
+        if let Some(encoder) = &self.ser.ctx.options.map_key_encoder {
+            key = encoder.encode(key)?;
+        } else if self.ser.ctx.options.json_compatible {
+            key = self.ser.json_key(key)?;
+        } else if let Some(bytes) = byte_list(&key) {
+            // `Vec<u8>` keys are serialized as `list`, which is unhashable
+            key = bytes.into_any();
+        } else if let Ok(bytes) = key.downcast::<PyByteArray>() {
+            key = PyBytes::new(self.ser.py, &bytes.to_vec()).into_any();
+        }
+        self.key = Some(key);
         Ok(())
     }
 
@@ -568,19 +1338,35 @@ impl<'py> ser::SerializeMap for Map<'py> {
             .key
             .take()
             .expect("Invalid Serialize implementation. Key is missing.");
-        self.map
-            .set_item(key, value.serialize(PyAnySerializer { py: self.py })?)?;
-        Ok(())
+        let value = self
+            .ser
+            .serialize_child(|| PathSegment::Key(key.clone()), value)?;
+        if self.ser.skips(&value) {
+            return Ok(());
+        }
+        self.ser.set_item(&self.map, key, value)
     }
 
     fn end(self) -> Result<Self::Ok> {
-        Ok(self.map.into_any())
+        self.ser.mapping(self.ser.sort_keys(self.map)?)
     }
 }
 
+/// Fields of a struct or a struct variant serialized so far
+enum Fields<'py> {
+    /// Values by their Python names
+    Keyed(Bound<'py, PyDict>),
+    /// Values in the order of `serialize_field` for [`StructOutput::Tuple`],
+    /// which keeps every position even if the names of fields collide
+    Positional(Scratch<Vec<Py<PyAny>>>),
+}
+
 pub struct Struct<'py> {
-    py: Python<'py>,
-    fields: Bound<'py, PyDict>,
+    ser: PyAnySerializer<'py>,
+    name: &'static str,
+    fields: Fields<'py>,
+    /// Name and Python names of serialized fields of the struct, recorded only to create its class
+    class: Option<(&'static str, Vec<String>)>,
 }
 
 impl<'py> ser::SerializeStruct for Struct<'py> {
@@ -591,20 +1377,48 @@ impl<'py> ser::SerializeStruct for Struct<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.fields
-            .set_item(key, value.serialize(PyAnySerializer { py: self.py })?)?;
-        Ok(())
+        let value = self
+            .ser
+            .serialize_child(|| PathSegment::Field(key), value)?;
+        let fields = match &mut self.fields {
+            Fields::Positional(values) => {
+                values.push(value.unbind());
+                return Ok(());
+            }
+            Fields::Keyed(fields) => fields,
+        };
+        if self.ser.skips_field(&value) {
+            return Ok(());
+        }
+        let name = match &mut self.class {
+            // Fields of classes cannot be keywords, so that `keyword_fields` does not apply
+            Some((_, fields)) => {
+                let name = self.ser.ctx.options.field_case.convert(key);
+                fields.push(name.to_string());
+                self.ser.string(&name)
+            }
+            None => self.ser.field_name(key),
+        };
+        self.ser.set_item(fields, name.into_any(), value)
     }
 
     fn end(self) -> Result<Self::Ok> {
-        Ok(self.fields.into_any())
+        if let (Some((name, names)), Fields::Keyed(fields)) = (&self.class, &self.fields) {
+            let output = self.ser.ctx.options.struct_output;
+            let class = struct_class::get(self.ser.py, output, name, names)?;
+            let obj = class.call((), Some(fields))?;
+            return self.ser.wrap_type_name(name, obj);
+        }
+        let obj = self.ser.struct_fields(self.fields)?;
+        self.ser.wrap_type_name(self.name, obj)
     }
 }
 
 pub struct StructVariant<'py> {
-    py: Python<'py>,
+    ser: PyAnySerializer<'py>,
+    name: &'static str,
     variant: &'static str,
-    fields: Bound<'py, PyDict>,
+    fields: Fields<'py>,
 }
 
 impl<'py> ser::SerializeStructVariant for StructVariant<'py> {
@@ -615,14 +1429,33 @@ impl<'py> ser::SerializeStructVariant for StructVariant<'py> {
     where
         T: ?Sized + Serialize,
     {
-        self.fields
-            .set_item(key, value.serialize(PyAnySerializer { py: self.py })?)?;
-        Ok(())
+        let variant = self.variant;
+        self.ser.ctx.enter(|| PathSegment::Field(variant));
+        let value = self.ser.serialize_child(|| PathSegment::Field(key), value);
+        self.ser.ctx.leave();
+        let value = value?;
+        let fields = match &mut self.fields {
+            Fields::Positional(values) => {
+                values.push(value.unbind());
+                return Ok(());
+            }
+            Fields::Keyed(fields) => fields,
+        };
+        if self.ser.skips_field(&value) {
+            return Ok(());
+        }
+        self.ser
+            .set_item(fields, self.ser.field_name(key).into_any(), value)
     }
 
     fn end(self) -> Result<Self::Ok> {
-        let dict = PyDict::new(self.py);
-        dict.set_item(self.variant, self.fields)?;
-        Ok(dict.into_any())
+        if let Fields::Keyed(fields) = &self.fields {
+            if let Some(class) = self.ser.variant_class(self.name, self.variant) {
+                return Ok(class.call((), Some(fields))?);
+            }
+        }
+        let dict = PyDict::new(self.ser.py);
+        dict.set_item(self.variant, self.ser.struct_fields(self.fields)?)?;
+        self.ser.mapping(dict)
     }
 }
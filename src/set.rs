@@ -0,0 +1,128 @@
+//! Python [`set`](https://docs.python.org/3/library/stdtypes.html#set) support
+//!
+//! Serde models sets as sequences, so `HashSet<T>` and `BTreeSet<T>` are serialized into `list` by default.
+//! Use this module with `#[serde(with = "serde_pyobject::set")]` on a field
+//! to serialize it into a `set` instead, or into a `frozenset` if
+//! [`PyObjectSerializerOptions::immutable`](crate::PyObjectSerializerOptions::immutable) is enabled.
+//! Other serde formats see a usual sequence.
+//!
+//! `set` and `frozenset` objects are always accepted as sequences by [`from_pyobject`](crate::from_pyobject).
+//! Fields using this module reject `list` and `tuple` with duplicate elements
+//! if [`PyObjectDeserializerOptions::unique_sets`](crate::PyObjectDeserializerOptions::unique_sets) is enabled.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::{PyAnyMethods, PySet}};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//! use std::collections::BTreeSet;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Post {
+//!     #[serde(with = "serde_pyobject::set")]
+//!     tags: BTreeSet<String>,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let post = Post { tags: BTreeSet::from(["rust".to_string(), "python".to_string()]) };
+//!     let obj = to_pyobject(py, &post).unwrap();
+//!     assert!(obj.get_item("tags").unwrap().is_exact_instance_of::<PySet>());
+//!
+//!     let reverted: Post = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, post);
+//! });
+//! ```
+
+use crate::{error::Result, ser::PyAnySerializer};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyFrozenSet, PyList, PySet, PyTuple},
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, marker::PhantomData};
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes
+pub(crate) const TOKEN: &str = "$serde_pyobject::set";
+
+/// Serialize a collection, e.g. `HashSet<T>` or `BTreeSet<T>`, as `set`
+pub fn serialize<S, T>(value: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    serializer.serialize_newtype_struct(TOKEN, value)
+}
+
+/// Deserialize a collection from `set`, `frozenset` or any other sequence
+pub fn deserialize<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_newtype_struct(TOKEN, SetVisitor(PhantomData))
+}
+
+/// Deserializes the collection in the newtype struct, or given directly by other formats
+struct SetVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> de::Visitor<'de> for SetVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a set or a sequence")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<T, D::Error> {
+        T::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> std::result::Result<T, A::Error> {
+        T::deserialize(de::value::SeqAccessDeserializer::new(seq))
+    }
+}
+
+/// Raise `ValueError` on the first duplicate element of `list` or `tuple`
+///
+/// Unhashable elements cannot be compared through a `set`, and are skipped.
+pub(crate) fn check_unique(obj: &Bound<'_, PyAny>) -> PyResult<()> {
+    if !obj.is_instance_of::<PyList>() && !obj.is_instance_of::<PyTuple>() {
+        return Ok(());
+    }
+    let seen = PySet::empty(obj.py())?;
+    for element in obj.try_iter()? {
+        let element = element?;
+        match seen.contains(&element) {
+            Ok(true) => {
+                return Err(PyValueError::new_err(format!(
+                    "duplicate element {} in a set",
+                    element.repr()?
+                )))
+            }
+            Ok(false) => seen.add(element)?,
+            Err(err) if err.is_instance_of::<PyTypeError>(obj.py()) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Create `set`, or `frozenset` if `frozen`, of the elements of the serialized sequence `value`
+pub(crate) fn to_set<'py, T>(
+    serializer: PyAnySerializer<'py>,
+    frozen: bool,
+    value: &T,
+) -> Result<Bound<'py, PyAny>>
+where
+    T: ?Sized + Serialize,
+{
+    let py = serializer.py();
+    let elements = value.serialize(serializer)?;
+    let elements = elements.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+    if frozen {
+        return Ok(PyFrozenSet::new(py, elements)?.into_any());
+    }
+    Ok(PySet::new(py, elements)?.into_any())
+}
@@ -0,0 +1,171 @@
+//! Share deserialized values between aliased Python objects
+//!
+//! Use this module with `#[serde(with = "serde_pyobject::shared")]` on `Arc<T>` fields.
+//! When the same Python object (by identity) is reached more than once during a single
+//! [`from_pyobject`](crate::from_pyobject) call, it is deserialized only once
+//! and every occurrence shares the same `Arc<T>`.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use pyo3::Python;
+//! use serde_pyobject::{from_pyobject, pydict};
+//! use std::sync::Arc;
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Inner {
+//!     value: i32,
+//! }
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Outer {
+//!     #[serde(with = "serde_pyobject::shared")]
+//!     a: Arc<Inner>,
+//!     #[serde(with = "serde_pyobject::shared")]
+//!     b: Arc<Inner>,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let inner = pydict! { py, "value" => 1 }.unwrap();
+//!     let outer = pydict! { py, "a" => &inner, "b" => &inner }.unwrap();
+//!     let outer: Outer = from_pyobject(outer).unwrap();
+//!     assert!(Arc::ptr_eq(&outer.a, &outer.b));
+//! });
+//! ```
+//!
+//! Other serde formats do not know about Python object identity,
+//! and this module simply deserializes a fresh `Arc<T>` for them.
+
+use pyo3::prelude::*;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+/// Name of the newtype struct which `PyAnyDeserializer` recognizes
+/// to pass the identity of the Python object to [`SharedVisitor`]
+pub(crate) const TOKEN: &str = "$serde_pyobject::shared";
+
+#[derive(Default)]
+struct IdentityMap {
+    /// Keep objects alive while the map exists, so that their `id` is not reused
+    objects: HashMap<usize, Py<PyAny>>,
+    values: HashMap<(usize, TypeId), Box<dyn Any>>,
+}
+
+thread_local! {
+    static IDENTITY_MAPS: RefCell<Vec<IdentityMap>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Guard of the identity map which lives during a top-level deserialization
+pub(crate) struct IdentityScope {
+    _private: (),
+}
+
+impl IdentityScope {
+    pub(crate) fn enter() -> Self {
+        IDENTITY_MAPS.with(|maps| maps.borrow_mut().push(IdentityMap::default()));
+        IdentityScope { _private: () }
+    }
+}
+
+impl Drop for IdentityScope {
+    fn drop(&mut self) {
+        let map = IDENTITY_MAPS.with(|maps| maps.borrow_mut().pop());
+        // Drop Python references outside of the borrow
+        drop(map);
+    }
+}
+
+/// Register the object in the current identity map, and returns its identity
+pub(crate) fn register(obj: &Bound<'_, PyAny>) -> Option<usize> {
+    let id = obj.as_ptr() as usize;
+    IDENTITY_MAPS.with(|maps| {
+        let mut maps = maps.borrow_mut();
+        let map = maps.last_mut()?;
+        map.objects
+            .entry(id)
+            .or_insert_with(|| obj.clone().unbind());
+        Some(id)
+    })
+}
+
+fn lookup<T: 'static>(id: usize) -> Option<Arc<T>> {
+    IDENTITY_MAPS.with(|maps| {
+        let maps = maps.borrow();
+        let value = maps.last()?.values.get(&(id, TypeId::of::<T>()))?;
+        value.downcast_ref::<Arc<T>>().cloned()
+    })
+}
+
+fn insert<T: 'static>(id: usize, value: Arc<T>) {
+    IDENTITY_MAPS.with(|maps| {
+        if let Some(map) = maps.borrow_mut().last_mut() {
+            map.values.insert((id, TypeId::of::<T>()), Box::new(value));
+        }
+    })
+}
+
+/// Serialize `Arc<T>` as `T`
+pub fn serialize<S, T>(value: &Arc<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize + ?Sized,
+{
+    (**value).serialize(serializer)
+}
+
+/// Deserialize `Arc<T>`, sharing the result for the same Python object
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Arc<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + 'static,
+{
+    deserializer.deserialize_newtype_struct(TOKEN, SharedVisitor(PhantomData))
+}
+
+struct SharedVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for SharedVisitor<T>
+where
+    T: Deserialize<'de> + 'static,
+{
+    type Value = Arc<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a shared value")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Arc::new)
+    }
+
+    // `PyAnyDeserializer` visits `(id, object)` pair for the token
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let id: usize = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        if let Some(value) = lookup::<T>(id) {
+            return Ok(value);
+        }
+        let value: T = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let value = Arc::new(value);
+        insert(id, value.clone());
+        Ok(value)
+    }
+}
@@ -0,0 +1,106 @@
+//! Markers of the Python singletons `...` and `NotImplemented`
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+/// Name of the struct which `PyAnySerializer` and `PyAnyDeserializer` recognize as `...`
+pub(crate) const ELLIPSIS_TOKEN: &str = "$serde_pyobject::Ellipsis";
+
+/// Name of the struct which `PyAnySerializer` and `PyAnyDeserializer` recognize as `NotImplemented`
+pub(crate) const NOT_IMPLEMENTED_TOKEN: &str = "$serde_pyobject::NotImplemented";
+
+macro_rules! singleton {
+    ($(#[$meta:meta])* $name:ident, $token:ident, $repr:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name;
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_unit_struct($token)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct MarkerVisitor;
+
+                impl<'de> Visitor<'de> for MarkerVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str($repr)
+                    }
+
+                    fn visit_unit<E: de::Error>(self) -> Result<$name, E> {
+                        Ok($name)
+                    }
+
+                    fn visit_newtype_struct<D: Deserializer<'de>>(
+                        self,
+                        deserializer: D,
+                    ) -> Result<$name, D::Error> {
+                        deserializer.deserialize_any(self)
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<$name, E> {
+                        if v == $repr {
+                            Ok($name)
+                        } else {
+                            Err(E::invalid_value(de::Unexpected::Str(v), &self))
+                        }
+                    }
+                }
+
+                // Not as a unit struct, which `#[serde(untagged)]` enums cannot match with the marker strings
+                deserializer.deserialize_newtype_struct($token, MarkerVisitor)
+            }
+        }
+    };
+}
+
+singleton!(
+    /// Python `...` (`Ellipsis`)
+    ///
+    /// This is serialized into `...`, and deserialized only from `...`
+    /// or from the string `"Ellipsis"` which [`SingletonPolicy::Marker`](crate::SingletonPolicy::Marker) produces.
+    /// Other serde formats see a unit struct.
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_pyobject::{from_pyobject, to_pyobject, Ellipsis};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// #[serde(untagged)]
+    /// enum Index {
+    ///     All(Ellipsis),
+    ///     At(usize),
+    /// }
+    ///
+    /// Python::with_gil(|py| {
+    ///     let obj = to_pyobject(py, &Index::All(Ellipsis)).unwrap();
+    ///     assert!(obj.is(&py.Ellipsis()));
+    ///
+    ///     let index: Ellipsis = from_pyobject(obj).unwrap();
+    ///     assert_eq!(index, Ellipsis);
+    /// });
+    /// ```
+    Ellipsis,
+    ELLIPSIS_TOKEN,
+    "Ellipsis"
+);
+
+singleton!(
+    /// Python `NotImplemented`
+    ///
+    /// This is serialized into `NotImplemented`, and deserialized only from `NotImplemented`
+    /// or from the string `"NotImplemented"` which [`SingletonPolicy::Marker`](crate::SingletonPolicy::Marker) produces.
+    /// Other serde formats see a unit struct.
+    NotImplemented,
+    NOT_IMPLEMENTED_TOKEN,
+    "NotImplemented"
+);
@@ -0,0 +1,131 @@
+//! Typed stream over a Python async iterator
+
+use crate::{de::from_pyobject, error::Result, py_module_cache};
+use futures_core::Stream;
+use pyo3::{exceptions::PyStopAsyncIteration, prelude::*, types::PyCFunction};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Stream over a Python async iterator, e.g. an async generator,
+/// which deserializes each yielded item
+///
+/// Each `__anext__()` is scheduled on an `asyncio` event loop running in another thread
+/// by `asyncio.run_coroutine_threadsafe`, and the task polling this stream is woken
+/// when the item is ready. This requires the `async` feature,
+/// and is unavailable on `wasm32-unknown-emscripten` (Pyodide) which has no threads.
+///
+/// ```
+/// use pyo3::{Python, ffi::c_str, types::{PyDict, PyAnyMethods}};
+/// use serde_pyobject::TypedStream;
+///
+/// let stream = Python::with_gil(|py| {
+///     let globals = PyDict::new(py);
+///     py.run(c_str!(r#"
+/// import asyncio, threading
+///
+/// loop = asyncio.new_event_loop()
+/// threading.Thread(target=loop.run_forever, daemon=True).start()
+///
+/// async def numbers():
+///     for i in range(3):
+///         await asyncio.sleep(0)
+///         yield i
+/// "#), Some(&globals), None).unwrap();
+///     let numbers = globals.get_item("numbers").unwrap().call0().unwrap();
+///     TypedStream::<u32>::new(&numbers, &globals.get_item("loop").unwrap()).unwrap()
+/// });
+/// // Poll `stream` with any executor, e.g. `futures::executor::block_on_stream(stream)`
+/// # drop(stream);
+/// ```
+pub struct TypedStream<T> {
+    iter: Py<PyAny>,
+    event_loop: Py<PyAny>,
+    /// `concurrent.futures.Future` of the running `__anext__()`
+    pending: Option<Py<PyAny>>,
+    /// Waker of the task which polled the stream last
+    waker: Arc<Mutex<Option<Waker>>>,
+    finished: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedStream<T>
+where
+    T: DeserializeOwned,
+{
+    /// Get the async iterator of `obj` by `aiter(obj)`, which runs on `event_loop`
+    pub fn new(obj: &Bound<'_, PyAny>, event_loop: &Bound<'_, PyAny>) -> Result<Self> {
+        Ok(Self {
+            iter: obj.call_method0("__aiter__")?.unbind(),
+            event_loop: event_loop.clone().unbind(),
+            pending: None,
+            waker: Arc::new(Mutex::new(None)),
+            finished: false,
+            _marker: PhantomData,
+        })
+    }
+
+    fn poll_item(&mut self, py: Python<'_>, cx: &mut Context<'_>) -> Result<Poll<Option<T>>> {
+        *self.waker.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        let future = match &self.pending {
+            Some(future) => future.bind(py).clone(),
+            None => {
+                let run = py_module_cache::get(py, "asyncio", "run_coroutine_threadsafe")?;
+                let future =
+                    run.call1((self.iter.call_method0(py, "__anext__")?, &self.event_loop))?;
+                let waker = self.waker.clone();
+                let wake = PyCFunction::new_closure(py, None, None, move |_args, _kwargs| {
+                    if let Some(waker) = waker.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                        waker.wake();
+                    }
+                })?;
+                future.call_method1("add_done_callback", (wake,))?;
+                self.pending = Some(future.clone().unbind());
+                future
+            }
+        };
+        if !future.call_method0("done")?.is_truthy()? {
+            return Ok(Poll::Pending);
+        }
+        self.pending = None;
+        match future.call_method0("result") {
+            Ok(item) => Ok(Poll::Ready(Some(from_pyobject(item)?))),
+            Err(err) if err.is_instance_of::<PyStopAsyncIteration>(py) => {
+                self.finished = true;
+                Ok(Poll::Ready(None))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+impl<T> Stream for TypedStream<T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(None);
+        }
+        Python::with_gil(|py| match this.poll_item(py, cx) {
+            Ok(poll) => poll.map(|item| item.map(Ok)),
+            Err(err) => Poll::Ready(Some(Err(err))),
+        })
+    }
+}
+
+impl<T> Unpin for TypedStream<T> {}
+
+impl<T> fmt::Debug for TypedStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedStream").field(&self.iter).finish()
+    }
+}
@@ -0,0 +1,72 @@
+//! Python classes generated for serialized Rust structs
+
+use crate::{error::Result, options::StructOutput, py_module_cache};
+use pyo3::{prelude::*, types::*};
+use std::{collections::HashMap, sync::Mutex};
+
+/// Kind and name of a Rust struct -> its serialized fields and the class of its instances
+type Classes = HashMap<(StructOutput, &'static str), Vec<(Vec<String>, Py<PyType>)>>;
+
+static CLASSES: Mutex<Option<Classes>> = Mutex::new(None);
+
+/// Class whose instances a struct serialized with
+/// [`PyObjectSerializerOptions::struct_output`](crate::PyObjectSerializerOptions::struct_output) becomes
+///
+/// A class is created for each pair of the struct name and the Python names of its serialized fields,
+/// which may differ between values of the same struct by `#[serde(skip_serializing_if)]`,
+/// or between options by [`PyObjectSerializerOptions::field_case`](crate::PyObjectSerializerOptions::field_case).
+pub(crate) fn get<'py>(
+    py: Python<'py>,
+    output: StructOutput,
+    name: &'static str,
+    fields: &[String],
+) -> Result<Bound<'py, PyType>> {
+    if let Some(class) = lookup(py, output, name, fields) {
+        return Ok(class);
+    }
+
+    let class = match output {
+        #[cfg(feature = "dataclass_support")]
+        StructOutput::Dataclass => crate::dataclass::make_dataclass(py, name, fields)?,
+        StructOutput::NamedTuple => py_module_cache::get(py, "collections", "namedtuple")?
+            .call1((name, PyTuple::new(py, fields)?))?
+            .downcast_into::<PyType>()?,
+        StructOutput::Dict | StructOutput::Namespace | StructOutput::Tuple => {
+            unreachable!("{:?} has no class for each struct", output)
+        }
+    };
+
+    let mut classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+    let entries = classes
+        .get_or_insert_with(HashMap::new)
+        .entry((output, name))
+        .or_default();
+    // Another thread may have created the class meanwhile, and the first one wins
+    if let Some((_, class)) = entries.iter().find(|(f, _)| f == fields) {
+        return Ok(class.bind(py).clone());
+    }
+    entries.push((fields.to_vec(), class.clone().unbind()));
+    Ok(class)
+}
+
+/// Drop the classes created by [`get`]
+pub(crate) fn clear() {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner()).take();
+    // Release Python references outside of the lock
+    drop(classes);
+}
+
+fn lookup<'py>(
+    py: Python<'py>,
+    output: StructOutput,
+    name: &'static str,
+    fields: &[String],
+) -> Option<Bound<'py, PyType>> {
+    let classes = CLASSES.lock().unwrap_or_else(|e| e.into_inner());
+    let (_, class) = classes
+        .as_ref()?
+        .get(&(output, name))?
+        .iter()
+        .find(|(f, _)| f == fields)?;
+    Some(class.bind(py).clone())
+}
@@ -0,0 +1,241 @@
+//! Conversion between Python objects and other serde data formats
+//!
+//! The input is parsed incrementally and the Python objects are built by [`PyObjectVisitor`]
+//! directly, without an intermediate representation such as `serde_json::Value`.
+//! In the other direction, Python objects are transcoded into the format by `serde_transcode`.
+
+use crate::{error::Result, visitor::PyObjectVisitor};
+use pyo3::prelude::*;
+use serde::de::DeserializeSeed;
+
+/// Parse JSON from `reader` into Python objects
+///
+/// JSON objects become `dict`, arrays become `list`, and `null` becomes `None`.
+/// Since the text is not held in memory at once, this is suitable for huge JSON files.
+/// Wrap a file in [`std::io::BufReader`] since the reader is read byte by byte.
+///
+/// A syntax error raises `ValueError`, and an I/O error raises `OSError`.
+/// This requires the `json` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{json_reader_to_pyobject, pydict, pylist};
+///
+/// Python::with_gil(|py| {
+///     let json = r#"{ "a": [1, 2.5, null], "b": { "c": true } }"#;
+///     let obj = json_reader_to_pyobject(py, json.as_bytes()).unwrap();
+///     let expected = pydict! { py,
+///         "a" => pylist![py; 1, 2.5, py.None()].unwrap(),
+///         "b" => pydict! { py, "c" => true }.unwrap()
+///     }
+///     .unwrap();
+///     assert!(obj.eq(expected).unwrap());
+/// });
+/// ```
+#[cfg(feature = "json")]
+pub fn json_reader_to_pyobject<'py, R>(py: Python<'py>, reader: R) -> Result<Bound<'py, PyAny>>
+where
+    R: std::io::Read,
+{
+    use pyo3::exceptions::{PyOSError, PyValueError};
+
+    let json_error = |err: serde_json::Error| {
+        if err.is_io() {
+            PyOSError::new_err(err.to_string())
+        } else {
+            PyValueError::new_err(err.to_string())
+        }
+    };
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let obj = PyObjectVisitor::new(py)
+        .deserialize(&mut de)
+        .map_err(json_error)?;
+    de.end().map_err(json_error)?;
+    Ok(obj)
+}
+
+/// Decode MessagePack `bytes` into Python objects
+///
+/// Maps become `dict`, arrays become `list`, binaries become `bytes`, and `nil` becomes `None`.
+/// Malformed input or trailing bytes raise `ValueError`.
+/// This requires the `rmp` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{msgpack_to_pyobject, pydict};
+///
+/// Python::with_gil(|py| {
+///     // {"a": 1}
+///     let obj = msgpack_to_pyobject(py, &[0x81, 0xa1, b'a', 0x01]).unwrap();
+///     assert!(obj.eq(pydict! { py, "a" => 1 }.unwrap()).unwrap());
+/// });
+/// ```
+#[cfg(feature = "rmp")]
+pub fn msgpack_to_pyobject<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let mut de = rmp_serde::Deserializer::new(std::io::Cursor::new(bytes));
+    let obj = PyObjectVisitor::new(py)
+        .deserialize(&mut de)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    if de.position() != bytes.len() as u64 {
+        return Err(PyValueError::new_err(format!(
+            "trailing bytes after MessagePack value at position {}",
+            de.position()
+        ))
+        .into());
+    }
+    Ok(obj)
+}
+
+/// Encode a Python object into MessagePack
+///
+/// The object is read as [`from_pyobject`](crate::from_pyobject) reads it into an untyped value,
+/// and transcoded into MessagePack on the fly.
+/// This requires the `rmp` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{pydict, pyobject_to_msgpack};
+///
+/// Python::with_gil(|py| {
+///     let obj = pydict! { py, "a" => 1 }.unwrap();
+///     let bytes = pyobject_to_msgpack(obj.as_any()).unwrap();
+///     assert_eq!(bytes, [0x81, 0xa1, b'a', 0x01]);
+/// });
+/// ```
+#[cfg(feature = "rmp")]
+pub fn pyobject_to_msgpack(obj: &Bound<'_, PyAny>) -> Result<Vec<u8>> {
+    use crate::de::PyAnyDeserializer;
+    use pyo3::exceptions::PyValueError;
+
+    let mut bytes = Vec::new();
+    let mut ser = rmp_serde::Serializer::new(&mut bytes);
+    serde_transcode::transcode(PyAnyDeserializer::new(obj.clone())?, &mut ser)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(bytes)
+}
+
+/// Python object built by [`PyObjectVisitor`],
+/// for formats which only accept [`DeserializeOwned`](serde::de::DeserializeOwned)
+#[cfg(any(feature = "cbor", feature = "bson"))]
+struct PyObjectValue(Py<PyAny>);
+
+#[cfg(any(feature = "cbor", feature = "bson"))]
+impl<'de> serde::Deserialize<'de> for PyObjectValue {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // The caller holds the GIL, so this only takes the token
+        Python::with_gil(|py| {
+            let obj = PyObjectVisitor::new(py).deserialize(deserializer)?;
+            Ok(PyObjectValue(obj.unbind()))
+        })
+    }
+}
+
+/// Decode CBOR `bytes` into Python objects
+///
+/// Maps become `dict`, arrays become `list`, byte strings become `bytes`,
+/// and `null` and `undefined` become `None`.
+/// Malformed input, tagged values, or trailing bytes raise `ValueError`.
+/// This requires the `cbor` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{cbor_to_pyobject, pydict};
+///
+/// Python::with_gil(|py| {
+///     // {"a": 1}
+///     let obj = cbor_to_pyobject(py, &[0xa1, 0x61, b'a', 0x01]).unwrap();
+///     assert!(obj.eq(pydict! { py, "a" => 1 }.unwrap()).unwrap());
+/// });
+/// ```
+#[cfg(feature = "cbor")]
+pub fn cbor_to_pyobject<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let mut rest = bytes;
+    let value: PyObjectValue =
+        ciborium::from_reader(&mut rest).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    if !rest.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "trailing bytes after CBOR value at position {}",
+            bytes.len() - rest.len()
+        ))
+        .into());
+    }
+    Ok(value.0.into_bound(py))
+}
+
+/// Encode a Python object into CBOR
+///
+/// The object is transcoded as [`pyobject_to_msgpack`] does.
+/// This requires the `cbor` feature.
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{pydict, pyobject_to_cbor};
+///
+/// Python::with_gil(|py| {
+///     let obj = pydict! { py, "a" => 1 }.unwrap();
+///     let bytes = pyobject_to_cbor(obj.as_any()).unwrap();
+///     assert_eq!(bytes, [0xa1, 0x61, b'a', 0x01]);
+/// });
+/// ```
+#[cfg(feature = "cbor")]
+pub fn pyobject_to_cbor(obj: &Bound<'_, PyAny>) -> Result<Vec<u8>> {
+    use crate::de::PyAnyDeserializer;
+    use pyo3::exceptions::PyValueError;
+
+    let mut bytes = Vec::new();
+    let transcoder = serde_transcode::Transcoder::new(PyAnyDeserializer::new(obj.clone())?);
+    ciborium::into_writer(&transcoder, &mut bytes)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(bytes)
+}
+
+/// Decode a BSON document into `dict`
+///
+/// Embedded documents become `dict`, arrays become `list`, and binaries become `bytes`.
+/// Other BSON types are represented in the [extended JSON] form,
+/// e.g. an ObjectId becomes `{"$oid": "..."}`.
+/// Malformed input raises `ValueError`.
+/// This requires the `bson` feature.
+///
+/// [extended JSON]: https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde_pyobject::{bson_to_pyobject, pydict, pyobject_to_bson};
+///
+/// Python::with_gil(|py| {
+///     let obj = pydict! { py, "a" => 1 }.unwrap();
+///     let bytes = pyobject_to_bson(obj.as_any()).unwrap();
+///     let decoded = bson_to_pyobject(py, &bytes).unwrap();
+///     assert!(decoded.eq(obj).unwrap());
+/// });
+/// ```
+#[cfg(feature = "bson")]
+pub fn bson_to_pyobject<'py>(py: Python<'py>, bytes: &[u8]) -> Result<Bound<'py, PyAny>> {
+    use pyo3::exceptions::PyValueError;
+
+    let value: PyObjectValue =
+        bson::from_slice(bytes).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(value.0.into_bound(py))
+}
+
+/// Encode a Python mapping into a BSON document
+///
+/// The object is transcoded as [`pyobject_to_msgpack`] does,
+/// and must be a mapping since the top level of BSON is a document.
+/// This requires the `bson` feature.
+#[cfg(feature = "bson")]
+pub fn pyobject_to_bson(obj: &Bound<'_, PyAny>) -> Result<Vec<u8>> {
+    use crate::de::PyAnyDeserializer;
+    use pyo3::exceptions::PyValueError;
+
+    let transcoder = serde_transcode::Transcoder::new(PyAnyDeserializer::new(obj.clone())?);
+    Ok(bson::to_vec(&transcoder).map_err(|err| PyValueError::new_err(err.to_string()))?)
+}
@@ -0,0 +1,228 @@
+//! Typed access to values defined in Python
+
+use crate::{
+    de::{from_pyobject, from_pyobject_with_path},
+    error::{Error, Result},
+    ser::to_pyobject,
+};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyIterator, PyTuple},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{ffi::CString, fmt, marker::PhantomData};
+
+/// Import `module` and deserialize its attribute `attr`
+///
+/// This covers the pattern that a Python module defines a configuration
+/// and Rust reads it as a typed struct.
+/// A conversion error is prefixed by the qualified name of the attribute
+/// and the path to the offending value.
+///
+/// ```
+/// use pyo3::Python;
+/// use serde_pyobject::from_py_attr;
+///
+/// Python::with_gil(|py| {
+///     let version: (u32, u32, u32, String, u32) = from_py_attr(py, "sys", "version_info").unwrap();
+///     assert_eq!(version.0, 3);
+/// });
+/// ```
+pub fn from_py_attr<'py, 'de, T>(py: Python<'py>, module: &str, attr: &str) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let obj = py.import(module)?.getattr(attr)?;
+    from_pyobject_with_path(obj, &Default::default())
+        .map_err(|err| err.context(py, format_args!("{}.{}", module, attr)))
+}
+
+/// Evaluate a Python expression and deserialize the result
+///
+/// An error is prefixed by the expression, and a conversion error also by the path
+/// to the offending value like `$.servers[1].port`.
+///
+/// ```
+/// use serde::Deserialize;
+/// use pyo3::Python;
+/// use serde_pyobject::eval_typed;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let servers: Vec<Server> =
+///         eval_typed(py, "[dict(host=h, port=8000 + i) for i, h in enumerate('ab')]", None, None)
+///             .unwrap();
+///     assert_eq!(servers[1], Server { host: "b".to_string(), port: 8001 });
+///
+///     let err = eval_typed::<Vec<Server>>(py, "[dict(host='a', port=-1)]", None, None).unwrap_err();
+///     assert_eq!(
+///         err.to_string(),
+///         "RuntimeError: [dict(host='a', port=-1)]: $[0]['port']: invalid value: integer `-1`, expected u16"
+///     );
+/// });
+/// ```
+pub fn eval_typed<'py, 'de, T>(
+    py: Python<'py>,
+    expr: &str,
+    globals: Option<&Bound<'py, PyDict>>,
+    locals: Option<&Bound<'py, PyDict>>,
+) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let code =
+        CString::new(expr).map_err(|_| PyValueError::new_err("expression contains a nul byte"))?;
+    py.eval(&code, globals, locals)
+        .map_err(Error::from)
+        .and_then(|obj| from_pyobject_with_path(obj, &Default::default()))
+        .map_err(|err| err.context(py, expr))
+}
+
+/// Python callable with typed arguments and return value
+///
+/// The arguments are serialized by [`to_pyobject`]. A tuple is passed as positional arguments,
+/// a `dict` (e.g. from a struct) as keyword arguments, and any other object as a single argument.
+/// The return value is deserialized by [`from_pyobject`].
+///
+/// ```
+/// use serde::Serialize;
+/// use pyo3::{Python, ffi::c_str};
+/// use serde_pyobject::TypedCallable;
+///
+/// #[derive(Serialize)]
+/// struct Query {
+///     sep: String,
+/// }
+///
+/// let (add, join) = Python::with_gil(|py| {
+///     let add = py.eval(c_str!("lambda a, b: a + b"), None, None).unwrap();
+///     let join = py.eval(c_str!("lambda sep: sep.join(['a', 'b'])"), None, None).unwrap();
+///     (
+///         TypedCallable::<(i32, i32), i32>::new(add).unwrap(),
+///         TypedCallable::<Query, String>::new(join).unwrap(),
+///     )
+/// });
+/// assert_eq!(add.call(&(1, 2)).unwrap(), 3);
+/// assert_eq!(join.call(&Query { sep: "-".to_string() }).unwrap(), "a-b");
+/// ```
+pub struct TypedCallable<Args, Ret> {
+    callable: Py<PyAny>,
+    _marker: PhantomData<fn(&Args) -> Ret>,
+}
+
+impl<Args, Ret> TypedCallable<Args, Ret>
+where
+    Args: Serialize,
+    Ret: DeserializeOwned,
+{
+    /// Wrap a callable Python object, or fail with `TypeError` if it is not callable
+    pub fn new(callable: Bound<'_, PyAny>) -> Result<Self> {
+        if !callable.is_callable() {
+            return Err(PyTypeError::new_err(format!(
+                "'{}' object is not callable",
+                callable.get_type().name()?
+            ))
+            .into());
+        }
+        Ok(Self {
+            callable: callable.unbind(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Call the Python object, acquiring the GIL
+    pub fn call(&self, args: &Args) -> Result<Ret> {
+        Python::with_gil(|py| {
+            let callable = self.callable.bind(py);
+            let args = to_pyobject(py, args)?;
+            let ret = if let Ok(args) = args.downcast::<PyTuple>() {
+                callable.call1(args)?
+            } else if let Ok(kwargs) = args.downcast::<PyDict>() {
+                callable.call((), Some(kwargs))?
+            } else {
+                callable.call1((args,))?
+            };
+            from_pyobject(ret)
+        })
+    }
+
+    /// The wrapped Python object
+    pub fn as_py(&self) -> &Py<PyAny> {
+        &self.callable
+    }
+}
+
+impl<'py, Args, Ret> FromPyObject<'py> for TypedCallable<Args, Ret>
+where
+    Args: Serialize,
+    Ret: DeserializeOwned,
+{
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Self::new(obj.clone()).map_err(PyErr::from)
+    }
+}
+
+impl<Args, Ret> fmt::Debug for TypedCallable<Args, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedCallable")
+            .field(&self.callable)
+            .finish()
+    }
+}
+
+/// Iterator over a Python iterable, which deserializes each item when it is yielded
+///
+/// Items are pulled from the Python iterator only when [`Iterator::next`] is called,
+/// so a generator producing a large result set is streamed without building a list.
+///
+/// ```
+/// use pyo3::{Python, ffi::c_str};
+/// use serde_pyobject::TypedIter;
+///
+/// Python::with_gil(|py| {
+///     let squares = py.eval(c_str!("(i * i for i in range(4))"), None, None).unwrap();
+///     let squares = TypedIter::<i64>::new(&squares).unwrap();
+///     assert_eq!(squares.collect::<Result<Vec<_>, _>>().unwrap(), vec![0, 1, 4, 9]);
+/// });
+/// ```
+pub struct TypedIter<'py, T> {
+    iter: Bound<'py, PyIterator>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'py, T> TypedIter<'py, T>
+where
+    T: DeserializeOwned,
+{
+    /// Get the iterator of a Python iterable by `iter(obj)`
+    pub fn new(obj: &Bound<'py, PyAny>) -> Result<Self> {
+        Ok(Self {
+            iter: obj.try_iter()?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Iterator for TypedIter<'_, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        Some(item.map_err(Error::from).and_then(from_pyobject))
+    }
+}
+
+impl<T> fmt::Debug for TypedIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedIter").field(&self.iter).finish()
+    }
+}
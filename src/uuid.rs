@@ -0,0 +1,138 @@
+//! Python [`uuid.UUID`](https://docs.python.org/3/library/uuid.html#uuid.UUID) support
+//!
+//! `uuid::Uuid` serializes itself as a string.
+//! Use this module with `#[serde(with = "serde_pyobject::uuid")]` on a field
+//! to serialize it into a `uuid.UUID` object instead,
+//! and to deserialize it back from a `uuid.UUID` object or its string.
+//! Other serde formats see the hyphenated string.
+//! Use [`option`] for `Option<Uuid>` fields.
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use pyo3::{Python, types::PyAnyMethods};
+//! use serde_pyobject::{to_pyobject, from_pyobject};
+//! use uuid::Uuid;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct User {
+//!     #[serde(with = "serde_pyobject::uuid")]
+//!     id: Uuid,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let user = User { id: Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8) };
+//!     let obj = to_pyobject(py, &user).unwrap();
+//!     assert_eq!(
+//!         obj.repr().unwrap().to_string(),
+//!         "{'id': UUID('67e55044-10b1-426f-9247-bb680e5fe0c8')}"
+//!     );
+//!
+//!     let reverted: User = from_pyobject(obj).unwrap();
+//!     assert_eq!(reverted, user);
+//! });
+//! ```
+
+use crate::{error::Result, py_module_cache};
+use ::uuid::Uuid;
+use pyo3::prelude::*;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Name of the newtype struct which `PyAnySerializer` recognizes, whose payload is the hyphenated string
+pub(crate) const TOKEN: &str = "$serde_pyobject::uuid";
+
+/// Serialize `Uuid` as `uuid.UUID`
+pub fn serialize<S: Serializer>(
+    value: &Uuid,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let mut buffer = ::uuid::Uuid::encode_buffer();
+    let hyphenated: &str = value.hyphenated().encode_lower(&mut buffer);
+    serializer.serialize_newtype_struct(TOKEN, hyphenated)
+}
+
+/// Deserialize `Uuid` from `uuid.UUID` or its string
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Uuid, D::Error> {
+    deserializer.deserialize_newtype_struct(TOKEN, UuidVisitor)
+}
+
+/// `#[serde(with = "serde_pyobject::uuid::option")]` for `Option<Uuid>` fields, where `None` is `None`
+pub mod option {
+    use super::*;
+
+    /// Serialize `Option<Uuid>` as `uuid.UUID` or `None`
+    pub fn serialize<S: Serializer>(
+        value: &Option<Uuid>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&Wrap(*value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize `Option<Uuid>` from `uuid.UUID`, its string or `None`
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Option<Uuid>, D::Error> {
+        let value: Option<Wrap> = Deserialize::deserialize(deserializer)?;
+        Ok(value.map(|value| value.0))
+    }
+
+    struct Wrap(Uuid);
+
+    impl Serialize for Wrap {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            super::serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Wrap {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            super::deserialize(deserializer).map(Wrap)
+        }
+    }
+}
+
+struct UuidVisitor;
+
+impl<'de> de::Visitor<'de> for UuidVisitor {
+    type Value = Uuid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a UUID")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Uuid, E> {
+        Uuid::parse_str(v).map_err(E::custom)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Uuid, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+/// Create `uuid.UUID` from the serialized hyphenated string
+pub(crate) fn to_py<'py>(payload: Bound<'py, PyAny>) -> Result<Bound<'py, PyAny>> {
+    let class = py_module_cache::get(payload.py(), "uuid", "UUID")?;
+    Ok(class.call1((payload,))?)
+}
+
+/// String of `obj` if it is `uuid.UUID`
+pub(crate) fn from_py<'py>(obj: &Bound<'py, PyAny>) -> Result<Option<Bound<'py, PyAny>>> {
+    // `uuid.UUID` objects exist only after `uuid` is imported
+    let Some(class) = py_module_cache::get_imported(obj.py(), "uuid", "UUID")? else {
+        return Ok(None);
+    };
+    if !obj.is_instance(&class)? {
+        return Ok(None);
+    }
+    Ok(Some(obj.str()?.into_any()))
+}
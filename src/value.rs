@@ -0,0 +1,125 @@
+//! Deserializers of a single Python scalar
+//!
+//! Unlike [`PyAnyDeserializer`](crate::PyAnyDeserializer), which dispatches on the type of the object,
+//! these deserializers expect a specific kind of scalar and report a Python `TypeError` otherwise.
+//! They are useful in [`DeserializeSeed`](serde::de::DeserializeSeed) implementations
+//! which already know what the object should be.
+//!
+//! ```
+//! use pyo3::prelude::*;
+//! use serde::Deserialize;
+//! use serde_pyobject::value::{IntDeserializer, StrDeserializer};
+//!
+//! Python::with_gil(|py| {
+//!     let obj = 42.into_pyobject(py).unwrap();
+//!     let x = u8::deserialize(IntDeserializer::new(obj)).unwrap();
+//!     assert_eq!(x, 42);
+//!
+//!     let obj = "hello".into_pyobject(py).unwrap();
+//!     let s = String::deserialize(StrDeserializer::new(obj)).unwrap();
+//!     assert_eq!(s, "hello");
+//!
+//!     let obj = 1.5.into_pyobject(py).unwrap();
+//!     let err = i32::deserialize(IntDeserializer::new(obj)).unwrap_err();
+//!     assert_eq!(err.to_string(), "TypeError: 'float' object cannot be interpreted as an integer");
+//! });
+//! ```
+
+use crate::{
+    de::{index, visit_int},
+    error::{Error, Result},
+};
+use pyo3::{prelude::*, types::*};
+use serde::de::{Deserializer, Visitor};
+
+/// Deserializer of a `bool`
+#[derive(Debug)]
+pub struct BoolDeserializer<'py> {
+    obj: Bound<'py, PyAny>,
+}
+
+impl<'py> BoolDeserializer<'py> {
+    /// Deserializer of `obj`
+    pub fn new<Any>(obj: Bound<'py, Any>) -> Self {
+        Self {
+            obj: obj.into_any(),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for BoolDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_bool(self.obj.downcast::<PyBool>()?.is_true())
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Deserializer of an `int`, or an object which has `__index__` such as `numpy.int64`
+///
+/// `bool` is visited as a boolean, and rejected by integer visitors as [`from_pyobject`](crate::from_pyobject) does.
+#[derive(Debug)]
+pub struct IntDeserializer<'py> {
+    obj: Bound<'py, PyAny>,
+}
+
+impl<'py> IntDeserializer<'py> {
+    /// Deserializer of `obj`
+    pub fn new<Any>(obj: Bound<'py, Any>) -> Self {
+        Self {
+            obj: obj.into_any(),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for IntDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let Ok(b) = self.obj.downcast::<PyBool>() {
+            return visitor.visit_bool(b.is_true());
+        }
+        visit_int(&index(&self.obj)?, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Deserializer of a `str`
+#[derive(Debug)]
+pub struct StrDeserializer<'py> {
+    obj: Bound<'py, PyAny>,
+}
+
+impl<'py> StrDeserializer<'py> {
+    /// Deserializer of `obj`
+    pub fn new<Any>(obj: Bound<'py, Any>) -> Self {
+        Self {
+            obj: obj.into_any(),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for StrDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_str(self.obj.downcast::<PyString>()?.to_str()?)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
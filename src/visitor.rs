@@ -0,0 +1,137 @@
+use pyo3::{prelude::*, types::*, IntoPyObjectExt};
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// [`Visitor`] which builds a Python object from any self-describing serde data
+///
+/// This is the counterpart of [`PyAnyDeserializer`](crate::PyAnyDeserializer)
+/// for manual [`Deserialize`](serde::Deserialize) implementations,
+/// e.g. to keep a part of the input as a Python object:
+///
+/// - `bool`, integers, floats and strings become `bool`, `int`, `float` and `str`
+/// - bytes become `bytes`
+/// - `None` and unit become `None`
+/// - sequences become `list`, and maps become `dict`
+///
+/// It also implements [`DeserializeSeed`] by [`Deserializer::deserialize_any`].
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use serde::de::DeserializeSeed;
+/// use serde_pyobject::{pydict, pylist, PyObjectVisitor};
+///
+/// Python::with_gil(|py| {
+///     let mut de = serde_json::Deserializer::from_str(r#"{ "a": [1, 2.5, null] }"#);
+///     let obj = PyObjectVisitor::new(py).deserialize(&mut de).unwrap();
+///     let expected = pydict! { py, "a" => pylist![py; 1, 2.5, py.None()].unwrap() }.unwrap();
+///     assert!(obj.eq(expected).unwrap());
+/// });
+/// ```
+#[derive(Clone, Copy)]
+pub struct PyObjectVisitor<'py> {
+    py: Python<'py>,
+}
+
+impl<'py> PyObjectVisitor<'py> {
+    /// Visitor which creates objects with the GIL token `py`
+    pub fn new(py: Python<'py>) -> Self {
+        Self { py }
+    }
+
+    fn object<T, E>(&self, value: T) -> Result<Bound<'py, PyAny>, E>
+    where
+        T: IntoPyObjectExt<'py>,
+        E: de::Error,
+    {
+        value.into_bound_py_any(self.py).map_err(E::custom)
+    }
+}
+
+impl fmt::Debug for PyObjectVisitor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PyObjectVisitor").finish_non_exhaustive()
+    }
+}
+
+impl<'de, 'py> Visitor<'de> for PyObjectVisitor<'py> {
+    type Value = Bound<'py, PyAny>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value representable in Python")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.object(v)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(PyBytes::new(self.py, v).into_any())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(self.py.None().into_bound(self.py))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let list = PyList::empty(self.py);
+        while let Some(value) = seq.next_element_seed(self)? {
+            list.append(value).map_err(de::Error::custom)?;
+        }
+        Ok(list.into_any())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let dict = PyDict::new(self.py);
+        while let Some((key, value)) = map.next_entry_seed(self, self)? {
+            dict.set_item(key, value).map_err(de::Error::custom)?;
+        }
+        Ok(dict.into_any())
+    }
+}
+
+impl<'de, 'py> DeserializeSeed<'de> for PyObjectVisitor<'py> {
+    type Value = Bound<'py, PyAny>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
@@ -0,0 +1,125 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject};
+
+#[test]
+fn deserialize_array() {
+    Python::with_gil(|py| {
+        let array = py.import("array").unwrap().getattr("array").unwrap();
+        for typecode in ["b", "B", "h", "H", "i", "I", "l", "L", "q", "Q"] {
+            let obj = array.call1((typecode, vec![1, 2, 3])).unwrap();
+            let values: Vec<i64> = from_pyobject(obj).unwrap();
+            assert_eq!(values, vec![1, 2, 3]);
+        }
+        for typecode in ["f", "d"] {
+            let obj = array.call1((typecode, vec![0.5, 1.5])).unwrap();
+            let values: Vec<f64> = from_pyobject(obj).unwrap();
+            assert_eq!(values, vec![0.5, 1.5]);
+        }
+        let obj = py
+            .eval(
+                c_str!("__import__('array').array('d', [1.0, 2.0])"),
+                None,
+                None,
+            )
+            .unwrap();
+        let values: (f32, f32) = from_pyobject(obj).unwrap();
+        assert_eq!(values, (1.0, 2.0));
+    });
+}
+
+#[test]
+fn deserialize_array_length_mismatch() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("__import__('array').array('i', [1, 2, 3])"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<(i32, i32), _>(obj).is_err());
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Samples {
+    #[serde(with = "serde_pyobject::array")]
+    bytes: Vec<u8>,
+    #[serde(with = "serde_pyobject::array")]
+    ints: Vec<i64>,
+    #[serde(with = "serde_pyobject::array")]
+    floats: Vec<f32>,
+    #[serde(with = "serde_pyobject::array")]
+    empty: Vec<u16>,
+}
+
+#[test]
+fn serialize_array() {
+    Python::with_gil(|py| {
+        let samples = Samples {
+            bytes: vec![0, 255],
+            ints: vec![-1, i64::MAX],
+            floats: vec![0.25, -1.5],
+            empty: vec![],
+        };
+        let obj = to_pyobject(py, &samples).unwrap();
+        for (field, typecode) in [
+            ("bytes", "B"),
+            ("ints", "q"),
+            ("floats", "f"),
+            ("empty", "H"),
+        ] {
+            let array = obj.get_item(field).unwrap();
+            assert_eq!(
+                array
+                    .getattr("typecode")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                typecode
+            );
+        }
+        let reverted: Samples = from_pyobject(obj).unwrap();
+        assert_eq!(reverted, samples);
+    });
+}
+
+#[test]
+fn other_formats() {
+    let samples: Samples =
+        serde_json::from_str(r#"{ "bytes": [1], "ints": [2], "floats": [3.0], "empty": [] }"#)
+            .unwrap();
+    assert_eq!(
+        serde_json::to_string(&samples).unwrap(),
+        r#"{"bytes":[1],"ints":[2],"floats":[3.0],"empty":[]}"#
+    );
+}
+
+#[test]
+fn other_buffers() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!(
+                    "memoryview(__import__('array').array('d', [0.5, 1.5]).tobytes()).cast('d')"
+                ),
+                None,
+                None,
+            )
+            .unwrap();
+        let values: Vec<f64> = from_pyobject(obj).unwrap();
+        assert_eq!(values, vec![0.5, 1.5]);
+
+        let obj = py.eval(c_str!("b'\\x01\\x02'"), None, None).unwrap();
+        let values: Vec<u8> = from_pyobject(obj).unwrap();
+        assert_eq!(values, vec![1, 2]);
+
+        // Multi-dimensional buffers are not flattened
+        let obj = py
+            .eval(c_str!("memoryview(bytes(4)).cast('B', (2, 2))"), None, None)
+            .unwrap();
+        let values: Vec<Vec<u8>> = from_pyobject(obj).unwrap();
+        assert_eq!(values, vec![vec![0, 0], vec![0, 0]]);
+    });
+}
@@ -0,0 +1,124 @@
+use pyo3::{prelude::*, types::PyDict};
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor},
+    Deserialize,
+};
+use serde_pyobject::{pydict, pylist, PyAnyDeserializer, PyObjectVisitor};
+use std::fmt;
+
+/// Keeps `extra` field as a Python object regardless of the input format
+struct Event {
+    kind: String,
+    extra: Py<PyAny>,
+}
+
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EventVisitor;
+
+        impl<'de> Visitor<'de> for EventVisitor {
+            type Value = Event;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an event")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Event, A::Error> {
+                Python::with_gil(|py| {
+                    let mut kind = None;
+                    let mut extra = None;
+                    while let Some(key) = map.next_key::<String>()? {
+                        match key.as_str() {
+                            "kind" => kind = Some(map.next_value()?),
+                            "extra" => {
+                                extra =
+                                    Some(map.next_value_seed(PyObjectVisitor::new(py))?.unbind())
+                            }
+                            _ => return Err(de::Error::unknown_field(&key, &["kind", "extra"])),
+                        }
+                    }
+                    Ok(Event {
+                        kind: kind.ok_or_else(|| de::Error::missing_field("kind"))?,
+                        extra: extra.unwrap_or_else(|| py.None()),
+                    })
+                })
+            }
+        }
+
+        deserializer.deserialize_map(EventVisitor)
+    }
+}
+
+#[test]
+fn py_object_visitor() {
+    Python::with_gil(|py| {
+        let event: Event = serde_json::from_str(
+            r#"{ "kind": "click", "extra": { "x": [1, -2, 0.5, true, null, "a"] } }"#,
+        )
+        .unwrap();
+        assert_eq!(event.kind, "click");
+        let expected = pydict! { py,
+            "x" => pylist![py; 1, -2, 0.5, true, py.None(), "a"].unwrap()
+        }
+        .unwrap();
+        assert!(event.extra.bind(py).eq(expected).unwrap());
+
+        let big = PyObjectVisitor::new(py)
+            .deserialize(&mut serde_json::Deserializer::from_str(
+                "18446744073709551615",
+            ))
+            .unwrap();
+        assert_eq!(big.extract::<u64>().unwrap(), u64::MAX);
+    });
+}
+
+#[test]
+fn py_any_deserializer() {
+    Python::with_gil(|py| {
+        let dict = pydict! { py, "kind" => "key", "extra" => (1, 2) }.unwrap();
+        let de = PyAnyDeserializer::new(dict).unwrap();
+        let event = Event::deserialize(de).unwrap();
+        assert_eq!(event.kind, "key");
+        assert!(event.extra.bind(py).eq(pylist![py; 1, 2].unwrap()).unwrap());
+
+        // Round trip of a Python object through the crate's dispatch
+        let obj = pydict! { py, "a" => pylist![py; 1, "b"].unwrap() }.unwrap();
+        let copy = PyObjectVisitor::new(py)
+            .deserialize(PyAnyDeserializer::new(obj.clone()).unwrap())
+            .unwrap();
+        assert!(copy.eq(&obj).unwrap());
+        assert!(!copy.is(&obj));
+    });
+}
+
+#[test]
+fn accessors() {
+    Python::with_gil(|py| {
+        struct Keys;
+        impl<'de> Visitor<'de> for Keys {
+            type Value = Vec<String>;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Vec<String>, A::Error> {
+                let mut keys = Vec::new();
+                while let Some((key, _)) = map.next_entry::<String, de::IgnoredAny>()? {
+                    keys.push(key);
+                }
+                Ok(keys)
+            }
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("a", 1).unwrap();
+        let map = PyAnyDeserializer::new(dict).unwrap().map_access().unwrap();
+        assert_eq!(Keys.visit_map(map).unwrap(), vec!["a"]);
+
+        let err = PyAnyDeserializer::new(pylist![py; 1].unwrap())
+            .unwrap()
+            .map_access()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'list' object cannot be converted to 'Mapping'"
+        );
+    });
+}
@@ -0,0 +1,79 @@
+use pyo3::{prelude::*, types::PyBytes};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_pyobject::{from_pyobject, pydict, to_pyobject};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+/// Byte string serialized by `serialize_bytes`, as `serde_bytes::ByteBuf` is
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct ByteBuf(Vec<u8>);
+
+impl Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl de::Visitor<'_> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+#[test]
+fn vec_u8_keys() {
+    Python::with_gil(|py| {
+        let dict = pydict! {
+            py,
+            PyBytes::new(py, b"ab") => 1,
+            PyBytes::new(py, b"") => 2
+        }
+        .unwrap();
+        let map: HashMap<Vec<u8>, i32> = from_pyobject(dict.clone()).unwrap();
+        assert_eq!(map, HashMap::from([(b"ab".to_vec(), 1), (Vec::new(), 2)]));
+
+        let obj = to_pyobject(py, &map).unwrap();
+        assert!(obj.eq(&dict).unwrap());
+    });
+}
+
+#[test]
+fn byte_buf_keys() {
+    Python::with_gil(|py| {
+        let dict = pydict! { py, PyBytes::new(py, b"\x00\xff") => "a" }.unwrap();
+        let map: BTreeMap<ByteBuf, String> = from_pyobject(dict.clone()).unwrap();
+        assert_eq!(
+            map,
+            BTreeMap::from([(ByteBuf(vec![0, 255]), "a".to_string())])
+        );
+
+        let obj = to_pyobject(py, &map).unwrap();
+        assert!(obj.eq(&dict).unwrap());
+    });
+}
+
+#[test]
+fn non_byte_list_keys() {
+    Python::with_gil(|py| {
+        // Lists are unhashable, and only those of integers in `range(256)` become `bytes`
+        let map = BTreeMap::from([(vec![1, 256], 0)]);
+        let err = to_pyobject(py, &map).unwrap_err();
+        assert_eq!(err.to_string(), "TypeError: unhashable type: 'list'");
+    });
+}
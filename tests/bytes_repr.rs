@@ -0,0 +1,188 @@
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyByteArray, PyBytes},
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, pylist, to_pyobject_with, BytesRepr,
+    PyObjectDeserializerOptions, PyObjectSerializerOptions,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// Byte string serialized by `serialize_bytes`, and deserialized from bytes or a sequence as `serde_bytes::ByteBuf` is
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ByteBuf(Vec<u8>);
+
+impl Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> de::Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(b) = seq.next_element()? {
+                    bytes.push(b);
+                }
+                Ok(ByteBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+#[test]
+fn representations() {
+    Python::with_gil(|py| {
+        let value = ByteBuf(b"\x00ab".to_vec());
+        for (repr, expected) in [
+            (BytesRepr::Bytes, "b'\\x00ab'"),
+            (BytesRepr::ByteArray, "bytearray(b'\\x00ab')"),
+            (BytesRepr::List, "[0, 97, 98]"),
+        ] {
+            let options = PyObjectSerializerOptions::new().bytes_repr(repr);
+            let obj = to_pyobject_with(py, &value, &options).unwrap();
+            assert_eq!(obj.repr().unwrap().to_string(), expected);
+            let back: ByteBuf = from_pyobject(obj).unwrap();
+            assert_eq!(back, value);
+        }
+
+        // `immutable` gives `bytes` and `tuple`
+        let options = PyObjectSerializerOptions::new()
+            .bytes_repr(BytesRepr::ByteArray)
+            .immutable(true);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert!(obj.is_exact_instance_of::<PyBytes>());
+        let options = options.bytes_repr(BytesRepr::List);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "(0, 97, 98)");
+    });
+}
+
+#[test]
+fn deserialize_any_representation() {
+    Python::with_gil(|py| {
+        for obj in [
+            PyBytes::new(py, b"ab").into_any(),
+            PyByteArray::new(py, b"ab").into_any(),
+            pylist![py; 97, 98].unwrap().into_any(),
+        ] {
+            let bytes: ByteBuf = from_pyobject(obj.clone()).unwrap();
+            assert_eq!(bytes, ByteBuf(b"ab".to_vec()));
+            let bytes: Vec<u8> = from_pyobject(obj).unwrap();
+            assert_eq!(bytes, b"ab");
+        }
+    });
+}
+
+#[test]
+fn deserialize_any_visits_bytes() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Blob {
+        Bytes(serde_bytes::ByteBuf),
+        Number(i64),
+    }
+
+    Python::with_gil(|py| {
+        for obj in [
+            PyBytes::new(py, b"ab").into_any(),
+            PyByteArray::new(py, b"ab").into_any(),
+            py.eval(c_str!("memoryview(b'ab')"), None, None).unwrap(),
+        ] {
+            let blob: Blob = from_pyobject(obj).unwrap();
+            assert_eq!(
+                blob,
+                Blob::Bytes(serde_bytes::ByteBuf::from(b"ab".to_vec()))
+            );
+        }
+        let blob: Blob = from_pyobject(1.into_pyobject(py).unwrap()).unwrap();
+        assert_eq!(blob, Blob::Number(1));
+
+        // Sequence targets still see the elements
+        let obj = py.eval(c_str!("memoryview(b'ab')"), None, None).unwrap();
+        let array: [u8; 2] = from_pyobject(obj).unwrap();
+        assert_eq!(array, *b"ab");
+    });
+}
+
+#[test]
+fn bytearray_keys() {
+    Python::with_gil(|py| {
+        // `bytearray` is unhashable, and keys become `bytes`
+        let map = BTreeMap::from([(ByteBuf(b"k".to_vec()), 1)]);
+        let options = PyObjectSerializerOptions::new().bytes_repr(BytesRepr::ByteArray);
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "{b'k': 1}");
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Packet {
+    #[serde(with = "serde_bytes")]
+    payload: Vec<u8>,
+    checksum: serde_bytes::ByteBuf,
+}
+
+#[test]
+fn serde_bytes_round_trip() {
+    Python::with_gil(|py| {
+        let packet = Packet {
+            payload: vec![0, 1, 255],
+            checksum: serde_bytes::ByteBuf::from(b"ok".to_vec()),
+        };
+        let obj = to_pyobject_with(py, &packet, &PyObjectSerializerOptions::new()).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'payload': b'\\x00\\x01\\xff', 'checksum': b'ok'}"
+        );
+        let back: Packet = from_pyobject(obj).unwrap();
+        assert_eq!(back, packet);
+
+        // `memoryview` is copied at once, including non-byte formats
+        let obj = py
+            .eval(
+                c_str!("{'payload': memoryview(b'\\x00\\x01\\xff'), 'checksum': memoryview(__import__('array').array('H', [1]))}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let back: Packet = from_pyobject(obj).unwrap();
+        assert_eq!(back.payload, vec![0, 1, 255]);
+        assert_eq!(back.checksum.len(), 2);
+    });
+}
+
+#[test]
+fn memoryview_length_limit() {
+    Python::with_gil(|py| {
+        let obj = py.eval(c_str!("memoryview(b'abcd')"), None, None).unwrap();
+        let options = PyObjectDeserializerOptions::new().max_str_len(3);
+        let err = from_pyobject_with::<serde_bytes::ByteBuf, _>(obj, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: memoryview of length 4 exceeds the limit 3"
+        );
+    });
+}
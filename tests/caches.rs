@@ -0,0 +1,29 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde_pyobject::{
+    clear_caches, pydict, to_pyobject_with, MapKeyEncoder, PyObjectSerializerOptions,
+};
+use std::collections::BTreeMap;
+
+#[test]
+fn clear_caches_() {
+    Python::with_gil(|py| {
+        let map = BTreeMap::from([(1, "a")]);
+        let options = PyObjectSerializerOptions::new().map_key_encoder(MapKeyEncoder::JsonDumps);
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert!(obj.eq(pydict! { py, "1" => "a" }.unwrap()).unwrap());
+
+        // Replace `json.dumps` as reloading a module does
+        py.run(
+            c_str!("import json; json.dumps = lambda obj: f'<{obj}>'"),
+            None,
+            None,
+        )
+        .unwrap();
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert!(obj.eq(pydict! { py, "1" => "a" }.unwrap()).unwrap());
+
+        clear_caches();
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert!(obj.eq(pydict! { py, "<1>" => "a" }.unwrap()).unwrap());
+    });
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    #[serde(with = "serde_pyobject::datetime")]
+    utc: DateTime<Utc>,
+    #[serde(with = "serde_pyobject::datetime")]
+    local: DateTime<FixedOffset>,
+    #[serde(with = "serde_pyobject::datetime")]
+    naive: NaiveDateTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    date: NaiveDate,
+    #[serde(with = "serde_pyobject::datetime")]
+    time: NaiveTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    elapsed: TimeDelta,
+    #[serde(with = "serde_pyobject::datetime::option")]
+    deadline: Option<NaiveDate>,
+}
+
+fn record() -> Record {
+    let naive = NaiveDate::from_ymd_opt(2024, 2, 29)
+        .unwrap()
+        .and_hms_micro_opt(12, 34, 56, 789)
+        .unwrap();
+    Record {
+        utc: naive.and_utc(),
+        local: naive
+            .and_local_timezone(FixedOffset::east_opt(9 * 3600).unwrap())
+            .unwrap(),
+        naive,
+        date: naive.date(),
+        time: naive.time(),
+        elapsed: TimeDelta::new(-2, 500_000_000).unwrap(),
+        deadline: None,
+    }
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let value = record();
+        let obj = to_pyobject(py, &value).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'utc': datetime.datetime(2024, 2, 29, 12, 34, 56, 789, tzinfo=datetime.timezone.utc), \
+             'local': datetime.datetime(2024, 2, 29, 12, 34, 56, 789, tzinfo=datetime.timezone(datetime.timedelta(seconds=32400))), \
+             'naive': datetime.datetime(2024, 2, 29, 12, 34, 56, 789), \
+             'date': datetime.date(2024, 2, 29), \
+             'time': datetime.time(12, 34, 56, 789), \
+             'elapsed': datetime.timedelta(days=-1, seconds=86398, microseconds=500000), \
+             'deadline': None}"
+        );
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, value);
+
+        let value = Record {
+            deadline: Some(value.date),
+            ..value
+        };
+        let obj = to_pyobject(py, &value).unwrap();
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, value);
+    });
+}
+
+#[test]
+fn iso_strings() {
+    Python::with_gil(|py| {
+        // ISO 8601 strings are accepted as well, e.g. from JSON
+        let obj = py
+            .eval(
+                c_str!("{'utc': '2024-02-29T12:34:56.000789Z', 'local': '2024-02-29T12:34:56.000789+09:00', 'naive': '2024-02-29T12:34:56.000789', 'date': '2024-02-29', 'time': '12:34:56.000789', 'elapsed': (-2, 500000), 'deadline': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, record());
+
+        // and JSON compatible output gives them
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &record(), &options).unwrap();
+        assert_eq!(
+            obj.get_item("local").unwrap().to_string(),
+            "2024-02-29T12:34:56.000789+09:00"
+        );
+        assert_eq!(obj.get_item("elapsed").unwrap().to_string(), "[-2, 500000]");
+    });
+}
+
+#[test]
+fn invalid() {
+    Python::with_gil(|py| {
+        #[derive(Debug, Deserialize)]
+        struct Stamp {
+            #[serde(with = "serde_pyobject::datetime")]
+            #[allow(dead_code)]
+            at: DateTime<Utc>,
+        }
+
+        // Naive `datetime` has no offset
+        let obj = py
+            .eval(
+                c_str!("{'at': __import__('datetime').datetime(2024, 1, 1)}"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<Stamp, _>(obj).is_err());
+    });
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "chrono-tz")]
+
+use chrono::{DateTime, NaiveDate, TimeZone};
+use chrono_tz::{America::New_York, Asia::Tokyo, Tz};
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Meeting {
+    #[serde(with = "serde_pyobject::datetime")]
+    at: DateTime<Tz>,
+    #[serde(with = "serde_pyobject::datetime::option")]
+    reminder: Option<DateTime<Tz>>,
+}
+
+#[test]
+fn zoneinfo_round_trip() {
+    Python::with_gil(|py| {
+        let naive = NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_micro_opt(12, 34, 56, 789)
+            .unwrap();
+        let meeting = Meeting {
+            at: New_York.from_local_datetime(&naive).unwrap(),
+            reminder: Some(Tokyo.from_local_datetime(&naive).unwrap()),
+        };
+        let obj = to_pyobject(py, &meeting).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'at': datetime.datetime(2024, 3, 10, 12, 34, 56, 789, tzinfo=zoneinfo.ZoneInfo(key='America/New_York')), \
+             'reminder': datetime.datetime(2024, 3, 10, 12, 34, 56, 789, tzinfo=zoneinfo.ZoneInfo(key='Asia/Tokyo'))}"
+        );
+        let reverted: Meeting = from_pyobject(obj).unwrap();
+        assert_eq!(reverted, meeting);
+        assert_eq!(reverted.at.timezone(), New_York);
+    });
+}
+
+#[test]
+fn fixed_offsets() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'at': __import__('datetime').datetime.fromisoformat('2024-01-01T00:00:00+00:00'), 'reminder': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let meeting: Meeting = from_pyobject(obj).unwrap();
+        assert_eq!(meeting.at.timezone(), Tz::UTC);
+
+        // Offsets other than UTC do not identify a zone
+        let obj = py
+            .eval(
+                c_str!("{'at': __import__('datetime').datetime.fromisoformat('2024-01-01T00:00:00+09:00'), 'reminder': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<Meeting, _>(obj).is_err());
+    });
+}
@@ -0,0 +1,154 @@
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyDict, PyList},
+};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject_with, pydict, to_pyobject_with, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Rect {
+    w: f64,
+    h: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle { r: f64 },
+    Rect(Rect),
+    Empty,
+}
+
+fn classes(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+from dataclasses import dataclass
+
+@dataclass
+class Circle:
+    r: float
+
+@dataclass
+class Rect:
+    w: float
+    h: float
+
+class Square(Rect):
+    def __init__(self, a):
+        super().__init__(a, a)
+
+class Empty:
+    pass
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+fn de_options(globals: &Bound<'_, PyDict>) -> PyObjectDeserializerOptions {
+    let class = |name: &str| {
+        globals
+            .get_item(name)
+            .unwrap()
+            .unwrap()
+            .downcast_into()
+            .unwrap()
+    };
+    PyObjectDeserializerOptions::new()
+        .class_variant("Shape", &class("Circle"), "Circle")
+        .class_variant("Shape", &class("Rect"), "Rect")
+        .class_variant("Shape", &class("Empty"), "Empty")
+}
+
+#[test]
+fn deserialize_class_variants() {
+    Python::with_gil(|py| {
+        let globals = classes(py);
+        let shapes = py
+            .eval(
+                c_str!(
+                    "[Circle(1.0), Rect(2.0, 3.0), Square(4.0), Empty(), {'Circle': {'r': 5.0}}]"
+                ),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+        let shapes: Vec<Shape> = from_pyobject_with(shapes, &de_options(&globals)).unwrap();
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::Circle { r: 1.0 },
+                Shape::Rect(Rect { w: 2.0, h: 3.0 }),
+                Shape::Rect(Rect { w: 4.0, h: 4.0 }),
+                Shape::Empty,
+                Shape::Circle { r: 5.0 },
+            ]
+        );
+    });
+}
+
+#[test]
+fn unregistered_enum() {
+    Python::with_gil(|py| {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Other {
+            Circle { r: f64 },
+        }
+        let globals = classes(py);
+        let dict = pydict! { py, "Circle" => pydict! { py, "r" => 1.0 }.unwrap() }.unwrap();
+        let other: Other = from_pyobject_with(dict, &de_options(&globals)).unwrap();
+        assert_eq!(other, Other::Circle { r: 1.0 });
+    });
+}
+
+#[test]
+fn serialize_class_variants() {
+    Python::with_gil(|py| {
+        let globals = classes(py);
+        let class = |name: &str| {
+            globals
+                .get_item(name)
+                .unwrap()
+                .unwrap()
+                .downcast_into()
+                .unwrap()
+        };
+        let options = PyObjectSerializerOptions::new()
+            .class_variant("Shape", &class("Circle"), "Circle")
+            .class_variant("Shape", &class("Rect"), "Rect")
+            .class_variant("Shape", &class("Empty"), "Empty");
+        let shapes = vec![
+            Shape::Circle { r: 1.0 },
+            Shape::Rect(Rect { w: 2.0, h: 3.0 }),
+            Shape::Empty,
+        ];
+        let obj = to_pyobject_with(py, &shapes, &options).unwrap();
+        for (item, name) in obj.try_iter().unwrap().zip(["Circle", "Rect", "Empty"]) {
+            assert!(item.unwrap().is_instance(&class(name)).unwrap());
+        }
+        let expected = py
+            .eval(
+                c_str!("[Circle(1.0), Rect(2.0, 3.0)]"),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+        assert!(obj
+            .downcast::<PyList>()
+            .unwrap()
+            .get_slice(0, 2)
+            .eq(expected)
+            .unwrap());
+
+        let reverted: Vec<Shape> = from_pyobject_with(obj, &de_options(&globals)).unwrap();
+        assert_eq!(reverted, shapes);
+    });
+}
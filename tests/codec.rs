@@ -0,0 +1,149 @@
+use pyo3::{prelude::*, types::PyDict};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{pydict, Codec, PyObjectDeserializerOptions, PyObjectSerializerOptions};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Record {
+    id: u32,
+    name: String,
+    inner: Inner,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Inner {
+    A { value: i32 },
+}
+
+fn record(id: u32) -> Record {
+    Record {
+        id,
+        name: format!("r{}", id),
+        inner: Inner::A {
+            value: -(id as i32),
+        },
+    }
+}
+
+#[test]
+fn round_trip() {
+    let codec = Codec::<Record>::new();
+    Python::with_gil(|py| {
+        for id in 0..10 {
+            let obj = codec.serialize(py, &record(id)).unwrap();
+            assert_eq!(codec.deserialize(obj).unwrap(), record(id));
+        }
+    });
+}
+
+fn keys(obj: Bound<'_, PyAny>) -> Vec<Bound<'_, PyAny>> {
+    obj.downcast::<PyDict>().unwrap().keys().iter().collect()
+}
+
+#[test]
+fn field_names_are_shared() {
+    let codec = Codec::<Record>::new();
+    Python::with_gil(|py| {
+        let first = keys(codec.serialize(py, &record(1)).unwrap());
+        let second = keys(codec.serialize(py, &record(2)).unwrap());
+        assert_eq!(first.len(), 3);
+        for (a, b) in first.iter().zip(&second) {
+            assert!(a.is(b));
+        }
+
+        // Clones share the names as well
+        let third = keys(codec.clone().serialize(py, &record(3)).unwrap());
+        assert!(first[0].is(&third[0]));
+    });
+}
+
+#[test]
+fn options() {
+    let de_options = PyObjectDeserializerOptions::new().max_elements(2);
+    let codec = Codec::<Vec<i32>>::with_options(&PyObjectSerializerOptions::new(), &de_options);
+    Python::with_gil(|py| {
+        let obj = codec.serialize(py, &vec![1, 2, 3]).unwrap();
+        let err = codec.deserialize(obj).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: total number of elements exceeds the limit 2"
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Defaults {
+    id: u32,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Strict {
+    id: u32,
+}
+
+#[test]
+fn planned_structs() {
+    Python::with_gil(|py| {
+        let codec = Codec::<Vec<Defaults>>::new();
+        let list = pyo3::types::PyList::new(
+            py,
+            [
+                pydict! { py, "id" => 1, "tags" => vec!["a"] }.unwrap(),
+                // The order of keys and missing fields with defaults do not matter
+                pydict! { py, "id" => 2 }.unwrap(),
+                // Other keys are ignored as usual
+                pydict! { py, "other" => 0, "id" => 3 }.unwrap(),
+            ],
+        )
+        .unwrap();
+        let expected = vec![
+            Defaults {
+                id: 1,
+                tags: vec!["a".to_string()],
+            },
+            Defaults {
+                id: 2,
+                tags: vec![],
+            },
+            Defaults {
+                id: 3,
+                tags: vec![],
+            },
+        ];
+        for _ in 0..2 {
+            assert_eq!(codec.deserialize(list.clone()).unwrap(), expected);
+        }
+
+        let codec = Codec::<Defaults>::new();
+        let err = codec
+            .deserialize(pydict! { py, "tags" => vec!["a"] }.unwrap())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "RuntimeError: missing field `id`");
+        let err = codec
+            .deserialize(pydict! { py, "id" => "x" }.unwrap())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            serde_pyobject::from_pyobject::<Defaults, _>(pydict! { py, "id" => "x" }.unwrap())
+                .unwrap_err()
+                .to_string()
+        );
+
+        let codec = Codec::<Strict>::new();
+        assert_eq!(
+            codec
+                .deserialize(pydict! { py, "id" => 1 }.unwrap())
+                .unwrap(),
+            Strict { id: 1 }
+        );
+        let err = codec
+            .deserialize(pydict! { py, "id" => 1, "other" => 0 }.unwrap())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: unknown field `other`, expected `id`"
+        );
+    });
+}
@@ -0,0 +1,158 @@
+use maplit::btreemap;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_columns, pydict, pylist, to_columns};
+
+#[derive(Serialize)]
+struct Record {
+    name: String,
+    score: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+#[test]
+fn structs() {
+    Python::with_gil(|py| {
+        let rows = vec![
+            Record {
+                name: "a".to_string(),
+                score: 1.0,
+                note: None,
+            },
+            Record {
+                name: "b".to_string(),
+                score: 2.5,
+                note: None,
+            },
+        ];
+        let columns = to_columns(py, &rows).unwrap();
+        assert!(columns
+            .eq(pydict! { py,
+                "name" => pylist![py; "a", "b"].unwrap(),
+                "score" => pylist![py; 1.0, 2.5].unwrap()
+            }
+            .unwrap())
+            .unwrap());
+    });
+}
+
+#[test]
+fn maps() {
+    Python::with_gil(|py| {
+        let rows = vec![
+            btreemap! { 1 => "a", 2 => "b" },
+            btreemap! { 1 => "c", 2 => "d" },
+        ];
+        let columns = to_columns(py, &rows).unwrap();
+        assert!(columns
+            .eq(pydict! { py,
+                1 => pylist![py; "a", "c"].unwrap(),
+                2 => pylist![py; "b", "d"].unwrap()
+            }
+            .unwrap())
+            .unwrap());
+    });
+}
+
+#[test]
+fn empty() {
+    Python::with_gil(|py| {
+        let columns = to_columns::<Record>(py, &[]).unwrap();
+        assert!(columns.is_empty());
+    });
+}
+
+#[test]
+fn inconsistent_fields() {
+    Python::with_gil(|py| {
+        let record = |note: Option<&str>| Record {
+            name: "a".to_string(),
+            score: 1.0,
+            note: note.map(str::to_string),
+        };
+
+        let err = to_columns(py, &[record(None), record(Some("x"))]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: row 1 has field 'note' which previous rows do not have"
+        );
+
+        let err = to_columns(py, &[record(Some("x")), record(None)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: row 1 does not have field 'note'"
+        );
+    });
+}
+
+#[test]
+fn not_records() {
+    Python::with_gil(|py| {
+        assert!(to_columns(py, &[1, 2]).is_err());
+    });
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: i32,
+    y: Option<i32>,
+}
+
+#[test]
+fn from_columns_() {
+    Python::with_gil(|py| {
+        let columns = pydict! { py,
+            "x" => pylist![py; 1, 2].unwrap(),
+            "y" => (3, py.None())
+        }
+        .unwrap();
+        let points: Vec<Point> = from_columns(columns).unwrap();
+        assert_eq!(
+            points,
+            vec![Point { x: 1, y: Some(3) }, Point { x: 2, y: None }]
+        );
+
+        let points: Vec<Point> = from_columns(PyDict::new(py)).unwrap();
+        assert!(points.is_empty());
+    });
+}
+
+#[test]
+fn from_columns_errors() {
+    Python::with_gil(|py| {
+        let columns = pydict! { py,
+            "x" => pylist![py; 1, 2].unwrap(),
+            "y" => pylist![py; 1].unwrap()
+        }
+        .unwrap();
+        let err = from_columns::<Point, _>(columns).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: column 'y' has 1 rows while column 'x' has 2 rows"
+        );
+
+        let columns = pydict! { py, "x" => 1 }.unwrap();
+        let err = from_columns::<Point, _>(columns).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: column 'x' is not a sequence"
+        );
+
+        let columns = pydict! { py,
+            "x" => pylist![py; 1, "a"].unwrap(),
+            "y" => pylist![py; 1, 2].unwrap()
+        }
+        .unwrap();
+        let err = from_columns::<Point, _>(columns).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: row 1: column 'x': invalid type: string \"a\", expected i32"
+        );
+
+        let columns = pydict! { py, "y" => pylist![py; 1].unwrap() }.unwrap();
+        let err = from_columns::<Point, _>(columns).unwrap_err();
+        assert_eq!(err.to_string(), "RuntimeError: row 0: missing field `x`");
+    });
+}
@@ -0,0 +1,66 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Signal {
+    #[serde(with = "serde_pyobject::complex")]
+    z: (f64, f64),
+    #[cfg(feature = "num-complex")]
+    #[serde(with = "serde_pyobject::complex")]
+    w: num_complex::Complex<f32>,
+}
+
+fn signal() -> Signal {
+    Signal {
+        z: (1.5, -2.0),
+        #[cfg(feature = "num-complex")]
+        w: num_complex::Complex::new(0.0, 1.0),
+    }
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let obj = to_pyobject(py, &signal()).unwrap();
+        assert_eq!(
+            obj.get_item("z").unwrap().repr().unwrap().to_string(),
+            "(1.5-2j)"
+        );
+        #[cfg(feature = "num-complex")]
+        assert_eq!(obj.get_item("w").unwrap().repr().unwrap().to_string(), "1j");
+        let back: Signal = from_pyobject(obj).unwrap();
+        assert_eq!(back, signal());
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &signal(), &options).unwrap();
+        assert_eq!(
+            obj.get_item("z").unwrap().repr().unwrap().to_string(),
+            "[1.5, -2.0]"
+        );
+        let back: Signal = from_pyobject(obj).unwrap();
+        assert_eq!(back, signal());
+    });
+}
+
+#[test]
+fn deserialize_complex() {
+    Python::with_gil(|py| {
+        // `complex` is a `(re, im)` tuple without the module
+        let obj = py
+            .eval(c_str!("[1+2j, complex(-0.5, 0)]"), None, None)
+            .unwrap();
+        let parts: Vec<(f64, f64)> = from_pyobject(obj).unwrap();
+        assert_eq!(parts, vec![(1.0, 2.0), (-0.5, 0.0)]);
+
+        // and real numbers are accepted by the module
+        #[derive(Debug, Deserialize)]
+        struct Real {
+            #[serde(with = "serde_pyobject::complex")]
+            z: (f64, f64),
+        }
+        let obj = py.eval(c_str!("{'z': 3}"), None, None).unwrap();
+        let real: Real = from_pyobject(obj).unwrap();
+        assert_eq!(real.z, (3.0, 0.0));
+    });
+}
@@ -0,0 +1,171 @@
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde_pyobject::{convert::object_to_dict, pydict};
+
+fn objects(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+from collections import UserDict
+from dataclasses import dataclass
+
+@dataclass
+class Point:
+    x: int
+    y: int
+
+@dataclass
+class Line:
+    start: Point
+    end: Point
+
+class Model:
+    """Stands for a pydantic v2 model, which is detected by duck typing"""
+    def __init__(self, name, point):
+        self.name = name
+        self.point = point
+    def model_dump(self):
+        return {"name": self.name, "point": {"x": self.point.x, "y": self.point.y}}
+
+class Plain:
+    def __init__(self):
+        self.a = 1
+
+class Slotted:
+    __slots__ = ("a", "unset")
+    def __init__(self):
+        self.a = 1
+
+class Derived(Slotted):
+    __slots__ = "b"
+    def __init__(self):
+        super().__init__()
+        self.b = 2
+
+line = Line(Point(0, 0), Point(1, 2))
+model = Model(name="m", point=Point(3, 4))
+plain = Plain()
+mapping = UserDict(a=1)
+slotted = Slotted()
+derived = Derived()
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+#[cfg(feature = "dataclass_support")]
+#[test]
+fn dataclass() {
+    use serde_pyobject::convert::dataclass_as_dict;
+    Python::with_gil(|py| {
+        let objects = objects(py);
+        let line = objects.get_item("line").unwrap().unwrap();
+        let dict = dataclass_as_dict(&line).unwrap().unwrap();
+        let expected = pydict! { py,
+            "start" => pydict! { py, "x" => 0, "y" => 0 }.unwrap(),
+            "end" => pydict! { py, "x" => 1, "y" => 2 }.unwrap()
+        }
+        .unwrap();
+        assert!(dict.eq(expected).unwrap());
+
+        let class = objects.get_item("Line").unwrap().unwrap();
+        assert!(dataclass_as_dict(&class).unwrap().is_none());
+        let model = objects.get_item("model").unwrap().unwrap();
+        assert!(dataclass_as_dict(&model).unwrap().is_none());
+    });
+}
+
+#[cfg(feature = "pydantic_support")]
+#[test]
+fn pydantic_model() {
+    use serde_pyobject::convert::pydantic_model_as_dict;
+    Python::with_gil(|py| {
+        let objects = objects(py);
+        let model = objects.get_item("model").unwrap().unwrap();
+        let dict = pydantic_model_as_dict(&model).unwrap().unwrap();
+        let expected = pydict! { py,
+            "name" => "m",
+            "point" => pydict! { py, "x" => 3, "y" => 4 }.unwrap()
+        }
+        .unwrap();
+        assert!(dict.eq(expected).unwrap());
+
+        let class = objects.get_item("Model").unwrap().unwrap();
+        assert!(pydantic_model_as_dict(&class).unwrap().is_none());
+        let line = objects.get_item("line").unwrap().unwrap();
+        assert!(pydantic_model_as_dict(&line).unwrap().is_none());
+    });
+}
+
+/// Requires attrs, which is an optional Python package.
+/// Run it by `cargo test --features attrs_support -- --ignored` with attrs installed.
+#[cfg(feature = "attrs_support")]
+#[test]
+#[ignore = "requires attrs"]
+fn attrs() {
+    use serde_pyobject::convert::attrs_as_dict;
+    Python::with_gil(|py| {
+        py.import("attr").expect("attrs is not installed");
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+import attr
+
+@attr.s(auto_attribs=True)
+class Point:
+    x: int
+    y: int
+
+point = Point(1, 2)
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let get = |name: &str| globals.get_item(name).unwrap().unwrap();
+        let expected = pydict! { py, "x" => 1, "y" => 2 }.unwrap();
+        assert!(attrs_as_dict(&get("point"))
+            .unwrap()
+            .unwrap()
+            .eq(&expected)
+            .unwrap());
+        assert!(object_to_dict(&get("point"))
+            .unwrap()
+            .eq(&expected)
+            .unwrap());
+        assert!(attrs_as_dict(&get("Point")).unwrap().is_none());
+        assert!(attrs_as_dict(&expected).unwrap().is_none());
+    });
+}
+
+#[test]
+fn fallback_chain() {
+    Python::with_gil(|py| {
+        let objects = objects(py);
+        let get = |name: &str| objects.get_item(name).unwrap().unwrap();
+
+        let dict = pydict! { py, "a" => 1 }.unwrap();
+        assert!(object_to_dict(&dict).unwrap().is(&dict));
+        assert!(object_to_dict(&get("mapping")).unwrap().eq(&dict).unwrap());
+        assert!(object_to_dict(&get("plain")).unwrap().eq(&dict).unwrap());
+        assert!(object_to_dict(&get("slotted")).unwrap().eq(&dict).unwrap());
+        assert!(object_to_dict(&get("derived"))
+            .unwrap()
+            .eq(pydict! { py, "a" => 1, "b" => 2 }.unwrap())
+            .unwrap());
+        assert!(object_to_dict(&get("line")).is_ok());
+        assert!(object_to_dict(&get("model")).is_ok());
+
+        let err = object_to_dict(1_i32.into_pyobject(py).unwrap().as_any()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'int' object cannot be converted to dict"
+        );
+    });
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "dataclass_support")]
+
+use pyo3::{exceptions::PyTypeError, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{clear_caches, create_dataclass, from_pyobject};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Record {
+    id: u32,
+    #[serde(rename = "label")]
+    name: String,
+    tags: Vec<String>,
+}
+
+#[test]
+fn create() {
+    Python::with_gil(|py| {
+        let class = create_dataclass::<Record>(py).unwrap();
+        assert_eq!(class.name().unwrap(), "Record");
+
+        let dataclasses = py.import("dataclasses").unwrap();
+        assert!(dataclasses
+            .call_method1("is_dataclass", (&class,))
+            .unwrap()
+            .is_truthy()
+            .unwrap());
+        let names: Vec<String> = dataclasses
+            .call_method1("fields", (&class,))
+            .unwrap()
+            .try_iter()
+            .unwrap()
+            .map(|field| field.unwrap().getattr("name").unwrap().extract().unwrap())
+            .collect();
+        assert_eq!(names, ["id", "label", "tags"]);
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("id", 1).unwrap();
+        kwargs.set_item("label", "a").unwrap();
+        kwargs.set_item("tags", vec!["x"]).unwrap();
+        let record: Record = from_pyobject(class.call((), Some(&kwargs)).unwrap()).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                id: 1,
+                name: "a".to_string(),
+                tags: vec!["x".to_string()]
+            }
+        );
+    });
+}
+
+#[test]
+fn cached() {
+    Python::with_gil(|py| {
+        #[derive(Deserialize)]
+        struct Cached {
+            _a: i32,
+        }
+
+        let first = create_dataclass::<Cached>(py).unwrap();
+        let second = create_dataclass::<Cached>(py).unwrap();
+        assert!(first.is(&second));
+
+        clear_caches();
+        let third = create_dataclass::<Cached>(py).unwrap();
+        assert!(!first.is(&third));
+    });
+}
+
+#[test]
+fn not_struct() {
+    Python::with_gil(|py| {
+        let err = create_dataclass::<HashMap<String, i32>>(py).unwrap_err();
+        let err = PyErr::from(err);
+        assert!(err.is_instance_of::<PyTypeError>(py));
+        assert_eq!(
+            err.value(py).to_string(),
+            "cannot create a dataclass from std::collections::hash::map::HashMap<alloc::string::String, i32>, which is not a struct"
+        );
+    });
+}
@@ -0,0 +1,202 @@
+use pyo3::{prelude::*, types::PyBytes};
+use serde::Deserialize;
+use serde_pyobject::{
+    from_pyobject_with, pydict, pylist, NonFinitePolicy, PyObjectDeserializerOptions,
+    UnknownKeyPolicy,
+};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Color {
+    Red,
+    Green,
+}
+
+#[test]
+fn max_str_len() {
+    Python::with_gil(|py| {
+        let options = PyObjectDeserializerOptions::new().max_str_len(3);
+
+        let list = pylist![py; "a", "abc"].unwrap();
+        let out: Vec<String> = from_pyobject_with(list, &options).unwrap();
+        assert_eq!(out, vec!["a", "abc"]);
+
+        let list = pylist![py; "a", "abcd"].unwrap();
+        let err = from_pyobject_with::<Vec<String>, _>(list, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: str of length 4 exceeds the limit 3"
+        );
+
+        let dict = pydict! { py, "long key" => 1 }.unwrap();
+        let err = from_pyobject_with::<BTreeMap<String, i32>, _>(dict, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: str of length 8 exceeds the limit 3"
+        );
+
+        let list = pylist![py; "Red", "Green"].unwrap();
+        let err = from_pyobject_with::<Vec<Color>, _>(list, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: str of length 5 exceeds the limit 3"
+        );
+
+        let bytes = PyBytes::new(py, b"abcd");
+        let err = from_pyobject_with::<Vec<u8>, _>(bytes, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: bytes of length 4 exceeds the limit 3"
+        );
+    });
+}
+
+#[test]
+fn max_elements() {
+    Python::with_gil(|py| {
+        let options = PyObjectDeserializerOptions::new().max_elements(4);
+
+        let dict = pydict! { py, "a" => (1, 2), "b" => (3, 4) }.unwrap();
+        let err =
+            from_pyobject_with::<BTreeMap<String, (i32, i32)>, _>(dict, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: total number of elements exceeds the limit 4"
+        );
+
+        let dict = pydict! { py, "a" => (1, 2), "b" => 3 }.unwrap();
+        let out: BTreeMap<String, serde_json::Value> = from_pyobject_with(dict, &options).unwrap();
+        assert_eq!(out.len(), 2);
+    });
+}
+
+#[test]
+fn stringify_keys() {
+    Python::with_gil(|py| {
+        let dict =
+            pydict! { py, 1 => "int", false => "bool", 1.5 => "float", "s" => "str" }.unwrap();
+        dict.set_item(py.None(), "none").unwrap();
+
+        let err = from_pyobject_with::<BTreeMap<String, String>, _>(
+            pydict! { py, 1 => "int" }.unwrap(),
+            &PyObjectDeserializerOptions::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: integer `1`, expected a string"
+        );
+
+        let options = PyObjectDeserializerOptions::new().stringify_keys(true);
+        let map: BTreeMap<String, String> = from_pyobject_with(dict, &options).unwrap();
+        assert_eq!(
+            map,
+            BTreeMap::from_iter(
+                [
+                    ("1", "int"),
+                    ("false", "bool"),
+                    ("1.5", "float"),
+                    ("s", "str"),
+                    ("null", "none")
+                ]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+            )
+        );
+
+        // Non-string key types are not affected
+        let dict = pydict! { py, 1 => "a" }.unwrap();
+        let map: BTreeMap<i32, String> = from_pyobject_with(dict, &options).unwrap();
+        assert_eq!(map[&1], "a");
+    });
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Polygon(Vec<Point>),
+}
+
+#[test]
+fn max_depth() {
+    Python::with_gil(|py| {
+        let polygon = pydict! {
+            py,
+            "Polygon" => pylist![py; pydict! { py, "x" => 1, "y" => 2 }.unwrap()].unwrap()
+        }
+        .unwrap();
+
+        // dict of the variant -> list -> dict of the point -> int
+        let options = PyObjectDeserializerOptions::new().max_depth(3);
+        let shape: Shape = from_pyobject_with(polygon.clone(), &options).unwrap();
+        assert_eq!(shape, Shape::Polygon(vec![Point { x: 1, y: 2 }]));
+
+        let options = PyObjectDeserializerOptions::new().max_depth(2);
+        let err = from_pyobject_with::<Shape, _>(polygon, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: nesting depth exceeds the limit 2"
+        );
+
+        // Scalars at the top level have no depth
+        let options = PyObjectDeserializerOptions::new().max_depth(0);
+        let n: i32 = from_pyobject_with(1_i32.into_pyobject(py).unwrap(), &options).unwrap();
+        assert_eq!(n, 1);
+    });
+}
+
+#[test]
+fn unknown_keys() {
+    Python::with_gil(|py| {
+        let dict = pydict! { py, "x" => 1, "y" => 2, "z" => 3 }.unwrap();
+        let point: Point =
+            from_pyobject_with(dict.clone(), &PyObjectDeserializerOptions::new()).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+
+        let options = PyObjectDeserializerOptions::new().unknown_keys(UnknownKeyPolicy::Error);
+        let err = from_pyobject_with::<Point, _>(dict, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: unknown field `z`, expected `x` or `y`"
+        );
+
+        let dict = pydict! { py, "x" => 1, "y" => 2, 0 => 3 }.unwrap();
+        let err = from_pyobject_with::<Point, _>(dict, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: unknown field `0`, expected `x` or `y`"
+        );
+
+        // Maps are not structs, and keep every key
+        let dict = pydict! { py, "z" => 3 }.unwrap();
+        let map: BTreeMap<String, i32> = from_pyobject_with(dict, &options).unwrap();
+        assert_eq!(map["z"], 3);
+    });
+}
+
+#[test]
+fn non_finite_floats() {
+    Python::with_gil(|py| {
+        let list = pylist![py; 1.5, f64::INFINITY, f64::NAN].unwrap();
+
+        let values: Vec<f64> = from_pyobject_with(list.clone(), &Default::default()).unwrap();
+        assert!(values[1].is_infinite() && values[2].is_nan());
+
+        let options = PyObjectDeserializerOptions::new().non_finite_floats(NonFinitePolicy::None);
+        let values: Vec<Option<f32>> = from_pyobject_with(list.clone(), &options).unwrap();
+        assert_eq!(values, vec![Some(1.5), None, None]);
+        let value: serde_json::Value = from_pyobject_with(list.clone(), &options).unwrap();
+        assert_eq!(value, serde_json::json!([1.5, null, null]));
+
+        let options = PyObjectDeserializerOptions::new().non_finite_floats(NonFinitePolicy::Error);
+        let err = from_pyobject_with::<Vec<f64>, _>(list, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: non-finite float inf is not allowed"
+        );
+    });
+}
@@ -0,0 +1,72 @@
+#![cfg(all(feature = "rust_decimal", feature = "bigdecimal"))]
+
+use bigdecimal::BigDecimal;
+use pyo3::{ffi::c_str, prelude::*};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Amounts {
+    #[serde(with = "serde_pyobject::decimal")]
+    fixed: Decimal,
+    #[serde(with = "serde_pyobject::decimal")]
+    big: BigDecimal,
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let value = Amounts {
+            fixed: Decimal::from_str("-12.3400").unwrap(),
+            big: BigDecimal::from_str("123456789012345678901234567890.000000001").unwrap(),
+        };
+        let obj = to_pyobject(py, &value).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'fixed': Decimal('-12.3400'), 'big': Decimal('123456789012345678901234567890.000000001')}"
+        );
+        let back: Amounts = from_pyobject(obj).unwrap();
+        assert_eq!(back, value);
+        // Trailing zeros are kept
+        assert_eq!(back.fixed.to_string(), "-12.3400");
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(obj.get_item("fixed").unwrap().to_string(), "-12.3400");
+    });
+}
+
+#[test]
+fn other_numbers() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'fixed': __import__('decimal').Decimal('1E-7'), 'big': 0.1}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let back: Amounts = from_pyobject(obj).unwrap();
+        assert_eq!(back.fixed, Decimal::new(1, 7));
+        assert_eq!(back.big, BigDecimal::from_str("0.1").unwrap());
+
+        let obj = py
+            .eval(c_str!("{'fixed': '1.5', 'big': 10**40}"), None, None)
+            .unwrap();
+        let back: Amounts = from_pyobject(obj).unwrap();
+        assert_eq!(back.fixed, Decimal::new(15, 1));
+        assert_eq!(back.big, BigDecimal::from_str("1e40").unwrap());
+
+        // `rust_decimal` has 96 bits, and does not round
+        let obj = py
+            .eval(
+                c_str!("{'fixed': __import__('decimal').Decimal('1.0000000000000000000000000000001'), 'big': 0}"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<Amounts, _>(obj).is_err());
+    });
+}
@@ -0,0 +1,60 @@
+use pyo3::prelude::*;
+use serde::Deserialize;
+use serde_pyobject::{from_pyobject, pydict};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Hash, Deserialize)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Circle(f64),
+    Square(f64),
+}
+
+#[test]
+fn enum_keys() {
+    Python::with_gil(|py| {
+        let dict = pydict! { py, "Red" => 1, "Blue" => 3 }.unwrap();
+        let map: HashMap<Color, i32> = from_pyobject(dict).unwrap();
+        assert_eq!(map, HashMap::from([(Color::Red, 1), (Color::Blue, 3)]));
+
+        let dict = pydict! { py, 0 => 1, 1 => 2 }.unwrap();
+        let map: HashMap<Color, i32> = from_pyobject(dict).unwrap();
+        assert_eq!(map, HashMap::from([(Color::Red, 1), (Color::Green, 2)]));
+
+        let dict = pydict! { py, "Purple" => 1 }.unwrap();
+        let err = from_pyobject::<HashMap<Color, i32>, _>(dict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: unknown variant `Purple`, expected one of `Red`, `Green`, `Blue`"
+        );
+
+        let dict = pydict! { py, 3 => 1 }.unwrap();
+        let err = from_pyobject::<HashMap<Color, i32>, _>(dict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid value: integer `3`, expected variant index 0 <= i < 3"
+        );
+    });
+}
+
+#[test]
+fn variant_index() {
+    Python::with_gil(|py| {
+        let dict = pydict! { py, 1 => 2.0 }.unwrap();
+        let shape: Shape = from_pyobject(dict).unwrap();
+        assert_eq!(shape, Shape::Square(2.0));
+
+        let err =
+            from_pyobject::<Color, _>(true.into_pyobject(py).unwrap().to_owned()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: boolean `true`, expected enum Color"
+        );
+    });
+}
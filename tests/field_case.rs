@@ -0,0 +1,121 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject_with, pydict, to_pyobject_with, Codec, FieldCase, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions,
+};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Request {
+    request_id: u32,
+    _internal_flag: bool,
+    #[serde(rename = "HTTPVersion")]
+    http_version: String,
+    headers: BTreeMap<String, String>,
+    body: Body,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Body {
+    JsonText { content_type: String },
+}
+
+fn request() -> Request {
+    Request {
+        request_id: 7,
+        _internal_flag: true,
+        http_version: "1.1".to_string(),
+        headers: BTreeMap::from([("user_agent".to_string(), "test".to_string())]),
+        body: Body::JsonText {
+            content_type: "application/json".to_string(),
+        },
+    }
+}
+
+#[test]
+fn cases() {
+    Python::with_gil(|py| {
+        for (case, expected) in [
+            (
+                FieldCase::Camel,
+                "{'requestId': 7, '_internalFlag': True, 'httpVersion': '1.1', 'headers': {'user_agent': 'test'}, 'body': {'JsonText': {'contentType': 'application/json'}}}",
+            ),
+            (
+                FieldCase::Pascal,
+                "{'RequestId': 7, '_InternalFlag': True, 'HttpVersion': '1.1', 'Headers': {'user_agent': 'test'}, 'Body': {'JsonText': {'ContentType': 'application/json'}}}",
+            ),
+            (
+                FieldCase::Kebab,
+                "{'request-id': 7, '_internal-flag': True, 'http-version': '1.1', 'headers': {'user_agent': 'test'}, 'body': {'JsonText': {'content-type': 'application/json'}}}",
+            ),
+            (
+                FieldCase::Snake,
+                "{'request_id': 7, '_internal_flag': True, 'http_version': '1.1', 'headers': {'user_agent': 'test'}, 'body': {'JsonText': {'content_type': 'application/json'}}}",
+            ),
+        ] {
+            let options = PyObjectSerializerOptions::new().field_case(case);
+            let obj = to_pyobject_with(py, &request(), &options).unwrap();
+            assert_eq!(obj.repr().unwrap().to_string(), expected);
+
+            let options = PyObjectDeserializerOptions::new().field_case(case);
+            let back: Request = from_pyobject_with(obj, &options).unwrap();
+            assert_eq!(back, request());
+        }
+    });
+}
+
+#[test]
+fn codec() {
+    Python::with_gil(|py| {
+        let codec = Codec::<Request>::with_options(
+            &PyObjectSerializerOptions::new().field_case(FieldCase::Camel),
+            &PyObjectDeserializerOptions::new().field_case(FieldCase::Camel),
+        );
+        for _ in 0..2 {
+            let obj = codec.serialize(py, &request()).unwrap();
+            assert!(obj.contains("requestId").unwrap());
+            assert_eq!(codec.deserialize(obj).unwrap(), request());
+        }
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Import {
+    from_: String,
+    type_: String,
+    class_name_: String,
+    r#as: Option<String>,
+}
+
+#[test]
+fn keyword_fields() {
+    Python::with_gil(|py| {
+        let import = Import {
+            from_: "os".to_string(),
+            type_: "module".to_string(),
+            class_name_: "Path".to_string(),
+            r#as: None,
+        };
+        let options = PyObjectSerializerOptions::new()
+            .keyword_fields(true)
+            .field_case(FieldCase::Camel);
+        let obj = to_pyobject_with(py, &import, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'from': 'os', 'type': 'module', 'className': 'Path', 'as': None}"
+        );
+
+        let options = PyObjectDeserializerOptions::new()
+            .keyword_fields(true)
+            .field_case(FieldCase::Camel);
+        let back: Import = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, import);
+
+        // Both forms are accepted
+        let dict =
+            pydict! { py, "from_" => "os", "type" => "module", "className" => "Path" }.unwrap();
+        let back: Import = from_pyobject_with(dict, &options).unwrap();
+        assert_eq!(back, import);
+    });
+}
@@ -0,0 +1,77 @@
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct Item {
+    name: String,
+    price: u32,
+}
+
+#[derive(Serialize)]
+struct Order {
+    items: Vec<Item>,
+    tags: BTreeMap<String, u32>,
+    price: u32,
+}
+
+fn order() -> Order {
+    Order {
+        items: vec![
+            Item {
+                name: "a".to_string(),
+                price: 1,
+            },
+            Item {
+                name: "b".to_string(),
+                price: 2,
+            },
+        ],
+        tags: BTreeMap::from([("x".to_string(), 1), ("y".to_string(), 2)]),
+        price: 3,
+    }
+}
+
+fn to_str(value: Bound<'_, PyAny>) -> PyResult<Bound<'_, PyAny>> {
+    Ok(value.str()?.into_any())
+}
+
+#[test]
+fn sequence_indices_are_skipped() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().field_override("items.price", to_str);
+        let obj = to_pyobject_with(py, &order(), &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'items': [{'name': 'a', 'price': '1'}, {'name': 'b', 'price': '2'}], 'tags': {'x': 1, 'y': 2}, 'price': 3}"
+        );
+    });
+}
+
+#[test]
+fn map_keys_and_whole_fields() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .field_override("tags.y", to_str)
+            .field_override("items", |value| {
+                Ok(value.len()?.into_pyobject(value.py())?.into_any())
+            });
+        let obj = to_pyobject_with(py, &order(), &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'items': 2, 'tags': {'x': 1, 'y': '2'}, 'price': 3}"
+        );
+    });
+}
+
+#[test]
+fn registering_again_replaces() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .field_override("price", to_str)
+            .field_override("price", |value| value.call_method1("__add__", (10,)));
+        let obj = to_pyobject_with(py, &order(), &options).unwrap();
+        assert_eq!(obj.get_item("price").unwrap().to_string(), "13");
+    });
+}
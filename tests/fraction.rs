@@ -0,0 +1,59 @@
+#![cfg(feature = "num-rational")]
+
+use num_rational::{Ratio, Rational64};
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Mix {
+    #[serde(with = "serde_pyobject::fraction")]
+    a: Rational64,
+    #[serde(with = "serde_pyobject::fraction")]
+    b: Ratio<u8>,
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let mix = Mix {
+            a: Rational64::new(-10, 4),
+            b: Ratio::new(1, 3),
+        };
+        let obj = to_pyobject(py, &mix).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'a': Fraction(-5, 2), 'b': Fraction(1, 3)}"
+        );
+        let back: Mix = from_pyobject(obj).unwrap();
+        assert_eq!(back, mix);
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &mix, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'a': [-5, 2], 'b': [1, 3]}"
+        );
+        let back: Mix = from_pyobject(obj).unwrap();
+        assert_eq!(back, mix);
+    });
+}
+
+#[test]
+fn other_representations() {
+    Python::with_gil(|py| {
+        let obj = py.eval(c_str!("{'a': 7, 'b': '2/6'}"), None, None).unwrap();
+        let mix: Mix = from_pyobject(obj).unwrap();
+        assert_eq!(mix.a, Rational64::from_integer(7));
+        assert_eq!(mix.b, Ratio::new(1, 3));
+
+        // Out of range for `u8`, or zero denominators are errors
+        for source in [
+            c_str!("{'a': 1, 'b': __import__('fractions').Fraction(1, 300)}"),
+            c_str!("{'a': (1, 0), 'b': 1}"),
+        ] {
+            let obj = py.eval(source, None, None).unwrap();
+            assert!(from_pyobject::<Mix, _>(obj).is_err());
+        }
+    });
+}
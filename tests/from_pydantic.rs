@@ -0,0 +1,103 @@
+#![cfg(feature = "pydantic_support")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_pyobject, from_pyobject_with, PyObjectDeserializerOptions};
+
+/// Globals defining `user`
+///
+/// Tests using this are ignored by default, since pydantic is an optional Python package.
+/// Run them by `cargo test -- --ignored` with pydantic installed.
+fn globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    py.import("pydantic").expect("pydantic is not installed");
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+from pydantic import BaseModel, Field
+
+class Address(BaseModel):
+    city: str
+
+class User(BaseModel):
+    user_name: str = Field(alias="userName")
+    age: int = 0
+    address: Address = Address(city="Tokyo")
+
+user = User(userName="alice", address={"city": "Osaka"})
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    user_name: String,
+    age: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Alias {
+    user_name: String,
+    #[serde(default = "unset")]
+    age: i64,
+}
+
+fn unset() -> i64 {
+    -1
+}
+
+#[test]
+#[ignore = "requires pydantic"]
+fn model_dump() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        let user = globals.get_item("user").unwrap().unwrap();
+
+        // Attributes by default
+        let plain: User = from_pyobject(user.clone()).unwrap();
+        assert_eq!(
+            plain,
+            User {
+                user_name: "alice".to_string(),
+                age: 0
+            }
+        );
+        let value: serde_json::Value = from_pyobject(user.clone()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"user_name": "alice", "age": 0, "address": {"city": "Osaka"}})
+        );
+
+        let options = PyObjectDeserializerOptions::new().pydantic_by_alias(true);
+        let alias: Alias = from_pyobject_with(user.clone(), &options).unwrap();
+        assert_eq!(alias.age, 0);
+
+        let options = options.pydantic_exclude_unset(true);
+        let alias: Alias = from_pyobject_with(user.clone(), &options).unwrap();
+        assert_eq!(
+            alias,
+            Alias {
+                user_name: "alice".to_string(),
+                age: -1
+            }
+        );
+        let value: serde_json::Value = from_pyobject_with(user.clone(), &options).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"userName": "alice", "address": {"city": "Osaka"}})
+        );
+
+        let options = PyObjectDeserializerOptions::new().pydantic_exclude_defaults(true);
+        let value: serde_json::Value = from_pyobject_with(user, &options).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"user_name": "alice", "address": {"city": "Osaka"}})
+        );
+    });
+}
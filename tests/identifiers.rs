@@ -0,0 +1,83 @@
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyString},
+};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_pyobject::{from_pyobject, from_pyobject_with, PyObjectDeserializerOptions};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Name,
+    Score,
+}
+
+#[test]
+fn field_identifier() {
+    Python::with_gil(|py| {
+        let field: Field = from_pyobject(PyString::new(py, "score")).unwrap();
+        assert_eq!(field, Field::Score);
+
+        // Other objects fall back to `deserialize_any`, e.g. `bytes`
+        let field: Field = from_pyobject(PyBytes::new(py, b"score")).unwrap();
+        assert_eq!(field, Field::Score);
+
+        let err = from_pyobject::<Field, _>(PyString::new(py, "age")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: unknown field `age`, expected `name` or `score`"
+        );
+    });
+}
+
+#[test]
+fn identifier_length_limit() {
+    Python::with_gil(|py| {
+        let options = PyObjectDeserializerOptions::new().max_str_len(3);
+        let err = from_pyobject_with::<Field, _>(PyString::new(py, "score"), &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: str of length 5 exceeds the limit 3"
+        );
+    });
+}
+
+/// Identifier which accepts only a string borrowed for `'de`
+#[derive(Debug)]
+struct BorrowedIdentifier<'de>(#[allow(dead_code)] &'de str);
+
+impl<'de> Deserialize<'de> for BorrowedIdentifier<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BorrowedVisitor;
+
+        impl<'de> Visitor<'de> for BorrowedVisitor {
+            type Value = BorrowedIdentifier<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a borrowed identifier")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(BorrowedIdentifier(v))
+            }
+        }
+
+        deserializer.deserialize_identifier(BorrowedVisitor)
+    }
+}
+
+#[test]
+fn identifier_is_not_borrowed() {
+    // The `&str` of a Python string is valid only while it is visited
+    Python::with_gil(|py| {
+        let err = from_pyobject::<BorrowedIdentifier, _>(PyString::new(py, "name")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: string \"name\", expected a borrowed identifier"
+        );
+    });
+}
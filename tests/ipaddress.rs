@@ -0,0 +1,88 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Host {
+    #[serde(with = "serde_pyobject::ipaddress")]
+    v4: Ipv4Addr,
+    #[serde(with = "serde_pyobject::ipaddress")]
+    v6: Ipv6Addr,
+    #[serde(with = "serde_pyobject::ipaddress")]
+    bind: SocketAddrV4,
+    #[serde(with = "serde_pyobject::ipaddress::option")]
+    gateway: Option<IpAddr>,
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let host = Host {
+            v4: Ipv4Addr::new(10, 0, 0, 1),
+            v6: Ipv6Addr::LOCALHOST,
+            bind: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 80),
+            gateway: Some(IpAddr::V6("fe80::1".parse().unwrap())),
+        };
+        let obj = to_pyobject(py, &host).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'v4': IPv4Address('10.0.0.1'), 'v6': IPv6Address('::1'), \
+             'bind': (IPv4Address('0.0.0.0'), 80), 'gateway': IPv6Address('fe80::1')}"
+        );
+        let back: Host = from_pyobject(obj).unwrap();
+        assert_eq!(back, host);
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &host, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'v4': '10.0.0.1', 'v6': '::1', 'bind': ['0.0.0.0', 80], 'gateway': 'fe80::1'}"
+        );
+        let back: Host = from_pyobject(obj).unwrap();
+        assert_eq!(back, host);
+    });
+}
+
+#[test]
+fn without_helper() {
+    Python::with_gil(|py| {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Peer {
+            ip: IpAddr,
+            addr: SocketAddr,
+            network: String,
+        }
+
+        let obj = py
+            .eval(
+                c_str!("{'ip': __import__('ipaddress').ip_address('192.168.1.2'), 'addr': '127.0.0.1:22', 'network': __import__('ipaddress').ip_network('10.0.0.0/8')}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let peer: Peer = from_pyobject(obj).unwrap();
+        assert_eq!(
+            peer,
+            Peer {
+                ip: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2)),
+                addr: "127.0.0.1:22".parse().unwrap(),
+                network: "10.0.0.0/8".to_string(),
+            }
+        );
+    });
+}
+
+#[test]
+fn mismatched_family() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'v4': '::1', 'v6': '::1', 'bind': ('0.0.0.0', 80), 'gateway': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<Host, _>(obj).is_err());
+    });
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "jiff")]
+
+use jiff::{
+    civil::{date, Date, DateTime, Time},
+    SignedDuration, Timestamp, Zoned,
+};
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    #[serde(with = "serde_pyobject::datetime")]
+    zoned: Zoned,
+    #[serde(with = "serde_pyobject::datetime")]
+    timestamp: Timestamp,
+    #[serde(with = "serde_pyobject::datetime")]
+    civil: DateTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    date: Date,
+    #[serde(with = "serde_pyobject::datetime")]
+    time: Time,
+    #[serde(with = "serde_pyobject::datetime")]
+    elapsed: SignedDuration,
+}
+
+fn record() -> Record {
+    let civil = date(2024, 3, 10).at(12, 34, 56, 789_000);
+    let zoned = civil.in_tz("America/New_York").unwrap();
+    Record {
+        timestamp: zoned.timestamp(),
+        zoned,
+        civil,
+        date: civil.date(),
+        time: civil.time(),
+        elapsed: SignedDuration::new(-1, -500_000_000),
+    }
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let value = record();
+        let obj = to_pyobject(py, &value).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'zoned': datetime.datetime(2024, 3, 10, 12, 34, 56, 789, tzinfo=zoneinfo.ZoneInfo(key='America/New_York')), \
+             'timestamp': datetime.datetime(2024, 3, 10, 16, 34, 56, 789, tzinfo=datetime.timezone.utc), \
+             'civil': datetime.datetime(2024, 3, 10, 12, 34, 56, 789), \
+             'date': datetime.date(2024, 3, 10), \
+             'time': datetime.time(12, 34, 56, 789), \
+             'elapsed': datetime.timedelta(days=-1, seconds=86398, microseconds=500000)}"
+        );
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, value);
+    });
+}
+
+#[test]
+fn fixed_offset() {
+    Python::with_gil(|py| {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Stamp {
+            #[serde(with = "serde_pyobject::datetime")]
+            at: Zoned,
+        }
+
+        // `datetime.timezone` has no name, and gives a fixed offset
+        let obj = py
+            .eval(
+                c_str!("{'at': __import__('datetime').datetime.fromisoformat('2024-03-10T12:00:00+05:30')}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let stamp: Stamp = from_pyobject(obj).unwrap();
+        assert_eq!(stamp.at.to_string(), "2024-03-10T12:00:00+05:30[+05:30]");
+
+        let obj = to_pyobject(py, &stamp).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'at': datetime.datetime(2024, 3, 10, 12, 0, tzinfo=datetime.timezone(datetime.timedelta(seconds=19800)))}"
+        );
+    });
+}
@@ -0,0 +1,35 @@
+use pyo3::{exceptions::PyImportError, ffi::c_str, prelude::*};
+use serde::Serialize;
+use serde_pyobject::{clear_caches, from_pyobject, to_pyobject};
+
+/// Standard modules may be unavailable in frozen or stripped environments,
+/// which must result in an error instead of a panic
+#[test]
+fn missing_array_module() {
+    Python::with_gil(|py| {
+        clear_caches();
+        py.run(
+            c_str!("import sys; sys.modules['array'] = None"),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let obj = py.eval(c_str!("object()"), None, None).unwrap();
+        let err = from_pyobject::<i32, _>(obj).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyImportError>(py));
+
+        #[derive(Serialize)]
+        struct B {
+            #[serde(with = "serde_pyobject::array")]
+            values: Vec<i32>,
+        }
+        let err = to_pyobject(py, &B { values: vec![1] }).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyImportError>(py));
+
+        py.run(c_str!("import sys; del sys.modules['array']"), None, None)
+            .unwrap();
+        clear_caches();
+        assert!(to_pyobject(py, &B { values: vec![1] }).is_ok());
+    });
+}
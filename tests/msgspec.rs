@@ -0,0 +1,81 @@
+#![cfg(feature = "msgspec")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{convert::msgspec_to_builtins, from_pyobject};
+
+/// Globals defining `User` and `Point`
+///
+/// Tests using this are ignored by default, since msgspec is an optional Python package.
+/// Run them by `cargo test --features msgspec -- --ignored` with msgspec installed.
+fn globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    py.import("msgspec").expect("msgspec is not installed");
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+import msgspec
+
+class User(msgspec.Struct):
+    name: str
+    raw: bytes
+    tags: list = []
+
+class Point(msgspec.Struct, array_like=True):
+    x: int
+    y: int
+
+user = User("alice", b"\x00\x01", ["a"])
+point = Point(1, 2)
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    name: String,
+    #[serde(with = "serde_bytes")]
+    raw: Vec<u8>,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+#[ignore = "requires msgspec"]
+fn msgspec_struct() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        let get = |name: &str| globals.get_item(name).unwrap().unwrap();
+
+        let user: User = from_pyobject(get("user")).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "alice".to_string(),
+                raw: vec![0, 1],
+                tags: vec!["a".to_string()],
+            }
+        );
+        let point: Point = from_pyobject(get("point")).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+        let value: serde_json::Value = from_pyobject(get("point")).unwrap();
+        assert_eq!(value, serde_json::json!([1, 2]));
+
+        let builtins = msgspec_to_builtins(&get("user")).unwrap().unwrap();
+        assert!(builtins
+            .get_item("raw")
+            .unwrap()
+            .is_instance_of::<pyo3::types::PyBytes>());
+        assert!(msgspec_to_builtins(&get("User")).unwrap().is_none());
+    });
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "numpy")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::from_pyobject;
+use std::ffi::CStr;
+
+/// `expr` evaluated with `np`
+///
+/// Tests using this are ignored by default, since numpy is an optional Python package.
+/// Run them by `cargo test --features numpy -- --ignored` with numpy installed.
+fn eval<'py>(py: Python<'py>, expr: &CStr) -> Bound<'py, PyAny> {
+    let numpy = py.import("numpy").expect("numpy is not installed");
+    let locals = PyDict::new(py);
+    locals.set_item("np", numpy).unwrap();
+    py.eval(expr, None, Some(&locals)).unwrap()
+}
+
+#[test]
+#[ignore = "requires numpy"]
+fn nested_sequences() {
+    Python::with_gil(|py| {
+        let obj = eval(py, c_str!("np.arange(6, dtype=np.float64).reshape(2, 3)"));
+        let rows: Vec<Vec<f64>> = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(rows, vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]]);
+        // Transposed, i.e. non-contiguous, arrays as well
+        let columns: Vec<Vec<i64>> = from_pyobject(obj.getattr("T").unwrap()).unwrap();
+        assert_eq!(columns, vec![vec![0, 3], vec![1, 4], vec![2, 5]]);
+
+        let obj = eval(py, c_str!("np.array([True, False])"));
+        let flags: Vec<bool> = from_pyobject(obj).unwrap();
+        assert_eq!(flags, vec![true, false]);
+    });
+}
+
+#[test]
+#[ignore = "requires numpy"]
+fn ndarray_struct() {
+    // `ndarray::Array2<i32>` in its serde format
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Array {
+        v: u8,
+        dim: (usize, usize),
+        data: Vec<i32>,
+    }
+
+    Python::with_gil(|py| {
+        let obj = eval(py, c_str!("np.arange(6, dtype=np.int32).reshape(3, 2)"));
+        let array: Array = from_pyobject(obj).unwrap();
+        assert_eq!(
+            array,
+            Array {
+                v: 1,
+                dim: (3, 2),
+                data: vec![0, 1, 2, 3, 4, 5]
+            }
+        );
+    });
+}
+
+#[test]
+#[ignore = "requires numpy"]
+fn scalars() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        id: i64,
+        score: f32,
+        valid: bool,
+        label: String,
+    }
+
+    Python::with_gil(|py| {
+        let obj = eval(
+            py,
+            c_str!("{'id': np.int64(1), 'score': np.float32(0.5), 'valid': np.bool_(True), 'label': np.str_('a')}"),
+        );
+        let row: Row = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                id: 1,
+                score: 0.5,
+                valid: true,
+                label: "a".to_string()
+            }
+        );
+        let value: serde_json::Value = from_pyobject(obj).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"id": 1, "score": 0.5, "valid": true, "label": "a"})
+        );
+    });
+}
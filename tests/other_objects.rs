@@ -0,0 +1,105 @@
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde_json::{json, Value};
+use serde_pyobject::from_pyobject;
+use std::collections::HashMap;
+
+fn eval<'py>(
+    py: Python<'py>,
+    globals: &Bound<'py, PyDict>,
+    expr: &std::ffi::CStr,
+) -> Bound<'py, PyAny> {
+    py.eval(expr, Some(globals), None).unwrap()
+}
+
+fn globals(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+from datetime import timezone
+
+class Plain:
+    def __init__(self):
+        self.a = 1
+        self.b = [1, 2]
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+#[test]
+fn iterables() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        let value: Value =
+            from_pyobject(eval(py, &globals, c_str!("(i * 2 for i in range(3))"))).unwrap();
+        assert_eq!(value, json!([0, 2, 4]));
+        let value: Value = from_pyobject(eval(py, &globals, c_str!("iter([1, 2])"))).unwrap();
+        assert_eq!(value, json!([1, 2]));
+        let value: Value = from_pyobject(eval(py, &globals, c_str!("{'x': 1}.keys()"))).unwrap();
+        assert_eq!(value, json!(["x"]));
+    });
+}
+
+#[test]
+fn objects_with_dict() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        let value: Value = from_pyobject(eval(py, &globals, c_str!("Plain()"))).unwrap();
+        assert_eq!(value, json!({"a": 1, "b": [1, 2]}));
+        let map: HashMap<String, Value> =
+            from_pyobject(eval(py, &globals, c_str!("Plain()"))).unwrap();
+        assert_eq!(map["a"], 1);
+    });
+}
+
+#[test]
+fn unsupported() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        for (expr, name) in [
+            (c_str!("object()"), "object"),
+            (c_str!("len"), "builtin_function_or_method"),
+            (c_str!("type"), "type"),
+            (c_str!("Plain"), "type"),
+            (c_str!("timezone.utc"), "timezone"),
+        ] {
+            let err = from_pyobject::<Value, _>(eval(py, &globals, expr)).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("RuntimeError: unsupported type: {name}")
+            );
+        }
+    });
+}
+
+#[test]
+fn iterable_object_into_map() {
+    Python::with_gil(|py| {
+        let globals = globals(py);
+        py.run(
+            c_str!(
+                r#"
+class Bag(Plain):
+    def __iter__(self):
+        return iter(self.b)
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let bag = eval(py, &globals, c_str!("Bag()"));
+        // The elements for sequences and `Value`, and `__dict__` for maps as `convert::object_to_dict` gives
+        let value: Value = from_pyobject(bag.clone()).unwrap();
+        assert_eq!(value, json!([1, 2]));
+        let map: HashMap<String, Value> = from_pyobject(bag.clone()).unwrap();
+        assert_eq!(map["b"], json!([1, 2]));
+        let dict = serde_pyobject::convert::object_to_dict(&bag).unwrap();
+        assert_eq!(dict.len(), map.len());
+    });
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "pandas")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_dataframe, from_pyobject, pylist};
+use std::{collections::HashMap, ffi::CStr};
+
+/// `expr` evaluated with `pd`
+///
+/// Tests using this are ignored by default, since pandas is an optional Python package.
+/// Run them by `cargo test --features pandas -- --ignored` with pandas installed.
+fn eval<'py>(py: Python<'py>, expr: &CStr) -> Bound<'py, PyAny> {
+    let pandas = py.import("pandas").expect("pandas is not installed");
+    let locals = PyDict::new(py);
+    locals.set_item("pd", pandas).unwrap();
+    py.eval(expr, None, Some(&locals)).unwrap()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Row {
+    name: String,
+    score: f64,
+}
+
+#[test]
+#[ignore = "requires pandas"]
+fn rows() {
+    Python::with_gil(|py| {
+        let df = eval(
+            py,
+            c_str!("pd.DataFrame({'name': ['a', 'b'], 'score': [0.5, 1.5]})"),
+        );
+        let expected = vec![
+            Row {
+                name: "a".to_string(),
+                score: 0.5,
+            },
+            Row {
+                name: "b".to_string(),
+                score: 1.5,
+            },
+        ];
+        let rows: Vec<Row> = from_dataframe(df.clone()).unwrap();
+        assert_eq!(rows, expected);
+        let rows: Vec<Row> = from_pyobject(df).unwrap();
+        assert_eq!(rows, expected);
+    });
+}
+
+#[test]
+fn not_a_dataframe() {
+    Python::with_gil(|py| {
+        let err = from_dataframe::<Row, _>(pylist![py; 1].unwrap()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: expected a data frame, got <class 'list'>"
+        );
+    });
+}
+
+#[test]
+#[ignore = "requires pandas"]
+fn series() {
+    Python::with_gil(|py| {
+        let series = eval(py, c_str!("pd.Series([0.5, 1.5], index=['a', 'b'])"));
+        let values: Vec<f64> = from_pyobject(series.clone()).unwrap();
+        assert_eq!(values, vec![0.5, 1.5]);
+        let map: HashMap<String, f64> = from_pyobject(series.clone()).unwrap();
+        assert_eq!(
+            map,
+            HashMap::from([("a".to_string(), 0.5), ("b".to_string(), 1.5)])
+        );
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Scores {
+            a: f64,
+            b: f64,
+        }
+        let scores: Scores = from_pyobject(series).unwrap();
+        assert_eq!(scores, Scores { a: 0.5, b: 1.5 });
+    });
+}
@@ -0,0 +1,92 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+use std::{ffi::OsString, path::PathBuf};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    #[serde(with = "serde_pyobject::path")]
+    root: PathBuf,
+    #[serde(with = "serde_pyobject::path")]
+    name: OsString,
+    #[serde(with = "serde_pyobject::path::option")]
+    cache: Option<PathBuf>,
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let config = Config {
+            root: PathBuf::from("/srv/data"),
+            name: OsString::from("app.toml"),
+            cache: None,
+        };
+        let obj = to_pyobject(py, &config).unwrap();
+        let path = py.import("pathlib").unwrap().getattr("Path").unwrap();
+        assert!(obj.get_item("root").unwrap().is_instance(&path).unwrap());
+        assert_eq!(obj.get_item("root").unwrap().to_string(), "/srv/data");
+        let back: Config = from_pyobject(obj).unwrap();
+        assert_eq!(back, config);
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &config, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'root': '/srv/data', 'name': 'app.toml', 'cache': None}"
+        );
+        let back: Config = from_pyobject(obj).unwrap();
+        assert_eq!(back, config);
+    });
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8() {
+    use std::os::unix::ffi::OsStringExt;
+
+    Python::with_gil(|py| {
+        let config = Config {
+            root: PathBuf::from(OsString::from_vec(b"/tmp/\xff".to_vec())),
+            name: OsString::from_vec(b"\xfe".to_vec()),
+            cache: Some(PathBuf::from("/tmp")),
+        };
+        let obj = to_pyobject(py, &config).unwrap();
+        // `os.fsdecode` escapes the bytes as surrogates
+        assert_eq!(
+            obj.get_item("root").unwrap().repr().unwrap().to_string(),
+            "PosixPath('/tmp/\\udcff')"
+        );
+        let back: Config = from_pyobject(obj).unwrap();
+        assert_eq!(back, config);
+    });
+}
+
+#[test]
+fn path_like() {
+    Python::with_gil(|py| {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Paths {
+            a: PathBuf,
+            b: String,
+            #[serde(with = "serde_pyobject::path")]
+            c: PathBuf,
+        }
+
+        let obj = py
+            .eval(
+                c_str!("{'a': __import__('pathlib').PurePosixPath('/a'), 'b': __import__('pathlib').PurePosixPath('b/c'), 'c': b'/c'}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let paths: Paths = from_pyobject(obj).unwrap();
+        assert_eq!(
+            paths,
+            Paths {
+                a: PathBuf::from("/a"),
+                b: "b/c".to_string(),
+                c: PathBuf::from("/c"),
+            }
+        );
+    });
+}
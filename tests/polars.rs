@@ -0,0 +1,53 @@
+#![cfg(feature = "polars")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_dataframe, from_pyobject};
+use std::ffi::CStr;
+
+/// `expr` evaluated with `pl`
+///
+/// Tests using this are ignored by default, since polars is an optional Python package.
+/// Run them by `cargo test --features polars -- --ignored` with polars installed.
+fn eval<'py>(py: Python<'py>, expr: &CStr) -> Bound<'py, PyAny> {
+    let polars = py.import("polars").expect("polars is not installed");
+    let locals = PyDict::new(py);
+    locals.set_item("pl", polars).unwrap();
+    py.eval(expr, None, Some(&locals)).unwrap()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Row {
+    name: String,
+    score: Option<f64>,
+}
+
+#[test]
+#[ignore = "requires polars"]
+fn dataframe_and_series() {
+    Python::with_gil(|py| {
+        let df = eval(
+            py,
+            c_str!("pl.DataFrame({'name': ['a', 'b'], 'score': [0.5, None]})"),
+        );
+        let expected = vec![
+            Row {
+                name: "a".to_string(),
+                score: Some(0.5),
+            },
+            Row {
+                name: "b".to_string(),
+                score: None,
+            },
+        ];
+        let rows: Vec<Row> = from_dataframe(df.clone()).unwrap();
+        assert_eq!(rows, expected);
+
+        let rows: Vec<Row> = from_pyobject(df.clone()).unwrap();
+        assert_eq!(rows, expected);
+
+        let series = df.get_item("score").unwrap();
+        let scores: Vec<Option<f64>> = from_pyobject(series).unwrap();
+        assert_eq!(scores, vec![Some(0.5), None]);
+    });
+}
@@ -0,0 +1,51 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::Serialize;
+use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+use std::sync::{Arc, Mutex};
+
+#[derive(Serialize)]
+struct Order {
+    id: u32,
+    items: Vec<String>,
+}
+
+#[test]
+fn paths_in_order() {
+    Python::with_gil(|py| {
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = paths.clone();
+        let options = PyObjectSerializerOptions::new().post_hook(move |value, path| {
+            recorded.lock().unwrap().push(path.to_string());
+            Ok(value)
+        });
+        let order = Order {
+            id: 1,
+            items: vec!["a".to_string(), "b".to_string()],
+        };
+        to_pyobject_with(py, &order, &options).unwrap();
+        // Children come first, and the root is not passed
+        assert_eq!(
+            *paths.lock().unwrap(),
+            vec!["$.id", "$.items[0]", "$.items[1]", "$.items"]
+        );
+    });
+}
+
+#[test]
+fn replace_and_fail() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .post_hook(|value, _| value.call_method0("upper").or(Ok(value)));
+        let obj = to_pyobject_with(py, &vec!["x", "y"], &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "['X', 'Y']");
+
+        let options = PyObjectSerializerOptions::new().post_hook(|value, path| {
+            if path.ends_with("[1]") {
+                return Err(PyValueError::new_err(format!("rejected {path}")));
+            }
+            Ok(value)
+        });
+        let err = to_pyobject_with(py, &vec![0, 1], &options).unwrap_err();
+        assert_eq!(err.to_string(), "ValueError: rejected $[1]");
+    });
+}
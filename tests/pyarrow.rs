@@ -0,0 +1,54 @@
+#![cfg(feature = "pyarrow")]
+
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_dataframe, from_pyobject};
+use std::ffi::CStr;
+
+/// `expr` evaluated with `pa`
+///
+/// Tests using this are ignored by default, since pyarrow is an optional Python package.
+/// Run them by `cargo test --features pyarrow -- --ignored` with pyarrow installed.
+fn eval<'py>(py: Python<'py>, expr: &CStr) -> Bound<'py, PyAny> {
+    let pyarrow = py.import("pyarrow").expect("pyarrow is not installed");
+    let locals = PyDict::new(py);
+    locals.set_item("pa", pyarrow).unwrap();
+    py.eval(expr, None, Some(&locals)).unwrap()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Row {
+    name: String,
+    score: Option<f64>,
+}
+
+#[test]
+#[ignore = "requires pyarrow"]
+fn table_and_record_batch() {
+    Python::with_gil(|py| {
+        let table = eval(
+            py,
+            c_str!("pa.table({'name': ['a', 'b'], 'score': [0.5, None]})"),
+        );
+        let expected = vec![
+            Row {
+                name: "a".to_string(),
+                score: Some(0.5),
+            },
+            Row {
+                name: "b".to_string(),
+                score: None,
+            },
+        ];
+        let rows: Vec<Row> = from_dataframe(table.clone()).unwrap();
+        assert_eq!(rows, expected);
+
+        let batch = table
+            .call_method0("to_batches")
+            .unwrap()
+            .get_item(0)
+            .unwrap();
+        let rows: Vec<Row> = from_pyobject(batch).unwrap();
+        assert_eq!(rows, expected);
+    });
+}
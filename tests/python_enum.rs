@@ -0,0 +1,73 @@
+use pyo3::{exceptions::PyTypeError, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{clear_caches, from_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Level {
+    Low,
+    #[serde(rename = "mid")]
+    Middle,
+    High,
+    Custom(u8),
+}
+
+#[test]
+fn members() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .python_enum::<Level>()
+            .unwrap();
+        let levels = vec![Level::Low, Level::Middle, Level::Low, Level::Custom(7)];
+        let obj = to_pyobject_with(py, &levels, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap(),
+            "[<Level.Low: 'Low'>, <Level.mid: 'mid'>, <Level.Low: 'Low'>, {'Custom': 7}]"
+        );
+        let class = obj.get_item(0).unwrap().get_type();
+        assert_eq!(class.len().unwrap(), 4);
+        assert!(obj.get_item(0).unwrap().is(&obj.get_item(2).unwrap()));
+
+        let back: Vec<Level> = from_pyobject(obj).unwrap();
+        assert_eq!(back, levels);
+    });
+}
+
+#[test]
+fn class_cached() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .python_enum::<Level>()
+            .unwrap();
+        let a = to_pyobject_with(py, &Level::High, &options).unwrap();
+        let b = to_pyobject_with(py, &Level::High, &options.clone()).unwrap();
+        assert!(a.is(&b));
+
+        clear_caches();
+        let c = to_pyobject_with(py, &Level::High, &options).unwrap();
+        assert!(!a.is(&c));
+        assert!(!a.eq(&c).unwrap());
+    });
+}
+
+#[test]
+fn unregistered_enum() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new();
+        let obj = to_pyobject_with(py, &Level::High, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "'High'");
+    });
+}
+
+#[test]
+fn not_enum() {
+    Python::with_gil(|py| {
+        let err = PyObjectSerializerOptions::new()
+            .python_enum::<Vec<u8>>()
+            .unwrap_err();
+        assert!(err.0.is_instance_of::<PyTypeError>(py));
+        assert_eq!(
+            err.0.value(py).to_string(),
+            "alloc::vec::Vec<u8> is not an enum"
+        );
+    });
+}
@@ -0,0 +1,188 @@
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_pyobject, from_pyobject_with, to_pyobject, PyObjectDeserializerOptions};
+
+fn eval<'py>(py: Python<'py>, expr: &std::ffi::CStr) -> Bound<'py, PyAny> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+from decimal import Decimal
+from uuid import UUID
+from fractions import Fraction
+from enum import IntEnum
+
+class Index:
+    """Integer-like object which is not a subclass of int, e.g. numpy.int64"""
+    def __init__(self, value):
+        self.value = value
+    def __index__(self):
+        return self.value
+
+class MyInt(int):
+    pass
+
+class MyFloat(float):
+    pass
+
+class MyStr(str):
+    pass
+
+class Color(IntEnum):
+    RED = 1
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    py.eval(expr, Some(&globals), None).unwrap()
+}
+
+#[test]
+fn integer_like() {
+    Python::with_gil(|py| {
+        let x: i32 = from_pyobject(eval(py, c_str!("Index(-3)"))).unwrap();
+        assert_eq!(x, -3);
+        let x: u8 = from_pyobject(eval(py, c_str!("MyInt(3)"))).unwrap();
+        assert_eq!(x, 3);
+        let x: u16 = from_pyobject(eval(py, c_str!("Color.RED"))).unwrap();
+        assert_eq!(x, 1);
+        let x: f64 = from_pyobject(eval(py, c_str!("Index(2)"))).unwrap();
+        assert_eq!(x, 2.0);
+        let x: Vec<i64> = from_pyobject(eval(py, c_str!("[Index(1), MyInt(2)]"))).unwrap();
+        assert_eq!(x, vec![1, 2]);
+
+        let err = from_pyobject::<u8, _>(eval(py, c_str!("Index(300)"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid value: integer `300`, expected u8"
+        );
+        let err = from_pyobject::<i32, _>(eval(py, c_str!("True"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: boolean `true`, expected i32"
+        );
+    });
+}
+
+#[test]
+fn large_integer() {
+    Python::with_gil(|py| {
+        let x: u64 = from_pyobject(eval(py, c_str!("2 ** 64 - 1"))).unwrap();
+        assert_eq!(x, u64::MAX);
+        let x: i128 = from_pyobject(eval(py, c_str!("-(2 ** 100)"))).unwrap();
+        assert_eq!(x, -(1 << 100));
+        let x: u128 = from_pyobject(eval(py, c_str!("2 ** 128 - 1"))).unwrap();
+        assert_eq!(x, u128::MAX);
+    });
+}
+
+#[test]
+fn serialize_large_integer() {
+    Python::with_gil(|py| {
+        for (value, expected) in [
+            (i128::MIN, "-(2 ** 127)"),
+            (-(1 << 100), "-(2 ** 100)"),
+            (i128::MAX, "2 ** 127 - 1"),
+        ] {
+            let obj = to_pyobject(py, &value).unwrap();
+            assert!(obj
+                .eq(eval(py, &std::ffi::CString::new(expected).unwrap()))
+                .unwrap());
+            assert_eq!(from_pyobject::<i128, _>(obj).unwrap(), value);
+        }
+        let obj = to_pyobject(py, &u128::MAX).unwrap();
+        assert!(obj.eq(eval(py, c_str!("2 ** 128 - 1"))).unwrap());
+        assert_eq!(from_pyobject::<u128, _>(obj).unwrap(), u128::MAX);
+
+        let obj = to_pyobject(py, &(1_u128 << 64)).unwrap();
+        let err = from_pyobject::<u64, _>(obj).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: integer `18446744073709551616` as i128, expected u64"
+        );
+    });
+}
+
+#[test]
+fn float_like() {
+    Python::with_gil(|py| {
+        let x: f64 = from_pyobject(eval(py, c_str!("Decimal('0.5')"))).unwrap();
+        assert_eq!(x, 0.5);
+        let x: f32 = from_pyobject(eval(py, c_str!("Fraction(1, 4)"))).unwrap();
+        assert_eq!(x, 0.25);
+        let x: f64 = from_pyobject(eval(py, c_str!("MyFloat(1.5)"))).unwrap();
+        assert_eq!(x, 1.5);
+
+        let err = from_pyobject::<f64, _>(eval(py, c_str!("False"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: boolean `false`, expected f64"
+        );
+    });
+}
+
+#[test]
+fn decimal_into_float() {
+    Python::with_gil(|py| {
+        let obj = eval(py, c_str!("Decimal('12.5')"));
+        let x: f64 = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(x, 12.5);
+        let value: serde_json::Value = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(value, serde_json::json!(12.5));
+
+        let options = PyObjectDeserializerOptions::new().lossy_decimals(false);
+        for err in [
+            from_pyobject_with::<f64, _>(obj.clone(), &options).unwrap_err(),
+            from_pyobject_with::<serde_json::Value, _>(obj, &options).unwrap_err(),
+        ] {
+            assert_eq!(
+                err.to_string(),
+                "TypeError: Decimal('12.5') is not converted into a float, see PyObjectDeserializerOptions::lossy_decimals"
+            );
+        }
+        // Other objects having `__float__` are not affected
+        let x: f32 = from_pyobject_with(eval(py, c_str!("Fraction(1, 4)")), &options).unwrap();
+        assert_eq!(x, 0.25);
+    });
+}
+
+#[test]
+fn untyped_scalars() {
+    Python::with_gil(|py| {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(untagged)]
+        enum Scalar {
+            Int(i64),
+            Float(f64),
+            Str(String),
+        }
+        let x: Vec<Scalar> = from_pyobject(eval(
+            py,
+            c_str!("[Index(1), Decimal('0.5'), MyStr('a'), MyInt(2)]"),
+        ))
+        .unwrap();
+        assert_eq!(
+            x,
+            vec![
+                Scalar::Int(1),
+                Scalar::Float(0.5),
+                Scalar::Str("a".to_string()),
+                Scalar::Int(2)
+            ]
+        );
+    });
+}
+
+#[test]
+fn uuid_as_string() {
+    Python::with_gil(|py| {
+        let id: String = from_pyobject(eval(
+            py,
+            c_str!("UUID('67e55044-10b1-426f-9247-bb680e5fe0c8')"),
+        ))
+        .unwrap();
+        assert_eq!(id, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    });
+}
@@ -0,0 +1,92 @@
+use pyo3::prelude::*;
+use serde::{Serialize, Serializer};
+use serde_pyobject::to_pyobject;
+use std::fmt;
+
+/// Serialized by `Display` through `collect_str`
+struct Displayed(&'static str);
+
+impl Serialize for Displayed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+/// Converted into a Python object while its `Display` writes into a scratch buffer
+struct NestedDisplay(&'static str);
+
+impl fmt::Display for NestedDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let inner = Python::with_gil(|py| {
+            to_pyobject(py, &Displayed(self.0))
+                .unwrap()
+                .extract::<String>()
+                .unwrap()
+        });
+        write!(f, "<{}>", inner)
+    }
+}
+
+impl Serialize for NestedDisplay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Converted into a Python object in the middle of its `Serialize`
+struct NestedSerialize;
+
+impl Serialize for NestedSerialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let repr = Python::with_gil(|py| {
+            let obj = to_pyobject(py, &Samples::new()).unwrap();
+            obj.repr().unwrap().to_string()
+        });
+        serializer.serialize_str(&repr)
+    }
+}
+
+#[derive(Serialize)]
+struct Samples {
+    label: Displayed,
+    #[serde(with = "serde_pyobject::array")]
+    ints: Vec<i32>,
+}
+
+impl Samples {
+    fn new() -> Self {
+        Samples {
+            label: Displayed("inner"),
+            ints: vec![1, 2],
+        }
+    }
+}
+
+#[test]
+fn nested_collect_str() {
+    Python::with_gil(|py| {
+        let obj = to_pyobject(
+            py,
+            &(
+                Displayed("a"),
+                NestedDisplay("b"),
+                Displayed("c"),
+                NestedDisplay("d"),
+            ),
+        )
+        .unwrap();
+        assert_eq!(obj.repr().unwrap(), "('a', '<b>', 'c', '<d>')");
+    });
+}
+
+#[test]
+fn nested_to_pyobject() {
+    Python::with_gil(|py| {
+        let obj = to_pyobject(py, &(Samples::new(), NestedSerialize, Samples::new())).unwrap();
+        let sample = "{'label': 'inner', 'ints': array('i', [1, 2])}";
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            format!("({sample}, \"{sample}\", {sample})")
+        );
+    });
+}
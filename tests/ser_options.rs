@@ -0,0 +1,278 @@
+use pyo3::prelude::*;
+use serde::{Serialize, Serializer};
+use serde_pyobject::{
+    pydict, to_pyobject_with, DuplicateKeyPolicy, MapKeyEncoder, NonFinitePolicy,
+    PyObjectSerializerOptions, SortKeys,
+};
+use std::collections::BTreeMap;
+
+/// Map which emits the same key more than once
+struct Pairs(Vec<(&'static str, i32)>);
+
+impl Serialize for Pairs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().cloned())
+    }
+}
+
+#[derive(Serialize)]
+struct Outer {
+    items: Vec<Pairs>,
+}
+
+#[test]
+fn duplicate_keys() {
+    Python::with_gil(|py| {
+        let pairs = Pairs(vec![("a", 1), ("b", 2), ("a", 3)]);
+
+        let options = PyObjectSerializerOptions::new();
+        let obj = to_pyobject_with(py, &pairs, &options).unwrap();
+        assert!(obj.eq(pydict! { py, "a" => 3, "b" => 2 }.unwrap()).unwrap());
+
+        let options =
+            PyObjectSerializerOptions::new().duplicate_keys(DuplicateKeyPolicy::FirstWins);
+        let obj = to_pyobject_with(py, &pairs, &options).unwrap();
+        assert!(obj.eq(pydict! { py, "a" => 1, "b" => 2 }.unwrap()).unwrap());
+
+        let options = PyObjectSerializerOptions::new().duplicate_keys(DuplicateKeyPolicy::Error);
+        let outer = Outer {
+            items: vec![Pairs(vec![("a", 1)]), pairs],
+        };
+        let err = to_pyobject_with(py, &outer, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: duplicate key 'a' in map at $.items[1]"
+        );
+    });
+}
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+enum Color {
+    Red,
+    Green,
+}
+
+#[test]
+fn map_key_encoder() {
+    Python::with_gil(|py| {
+        let map = BTreeMap::from([(1, "a"), (2, "b")]);
+        let options = PyObjectSerializerOptions::new().map_key_encoder(MapKeyEncoder::JsonDumps);
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert!(obj
+            .eq(pydict! { py, "1" => "a", "2" => "b" }.unwrap())
+            .unwrap());
+
+        let map = BTreeMap::from([(Color::Red, 1), (Color::Green, 2)]);
+        let options = PyObjectSerializerOptions::new().map_key_fn(|key| key.call_method0("lower"));
+        let obj = to_pyobject_with(py, &map, &options).unwrap();
+        assert!(obj
+            .eq(pydict! { py, "red" => 1, "green" => 2 }.unwrap())
+            .unwrap());
+    });
+}
+
+#[test]
+fn map_key_encoder_collision() {
+    Python::with_gil(|py| {
+        let map = BTreeMap::from([("A", 1), ("a", 2)]);
+        let options = PyObjectSerializerOptions::new()
+            .map_key_fn(|key| key.call_method0("lower"))
+            .duplicate_keys(DuplicateKeyPolicy::Error);
+        let err = to_pyobject_with(py, &map, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: duplicate key 'a' in map at $"
+        );
+    });
+}
+
+#[derive(Serialize)]
+struct Record {
+    label: String,
+    value: i32,
+}
+
+fn label<'py>(obj: &Bound<'py, PyAny>, i: usize) -> Bound<'py, PyAny> {
+    obj.get_item(i).unwrap().get_item("label").unwrap()
+}
+
+#[test]
+fn intern_strings() {
+    Python::with_gil(|py| {
+        let records = vec![
+            Record {
+                label: "cat".to_string(),
+                value: 1,
+            },
+            Record {
+                label: "cat".to_string(),
+                value: 2,
+            },
+        ];
+
+        let obj = to_pyobject_with(py, &records, &PyObjectSerializerOptions::new()).unwrap();
+        assert!(label(&obj, 0).eq(label(&obj, 1)).unwrap());
+        assert!(!label(&obj, 0).is(&label(&obj, 1)));
+
+        let options = PyObjectSerializerOptions::new().intern_strings(true);
+        let obj = to_pyobject_with(py, &records, &options).unwrap();
+        assert!(label(&obj, 0).is(&label(&obj, 1)));
+    });
+}
+
+#[derive(Serialize)]
+struct Profile {
+    name: String,
+    nickname: Option<String>,
+    links: BTreeMap<&'static str, Option<&'static str>>,
+    contact: Contact,
+}
+
+#[derive(Serialize)]
+enum Contact {
+    Email { address: Option<String> },
+}
+
+#[test]
+fn skip_none_fields() {
+    Python::with_gil(|py| {
+        let profile = Profile {
+            name: "alice".to_string(),
+            nickname: None,
+            links: BTreeMap::from([("home", Some("https://example.com")), ("blog", None)]),
+            contact: Contact::Email { address: None },
+        };
+
+        let obj = to_pyobject_with(py, &profile, &PyObjectSerializerOptions::new()).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'name': 'alice', 'nickname': None, 'links': {'blog': None, 'home': 'https://example.com'}, 'contact': {'Email': {'address': None}}}"
+        );
+
+        let options = PyObjectSerializerOptions::new().skip_none_fields(true);
+        let obj = to_pyobject_with(py, &profile, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'name': 'alice', 'links': {'home': 'https://example.com'}, 'contact': {'Email': {}}}"
+        );
+
+        // Elements of sequences are kept
+        let obj = to_pyobject_with(py, &vec![None, Some(1)], &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "[None, 1]");
+    });
+}
+
+#[derive(Serialize)]
+struct Unordered {
+    zeta: Pairs,
+    alpha: Contact,
+}
+
+#[test]
+fn sort_keys() {
+    Python::with_gil(|py| {
+        let value = Unordered {
+            zeta: Pairs(vec![("b", 2), ("a", 1)]),
+            alpha: Contact::Email { address: None },
+        };
+
+        let options = PyObjectSerializerOptions::new().sort_keys(SortKeys::TopLevel);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'alpha': {'Email': {'address': None}}, 'zeta': {'b': 2, 'a': 1}}"
+        );
+
+        let options = PyObjectSerializerOptions::new().sort_keys(SortKeys::Recursive);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'alpha': {'Email': {'address': None}}, 'zeta': {'a': 1, 'b': 2}}"
+        );
+
+        // Keys which cannot be compared
+        let mut map = BTreeMap::new();
+        map.insert(Some(1), 0);
+        map.insert(None, 0);
+        let err = to_pyobject_with(py, &map, &options).unwrap_err();
+        assert!(err.to_string().starts_with("TypeError: '<' not supported"));
+    });
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Polygon(Vec<(i32, i32)>),
+    Circle { radius: f64 },
+}
+
+#[test]
+fn immutable() {
+    Python::with_gil(|py| {
+        let value = (
+            vec![
+                Shape::Polygon(vec![(0, 0), (1, 1)]),
+                Shape::Circle { radius: 1.0 },
+            ],
+            BTreeMap::from([("tags", vec!["a"])]),
+        );
+        let options = PyObjectSerializerOptions::new().immutable(true);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "((mappingproxy({'Polygon': ((0, 0), (1, 1))}), mappingproxy({'Circle': mappingproxy({'radius': 1.0})})), mappingproxy({'tags': ('a',)}))"
+        );
+        assert!(obj.get_item(1).unwrap().set_item("x", 1).is_err());
+
+        // Takes precedence over `json_compatible`
+        let options = options.json_compatible(true);
+        let obj = to_pyobject_with(py, &(vec![1], &b"ab"[..]), &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "((1,), (97, 98))");
+    });
+}
+
+#[test]
+fn non_finite_floats() {
+    Python::with_gil(|py| {
+        let values = (f32::NAN, f64::NEG_INFINITY, 0.5);
+        let obj = to_pyobject_with(py, &values, &PyObjectSerializerOptions::new()).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "(nan, -inf, 0.5)");
+
+        let options = PyObjectSerializerOptions::new().non_finite_floats(NonFinitePolicy::None);
+        let obj = to_pyobject_with(py, &values, &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "(None, None, 0.5)");
+
+        // `Error` takes precedence over `json_compatible`
+        let options = PyObjectSerializerOptions::new()
+            .json_compatible(true)
+            .non_finite_floats(NonFinitePolicy::Error);
+        let map = BTreeMap::from([("a", vec![1.0, f64::NAN])]);
+        let err = to_pyobject_with(py, &map, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: non-finite float NaN at $['a'][1]"
+        );
+    });
+}
+
+#[test]
+fn chained_options() {
+    #[derive(Serialize)]
+    struct A {
+        a: Option<i32>,
+        b: Vec<f64>,
+    }
+
+    Python::with_gil(|py| {
+        let value = A {
+            a: None,
+            b: vec![1.0, f64::NAN],
+        };
+        // Builder methods compose, and each call returns the updated options
+        let options = PyObjectSerializerOptions::new()
+            .skip_none_fields(true)
+            .immutable(true)
+            .non_finite_floats(NonFinitePolicy::None);
+        let obj = to_pyobject_with(py, &value, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "mappingproxy({'b': (1.0, None)})");
+    });
+}
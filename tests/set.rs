@@ -0,0 +1,126 @@
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyFrozenSet, PySet},
+};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, to_pyobject, to_pyobject_with, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions,
+};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Permissions {
+    #[serde(with = "serde_pyobject::set")]
+    users: HashSet<String>,
+    #[serde(with = "serde_pyobject::set")]
+    codes: BTreeSet<(u8, u8)>,
+    plain: BTreeSet<i32>,
+}
+
+fn permissions() -> Permissions {
+    Permissions {
+        users: HashSet::from(["alice".to_string(), "bob".to_string()]),
+        codes: BTreeSet::from([(1, 2), (3, 4)]),
+        plain: BTreeSet::from([1, 2]),
+    }
+}
+
+#[test]
+fn serialize_set() {
+    Python::with_gil(|py| {
+        let obj = to_pyobject(py, &permissions()).unwrap();
+        let users = obj.get_item("users").unwrap();
+        assert!(users.is_exact_instance_of::<PySet>());
+        assert!(users
+            .eq(py.eval(c_str!("{'alice', 'bob'}"), None, None).unwrap())
+            .unwrap());
+        assert!(obj
+            .get_item("codes")
+            .unwrap()
+            .eq(py.eval(c_str!("{(1, 2), (3, 4)}"), None, None).unwrap())
+            .unwrap());
+        // Without the attribute, sets are sequences
+        assert_eq!(
+            obj.get_item("plain").unwrap().repr().unwrap().to_string(),
+            "[1, 2]"
+        );
+
+        let reverted: Permissions = from_pyobject(obj).unwrap();
+        assert_eq!(reverted, permissions());
+    });
+}
+
+#[test]
+fn serialize_frozenset() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().immutable(true);
+        let obj = to_pyobject_with(py, &permissions(), &options).unwrap();
+        assert!(obj
+            .get_item("users")
+            .unwrap()
+            .is_exact_instance_of::<PyFrozenSet>());
+
+        let reverted: Permissions = from_pyobject(obj).unwrap();
+        assert_eq!(reverted, permissions());
+
+        // `json_compatible` gives `list`
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &permissions(), &options).unwrap();
+        assert_eq!(
+            obj.get_item("codes").unwrap().repr().unwrap().to_string(),
+            "[[1, 2], [3, 4]]"
+        );
+    });
+}
+
+#[test]
+fn deserialize_set() {
+    Python::with_gil(|py| {
+        let obj = py.eval(c_str!("{3, 1, 2}"), None, None).unwrap();
+        let set: BTreeSet<i32> = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(set, BTreeSet::from([1, 2, 3]));
+        let mut values: Vec<i32> = from_pyobject(obj).unwrap();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let obj = py.eval(c_str!("frozenset(['a'])"), None, None).unwrap();
+        let set: HashSet<String> = from_pyobject(obj).unwrap();
+        assert_eq!(set, HashSet::from(["a".to_string()]));
+    });
+}
+
+#[test]
+fn unique_sets() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'users': ['alice', 'alice'], 'codes': [(1, 2)], 'plain': [1, 1]}"),
+                None,
+                None,
+            )
+            .unwrap();
+        // Duplicates are dropped by default
+        let permissions: Permissions = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(permissions.users, HashSet::from(["alice".to_string()]));
+
+        let options = PyObjectDeserializerOptions::new().unique_sets(true);
+        let err = from_pyobject_with::<Permissions, _>(obj, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: duplicate element 'alice' in a set"
+        );
+
+        // Fields without the attribute, and `set` objects are not checked
+        let obj = py
+            .eval(
+                c_str!("{'users': {'alice'}, 'codes': ((1, 2), (3, 4)), 'plain': [1, 1]}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let permissions: Permissions = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(permissions.plain, BTreeSet::from([1]));
+    });
+}
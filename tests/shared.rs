@@ -0,0 +1,69 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, pydict, pylist, to_pyobject};
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Inner {
+    value: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Node {
+    #[serde(with = "serde_pyobject::shared")]
+    inner: Arc<Inner>,
+}
+
+#[test]
+fn aliased_objects_are_shared() {
+    Python::with_gil(|py| {
+        let inner = pydict! { py, "value" => 1 }.unwrap();
+        let a = pydict! { py, "inner" => &inner }.unwrap();
+        let b = pydict! { py, "inner" => &inner }.unwrap();
+        let nodes: Vec<Node> = from_pyobject(pylist![py; a, b].unwrap()).unwrap();
+        assert!(Arc::ptr_eq(&nodes[0].inner, &nodes[1].inner));
+    });
+}
+
+#[test]
+fn equal_objects_are_not_shared() {
+    Python::with_gil(|py| {
+        let a = pydict! { py, "inner" => pydict! { py, "value" => 1 }.unwrap() }.unwrap();
+        let b = pydict! { py, "inner" => pydict! { py, "value" => 1 }.unwrap() }.unwrap();
+        let nodes: Vec<Node> = from_pyobject(pylist![py; a, b].unwrap()).unwrap();
+        assert_eq!(nodes[0], nodes[1]);
+        assert!(!Arc::ptr_eq(&nodes[0].inner, &nodes[1].inner));
+    });
+}
+
+#[test]
+fn not_shared_across_calls() {
+    Python::with_gil(|py| {
+        let inner = pydict! { py, "value" => 1 }.unwrap();
+        let node = pydict! { py, "inner" => &inner }.unwrap();
+        let first: Node = from_pyobject(node.clone()).unwrap();
+        let second: Node = from_pyobject(node).unwrap();
+        assert!(!Arc::ptr_eq(&first.inner, &second.inner));
+    });
+}
+
+#[test]
+fn revertible() {
+    Python::with_gil(|py| {
+        let node = Node {
+            inner: Arc::new(Inner { value: 3 }),
+        };
+        let obj = to_pyobject(py, &node).unwrap();
+        assert!(obj
+            .eq(pydict! { py, "inner" => pydict! { py, "value" => 3 }.unwrap() }.unwrap())
+            .unwrap());
+        let reverted: Node = from_pyobject(obj).unwrap();
+        assert_eq!(node, reverted);
+    });
+}
+
+#[test]
+fn other_formats() {
+    let node: Node = serde_json::from_str(r#"{ "inner": { "value": 2 } }"#).unwrap();
+    assert_eq!(node.inner.value, 2);
+}
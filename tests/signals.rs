@@ -0,0 +1,29 @@
+//! Python handles signals only in the thread which initialized the interpreter,
+//! so this file has a single test which initializes it.
+
+use pyo3::{ffi, ffi::c_str, prelude::*, types::PyList};
+use serde_pyobject::{from_pyobject, to_pyobject};
+
+#[test]
+fn keyboard_interrupt() {
+    Python::with_gil(|py| {
+        // The embedded interpreter does not install the handler of SIGINT by default
+        py.run(
+            c_str!("import signal; signal.signal(signal.SIGINT, signal.default_int_handler)"),
+            None,
+            None,
+        )
+        .unwrap();
+        let values = vec![0_i32; 10_000];
+
+        // Simulate Ctrl-C
+        unsafe { ffi::PyErr_SetInterrupt() };
+        let err = to_pyobject(py, &values).unwrap_err();
+        assert_eq!(err.to_string(), "KeyboardInterrupt: ");
+
+        let list = PyList::new(py, &values).unwrap();
+        unsafe { ffi::PyErr_SetInterrupt() };
+        let err = from_pyobject::<Vec<i32>, _>(list).unwrap_err();
+        assert_eq!(err.to_string(), "KeyboardInterrupt: ");
+    });
+}
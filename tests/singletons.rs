@@ -0,0 +1,74 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, to_pyobject, Ellipsis, NotImplemented,
+    PyObjectDeserializerOptions, SingletonPolicy,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Slot {
+    All(Ellipsis),
+    Missing(NotImplemented),
+    At(usize),
+}
+
+#[test]
+fn markers_round_trip() {
+    Python::with_gil(|py| {
+        let obj = to_pyobject(py, &Ellipsis).unwrap();
+        assert!(obj.is(&py.Ellipsis()));
+        assert_eq!(from_pyobject::<Ellipsis, _>(obj).unwrap(), Ellipsis);
+
+        let obj = to_pyobject(py, &NotImplemented).unwrap();
+        assert!(obj.is(&py.NotImplemented()));
+        assert_eq!(
+            from_pyobject::<NotImplemented, _>(obj).unwrap(),
+            NotImplemented
+        );
+
+        let err = from_pyobject::<Ellipsis, _>(py.NotImplemented().into_bound(py)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: NotImplemented is not supported, see PyObjectDeserializerOptions::singletons"
+        );
+        assert!(from_pyobject::<Ellipsis, _>(py.None().into_bound(py)).is_err());
+    });
+}
+
+#[test]
+fn policy() {
+    Python::with_gil(|py| {
+        let ellipsis = py.Ellipsis().into_bound(py);
+
+        let err = from_pyobject::<serde_json::Value, _>(ellipsis.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: Ellipsis is not supported, see PyObjectDeserializerOptions::singletons"
+        );
+
+        let options = PyObjectDeserializerOptions::new().singletons(SingletonPolicy::None);
+        let value: Option<i32> = from_pyobject_with(ellipsis.clone(), &options).unwrap();
+        assert_eq!(value, None);
+
+        let options = PyObjectDeserializerOptions::new().singletons(SingletonPolicy::Marker);
+        let value: serde_json::Value = from_pyobject_with(ellipsis.clone(), &options).unwrap();
+        assert_eq!(value, serde_json::json!("Ellipsis"));
+    });
+}
+
+#[test]
+fn untagged_markers() {
+    Python::with_gil(|py| {
+        let options = PyObjectDeserializerOptions::new().singletons(SingletonPolicy::Marker);
+        let slots = vec![
+            Slot::All(Ellipsis),
+            Slot::Missing(NotImplemented),
+            Slot::At(3),
+        ];
+        let obj = to_pyobject(py, &slots).unwrap();
+        assert_eq!(obj.repr().unwrap(), "[Ellipsis, NotImplemented, 3]");
+        let back: Vec<Slot> = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, slots);
+    });
+}
@@ -0,0 +1,130 @@
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, to_pyobject, to_pyobject_with, NaiveDateTimePolicy,
+    PyObjectDeserializerOptions, PyObjectSerializerOptions,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Job {
+    #[serde(with = "serde_pyobject::datetime")]
+    started: SystemTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    timeout: Duration,
+    #[serde(with = "serde_pyobject::datetime::option")]
+    finished: Option<SystemTime>,
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let job = Job {
+            started: UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_000),
+            timeout: Duration::from_millis(1500),
+            finished: Some(UNIX_EPOCH - Duration::from_micros(1)),
+        };
+        let obj = to_pyobject(py, &job).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'started': datetime.datetime(2023, 11, 14, 22, 13, 20, 123456, tzinfo=datetime.timezone.utc), \
+             'timeout': datetime.timedelta(seconds=1, microseconds=500000), \
+             'finished': datetime.datetime(1969, 12, 31, 23, 59, 59, 999999, tzinfo=datetime.timezone.utc)}"
+        );
+        let back: Job = from_pyobject(obj).unwrap();
+        assert_eq!(back, job);
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &job, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'started': [1700000000, 123456], 'timeout': [1, 500000], 'finished': [-1, 999999]}"
+        );
+    });
+}
+
+#[test]
+fn aware_datetime() {
+    Python::with_gil(|py| {
+        // Any time zone gives the same instant
+        let obj = py
+            .eval(
+                c_str!("{'started': __import__('datetime').datetime.fromisoformat('2023-11-15T07:13:20+09:00'), 'timeout': __import__('datetime').timedelta(minutes=1), 'finished': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let job: Job = from_pyobject(obj).unwrap();
+        assert_eq!(job.started, UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert_eq!(job.timeout, Duration::from_secs(60));
+
+        let obj = py
+            .eval(
+                c_str!("{'started': __import__('datetime').datetime(2023, 1, 1), 'timeout': __import__('datetime').timedelta(-1), 'finished': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(from_pyobject::<Job, _>(obj).is_err());
+    });
+}
+
+#[test]
+fn naive_datetime_policy() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'started': __import__('datetime').datetime(2023, 11, 14, 22, 13, 20), 'timeout': __import__('datetime').timedelta(0), 'finished': None}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let err = from_pyobject::<Job, _>(obj.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: naive datetime 2023-11-14 22:13:20 is not allowed, see PyObjectDeserializerOptions::naive_datetimes"
+        );
+
+        let options =
+            PyObjectDeserializerOptions::new().naive_datetimes(NaiveDateTimePolicy::AssumeUtc);
+        let job: Job = from_pyobject_with(obj.clone(), &options).unwrap();
+        assert_eq!(job.started, UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+
+        // Same as Python regards the naive `datetime` as the local time
+        let options =
+            PyObjectDeserializerOptions::new().naive_datetimes(NaiveDateTimePolicy::AssumeLocal);
+        let job: Job = from_pyobject_with(obj.clone(), &options).unwrap();
+        let timestamp: f64 = obj
+            .get_item("started")
+            .unwrap()
+            .call_method0("timestamp")
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(job.started, UNIX_EPOCH + Duration::from_secs_f64(timestamp));
+    });
+}
+
+#[test]
+fn deserialize_any_iso_strings() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("(lambda d: {'at': d.datetime(2024, 2, 29, 12, 0, tzinfo=d.timezone.utc), 'day': d.date(2024, 2, 29), 'time': d.time(1, 2, 3, 500), 'elapsed': d.timedelta(seconds=90, microseconds=500000), 'back': -d.timedelta(seconds=1)})(__import__('datetime'))"),
+                None,
+                None,
+            )
+            .unwrap();
+        let value: serde_json::Value = from_pyobject(obj).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "at": "2024-02-29T12:00:00+00:00",
+                "day": "2024-02-29",
+                "time": "01:02:03.000500",
+                "elapsed": "PT90.5S",
+                "back": "-PT1S",
+            })
+        );
+    });
+}
@@ -0,0 +1,75 @@
+#![cfg(all(feature = "async", not(target_os = "emscripten")))]
+
+use futures_core::Stream;
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::TypedStream;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::{self, Thread},
+};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll the next item, parking the thread until it is woken
+fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::new(&mut *stream).poll_next(&mut cx) {
+            Poll::Ready(item) => return item,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Item {
+    id: u32,
+}
+
+#[test]
+fn typed_stream() {
+    let mut stream = Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+import asyncio, threading
+
+loop = asyncio.new_event_loop()
+threading.Thread(target=loop.run_forever, daemon=True).start()
+
+async def items():
+    for i in range(2):
+        await asyncio.sleep(0.01)
+        yield {"id": i}
+    yield {"id": -1}
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let items = globals.get_item("items").unwrap().unwrap().call0().unwrap();
+        let event_loop = globals.get_item("loop").unwrap().unwrap();
+        TypedStream::<Item>::new(&items, &event_loop).unwrap()
+    });
+
+    assert_eq!(next(&mut stream).unwrap().unwrap(), Item { id: 0 });
+    assert_eq!(next(&mut stream).unwrap().unwrap(), Item { id: 1 });
+    assert_eq!(
+        next(&mut stream).unwrap().unwrap_err().to_string(),
+        "RuntimeError: invalid value: integer `-1`, expected u32"
+    );
+    assert!(next(&mut stream).is_none());
+    assert!(next(&mut stream).is_none());
+}
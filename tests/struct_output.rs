@@ -0,0 +1,292 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, to_pyobject_with, FieldCase, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions, StructOutput,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Line {
+    start: Point,
+    end: Point,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[cfg(feature = "dataclass_support")]
+#[test]
+fn struct_as_dataclass() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().struct_output(StructOutput::Dataclass);
+        let line = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 1, y: 2 },
+            label: Some("diagonal".to_string()),
+        };
+        let obj = to_pyobject_with(py, &line, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap(),
+            "Line(start=Point(x=0, y=0), end=Point(x=1, y=2), label='diagonal')"
+        );
+        assert!(py
+            .import("dataclasses")
+            .unwrap()
+            .call_method1("is_dataclass", (&obj,))
+            .unwrap()
+            .is_truthy()
+            .unwrap());
+
+        // Instances are deserialized from their attributes
+        let back: Line = from_pyobject(obj).unwrap();
+        assert_eq!(back, line);
+    });
+}
+
+#[cfg(feature = "dataclass_support")]
+#[test]
+fn classes_cached() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().struct_output(StructOutput::Dataclass);
+        let a = to_pyobject_with(py, &Point { x: 1, y: 2 }, &options).unwrap();
+        let b = to_pyobject_with(py, &Point { x: 3, y: 4 }, &options).unwrap();
+        assert!(a.get_type().is(&b.get_type()));
+
+        // Skipped fields give another class of the same name
+        let labeled = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 0, y: 0 },
+            label: Some("dot".to_string()),
+        };
+        let unlabeled = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 0, y: 0 },
+            label: None,
+        };
+        let labeled = to_pyobject_with(py, &labeled, &options).unwrap();
+        let unlabeled = to_pyobject_with(py, &unlabeled, &options).unwrap();
+        assert!(!labeled.get_type().is(&unlabeled.get_type()));
+        assert_eq!(unlabeled.get_type().name().unwrap(), "Line");
+        assert!(!unlabeled.hasattr("label").unwrap());
+
+        serde_pyobject::clear_caches();
+        let c = to_pyobject_with(py, &Point { x: 5, y: 6 }, &options).unwrap();
+        assert!(!a.get_type().is(&c.get_type()));
+    });
+}
+
+#[test]
+fn struct_as_namedtuple() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().struct_output(StructOutput::NamedTuple);
+        let line = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 1, y: 2 },
+            label: Some("diagonal".to_string()),
+        };
+        let obj = to_pyobject_with(py, &line, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap(),
+            "Line(start=Point(x=0, y=0), end=Point(x=1, y=2), label='diagonal')"
+        );
+        assert!(obj.is_instance_of::<pyo3::types::PyTuple>());
+        assert_eq!(
+            obj.get_item(1)
+                .unwrap()
+                .getattr("y")
+                .unwrap()
+                .extract::<i32>()
+                .unwrap(),
+            2
+        );
+
+        // Deserialized by the names of fields
+        let back: Line = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(back, line);
+
+        let other = to_pyobject_with(py, &line, &options).unwrap();
+        assert!(obj.get_type().is(&other.get_type()));
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle { center: Point, r: f64 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    user_id: u32,
+    display_name: String,
+}
+
+#[test]
+fn class_output_with_field_case() {
+    Python::with_gil(|py| {
+        let user = User {
+            user_id: 1,
+            display_name: "Alice".to_string(),
+        };
+        let de_options = PyObjectDeserializerOptions::new().field_case(FieldCase::Camel);
+        let outputs = [
+            #[cfg(feature = "dataclass_support")]
+            (
+                StructOutput::Dataclass,
+                "User(userId=1, displayName='Alice')",
+            ),
+            (
+                StructOutput::NamedTuple,
+                "User(userId=1, displayName='Alice')",
+            ),
+        ];
+        for (output, repr) in outputs {
+            let options = PyObjectSerializerOptions::new()
+                .struct_output(output)
+                .field_case(FieldCase::Camel);
+            let obj = to_pyobject_with(py, &user, &options).unwrap();
+            assert_eq!(obj.repr().unwrap(), repr);
+            let back: User = from_pyobject_with(obj, &de_options).unwrap();
+            assert_eq!(back, user);
+        }
+    });
+}
+
+#[test]
+fn struct_as_namespace() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().struct_output(StructOutput::Namespace);
+        let line = Line {
+            start: Point { x: 0, y: 0 },
+            end: Point { x: 1, y: 2 },
+            label: None,
+        };
+        let obj = to_pyobject_with(py, &line, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap(),
+            "namespace(start=namespace(x=0, y=0), end=namespace(x=1, y=2))"
+        );
+        let back: Line = from_pyobject(obj).unwrap();
+        assert_eq!(back, line);
+
+        let circle = Shape::Circle {
+            center: Point { x: 1, y: 1 },
+            r: 0.5,
+        };
+        let obj = to_pyobject_with(py, &circle, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap(),
+            "{'Circle': namespace(center=namespace(x=1, y=1), r=0.5)}"
+        );
+        let back: Shape = from_pyobject(obj).unwrap();
+        assert_eq!(back, circle);
+    });
+}
+
+#[test]
+fn struct_as_tuple() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .struct_output(StructOutput::Tuple)
+            .skip_none_fields(true);
+        let points = vec![Point { x: 0, y: 1 }, Point { x: 2, y: 3 }];
+        let obj = to_pyobject_with(py, &points, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "[(0, 1), (2, 3)]");
+        let back: Vec<Point> = from_pyobject(obj).unwrap();
+        assert_eq!(back, points);
+
+        let circle = Shape::Circle {
+            center: Point { x: 1, y: 1 },
+            r: 0.5,
+        };
+        let obj = to_pyobject_with(py, &circle, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "{'Circle': ((1, 1), 0.5)}");
+        let back: Shape = from_pyobject(obj).unwrap();
+        assert_eq!(back, circle);
+
+        // `None` keeps its position
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Pair {
+            a: Option<i32>,
+            b: Option<i32>,
+        }
+        let pair = Pair {
+            a: Some(1),
+            b: None,
+        };
+        let obj = to_pyobject_with(py, &pair, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "(1, None)");
+        let back: Pair = from_pyobject(obj).unwrap();
+        assert_eq!(back, pair);
+
+        // Fields whose names collide keep their positions
+        #[derive(Serialize)]
+        #[allow(non_snake_case)]
+        struct Colliding {
+            user_id: i32,
+            userId: i32,
+        }
+        let colliding = Colliding {
+            user_id: 1,
+            userId: 2,
+        };
+        let obj = to_pyobject_with(
+            py,
+            &colliding,
+            &options.clone().field_case(FieldCase::Camel),
+        )
+        .unwrap();
+        assert_eq!(obj.repr().unwrap(), "(1, 2)");
+
+        // Lists are read positionally as well
+        let options = options.json_compatible(true);
+        let obj = to_pyobject_with(py, &points, &options).unwrap();
+        assert_eq!(obj.repr().unwrap(), "[[0, 1], [2, 3]]");
+        let back: Vec<Point> = from_pyobject(obj).unwrap();
+        assert_eq!(back, points);
+    });
+}
+
+#[test]
+fn disabled_by_default() {
+    Python::with_gil(|py| {
+        let obj =
+            to_pyobject_with(py, &Point { x: 1, y: 2 }, &PyObjectSerializerOptions::new()).unwrap();
+        assert_eq!(obj.repr().unwrap(), "{'x': 1, 'y': 2}");
+    });
+}
+
+#[test]
+fn class_output_with_keyword_fields() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Element {
+        class_: String,
+        id_: u32,
+    }
+
+    Python::with_gil(|py| {
+        let element = Element {
+            class_: "button".to_string(),
+            id_: 1,
+        };
+        let outputs = [
+            #[cfg(feature = "dataclass_support")]
+            StructOutput::Dataclass,
+            StructOutput::NamedTuple,
+        ];
+        for output in outputs {
+            let options = PyObjectSerializerOptions::new()
+                .struct_output(output)
+                .keyword_fields(true);
+            let obj = to_pyobject_with(py, &element, &options).unwrap();
+            // The trailing underscore is kept since `class` cannot be a field of a class
+            assert_eq!(obj.repr().unwrap(), "Element(class_='button', id_=1)");
+            let back: Element = from_pyobject(obj).unwrap();
+            assert_eq!(back, element);
+        }
+    });
+}
@@ -0,0 +1,343 @@
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::{from_pyobject, from_pyobject_with, pylist, PyObjectDeserializerOptions};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct User {
+    name: String,
+    age: u32,
+    #[serde(default)]
+    tags: Vec<String>,
+    address: Address,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Address {
+    city: String,
+}
+
+#[test]
+fn struct_from_attributes() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+from dataclasses import dataclass
+
+@dataclass
+class Address:
+    city: str
+
+class User:
+    def __init__(self):
+        self.name = "alice"
+        self._secret = object()
+        self.address = Address("Tokyo")
+
+    @property
+    def age(self):
+        return 20
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let user = globals.get_item("User").unwrap().unwrap().call0().unwrap();
+        let user: User = from_pyobject(user).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "alice".to_string(),
+                age: 20,
+                tags: Vec::new(),
+                address: Address {
+                    city: "Tokyo".to_string()
+                },
+            }
+        );
+    });
+}
+
+#[test]
+fn missing_attribute() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("__import__('types').SimpleNamespace(city=1, name='bob')"),
+                None,
+                None,
+            )
+            .unwrap();
+        let err = from_pyobject::<User, _>(obj.clone()).unwrap_err();
+        assert_eq!(err.to_string(), "RuntimeError: missing field `age`");
+
+        let err = from_pyobject::<Address, _>(obj).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: integer `1`, expected a string"
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Profile {
+    name: String,
+    nickname: Option<String>,
+    email: Option<String>,
+}
+
+#[test]
+fn missing_attribute_as_none() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+from types import SimpleNamespace
+
+class Slotted:
+    __slots__ = ("name", "nickname", "email")
+
+    def __init__(self):
+        self.name = "carol"
+        self.email = None
+
+namespace = SimpleNamespace(name="dave", nickname="d")
+slotted = Slotted()
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+
+        let namespace = globals.get_item("namespace").unwrap().unwrap();
+        let profile: Profile = from_pyobject(namespace).unwrap();
+        assert_eq!(
+            profile,
+            Profile {
+                name: "dave".to_string(),
+                nickname: Some("d".to_string()),
+                email: None,
+            }
+        );
+
+        // Unset slots raise `AttributeError` as missing attributes do
+        let slotted = globals.get_item("slotted").unwrap().unwrap();
+        let profile: Profile = from_pyobject(slotted).unwrap();
+        assert_eq!(
+            profile,
+            Profile {
+                name: "carol".to_string(),
+                nickname: None,
+                email: None,
+            }
+        );
+    });
+}
+
+#[test]
+fn attribute_error_propagates() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+class Broken:
+    @property
+    def city(self):
+        raise ValueError("broken")
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let obj = globals
+            .get_item("Broken")
+            .unwrap()
+            .unwrap()
+            .call0()
+            .unwrap();
+        let err = from_pyobject::<Address, _>(obj).unwrap_err();
+        assert_eq!(err.to_string(), "ValueError: broken");
+    });
+}
+
+#[test]
+fn struct_from_sequence() {
+    Python::with_gil(|py| {
+        let rows = pylist![py; ("Tokyo",), pylist![py; "Osaka"].unwrap()].unwrap();
+        let rows: Vec<Address> = from_pyobject(rows).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Address {
+                    city: "Tokyo".to_string()
+                },
+                Address {
+                    city: "Osaka".to_string()
+                }
+            ]
+        );
+
+        // Surplus elements are ignored by default
+        let row = pylist![py; "Tokyo", "Japan"].unwrap();
+        let address: Address = from_pyobject(row.clone()).unwrap();
+        assert_eq!(address.city, "Tokyo");
+
+        let options = PyObjectDeserializerOptions::new().exact_struct_length(true);
+        let err = from_pyobject_with::<Address, _>(row, &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid length 2, expected struct Address with 1 elements"
+        );
+    });
+}
+
+#[test]
+fn struct_from_shorter_sequence() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        id: u32,
+        #[serde(default)]
+        note: Option<String>,
+    }
+
+    Python::with_gil(|py| {
+        let row: Row = from_pyobject(pylist![py; 1].unwrap()).unwrap();
+        assert_eq!(row, Row { id: 1, note: None });
+        let row: Row = from_pyobject(pylist![py; 1, "x"].unwrap()).unwrap();
+        assert_eq!(
+            row,
+            Row {
+                id: 1,
+                note: Some("x".to_string())
+            }
+        );
+
+        let options = PyObjectDeserializerOptions::new().exact_struct_length(true);
+        let err = from_pyobject_with::<Row, _>(pylist![py; 1].unwrap(), &options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid length 1, expected struct Row with 2 elements"
+        );
+    });
+}
+
+#[test]
+fn range() {
+    Python::with_gil(|py| {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Range {
+            start: i64,
+            stop: i64,
+            step: i64,
+        }
+
+        let obj = py.eval(c_str!("range(1, 10, 3)"), None, None).unwrap();
+        let values: Vec<i64> = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(values, vec![1, 4, 7]);
+        // Not positionally from the three elements
+        let range: Range = from_pyobject(obj).unwrap();
+        assert_eq!(
+            range,
+            Range {
+                start: 1,
+                stop: 10,
+                step: 3
+            }
+        );
+    });
+}
+
+#[test]
+fn named_tuple() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+from collections import namedtuple
+from typing import NamedTuple
+
+Address = namedtuple("Address", ["city"])
+
+class User(NamedTuple):
+    age: int
+    name: str
+    address: Address
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let obj = py
+            .eval(
+                c_str!("User(20, 'alice', Address('Tokyo'))"),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+        // By the names of fields, although `age` comes first in the tuple
+        let user: User = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "alice".to_string(),
+                age: 20,
+                tags: vec![],
+                address: Address {
+                    city: "Tokyo".to_string()
+                }
+            }
+        );
+        // Positionally for tuples
+        let (age, name, (city,)): (u32, String, (String,)) = from_pyobject(obj.clone()).unwrap();
+        assert_eq!((age, name.as_str(), city.as_str()), (20, "alice", "Tokyo"));
+
+        let map: std::collections::BTreeMap<String, serde_json::Value> =
+            from_pyobject(obj).unwrap();
+        assert_eq!(map["age"], 20);
+        assert_eq!(map["address"], serde_json::json!(["Tokyo"]));
+    });
+}
+
+#[test]
+fn slots() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+class Address:
+    __slots__ = ("city",)
+    def __init__(self, city):
+        self.city = city
+
+class User:
+    __slots__ = ("name", "age", "address")
+    def __init__(self):
+        self.name = "alice"
+        self.age = 20
+        self.address = Address("Tokyo")
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let obj = py.eval(c_str!("User()"), Some(&globals), None).unwrap();
+        let user: User = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(user.address.city, "Tokyo");
+
+        let value: serde_json::Value = from_pyobject(obj).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"name": "alice", "age": 20, "address": {"city": "Tokyo"}})
+        );
+    });
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "time")]
+
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject};
+use time::{macros::datetime, Date, Duration, OffsetDateTime, PrimitiveDateTime, Time};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    #[serde(with = "serde_pyobject::datetime")]
+    aware: OffsetDateTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    naive: PrimitiveDateTime,
+    #[serde(with = "serde_pyobject::datetime")]
+    date: Date,
+    #[serde(with = "serde_pyobject::datetime")]
+    time: Time,
+    #[serde(with = "serde_pyobject::datetime")]
+    elapsed: Duration,
+}
+
+fn record() -> Record {
+    let naive = datetime!(2024-02-29 12:34:56.000789);
+    Record {
+        aware: datetime!(2024-02-29 12:34:56.000789 -05:30),
+        naive,
+        date: naive.date(),
+        time: naive.time(),
+        elapsed: Duration::new(-1, -500_000_000),
+    }
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let value = record();
+        let obj = to_pyobject(py, &value).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'aware': datetime.datetime(2024, 2, 29, 12, 34, 56, 789, tzinfo=datetime.timezone(datetime.timedelta(days=-1, seconds=66600))), \
+             'naive': datetime.datetime(2024, 2, 29, 12, 34, 56, 789), \
+             'date': datetime.date(2024, 2, 29), \
+             'time': datetime.time(12, 34, 56, 789), \
+             'elapsed': datetime.timedelta(days=-1, seconds=86398, microseconds=500000)}"
+        );
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, value);
+    });
+}
+
+#[test]
+fn iso_strings() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'aware': '2024-02-29T12:34:56.000789-05:30', 'naive': '2024-02-29T12:34:56.000789', 'date': '2024-02-29', 'time': '12:34:56.000789', 'elapsed': (-2, 500000)}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(back, record());
+    });
+}
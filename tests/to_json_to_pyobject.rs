@@ -1,8 +1,10 @@
 use maplit::*;
 use pyo3::prelude::*;
 use serde::Serialize;
+use serde_pyobject::{to_pyobject_with, PyObjectSerializerOptions};
+use std::collections::BTreeMap;
 
-fn to_json_to_pyobject<T: Serialize>(py: Python<'_>, obj: T) -> PyResult<Bound<PyAny>> {
+fn to_json_to_pyobject<T: Serialize>(py: Python<'_>, obj: T) -> PyResult<Bound<'_, PyAny>> {
     let json = serde_json::to_string(&obj).unwrap();
     let obj = py.import("json")?.getattr("loads")?.call1((json,))?;
     Ok(obj)
@@ -16,6 +18,19 @@ fn test(obj: impl Serialize) {
     })
 }
 
+/// Compare with [`PyObjectSerializerOptions::json_compatible`], which closes the divergences skipped below
+fn test_json_compatible(obj: impl Serialize) {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let direct = to_pyobject_with(py, &obj, &options).unwrap();
+        let by_json = to_json_to_pyobject(py, obj).unwrap();
+        assert_eq!(
+            dbg!(direct).repr().unwrap().to_string(),
+            dbg!(by_json).repr().unwrap().to_string()
+        );
+    })
+}
+
 #[test]
 fn primitive() {
     test(1_u8);
@@ -30,6 +45,22 @@ fn string() {
     test("test".to_string());
 }
 
+/// Serialized by `Display` through `collect_str`
+struct Displayed(f64);
+
+impl Serialize for Displayed {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:.2}", self.0))
+    }
+}
+
+#[test]
+fn collect_str() {
+    test(Displayed(1.0 / 3.0));
+    test(vec![Displayed(0.5), Displayed(2.0)]);
+    test(std::net::Ipv4Addr::new(127, 0, 0, 1));
+}
+
 #[test]
 fn option() {
     test(Some(10_u8));
@@ -152,3 +183,79 @@ fn struct_variant() {
         b: 30,
     });
 }
+
+#[derive(Serialize)]
+struct UnitStruct;
+
+#[derive(Serialize)]
+struct TupleStruct(u8, u8, u8);
+
+#[derive(Serialize)]
+enum TupleVariant {
+    T(u8, u8),
+}
+
+/// Serialized by `serialize_bytes`, as `serde_bytes::Bytes` is
+struct Bytes(&'static [u8]);
+
+impl Serialize for Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[derive(Serialize)]
+struct Samples(#[serde(with = "serde_pyobject::array")] Vec<f64>);
+
+#[test]
+fn json_compatible() {
+    test_json_compatible(());
+    test_json_compatible(UnitStruct);
+    test_json_compatible((1, "test"));
+    test_json_compatible(TupleStruct(1, 2, 3));
+    test_json_compatible(TupleVariant::T(1, 2));
+    test_json_compatible(Bytes(b"ab"));
+    test_json_compatible(vec![f64::NAN, f64::INFINITY, 1.5]);
+    test_json_compatible(0.1_f32);
+    test_json_compatible(Samples(vec![1.0, 2.0]));
+    test_json_compatible(BTreeMap::from([(1, "a"), (-2, "b")]));
+    test_json_compatible(BTreeMap::from([(true, "t")]));
+    test_json_compatible(BTreeMap::from([('c', ())]));
+    // Compatible options still give the same output
+    test_json_compatible(A {
+        a: 1,
+        b: "b".to_string(),
+    });
+}
+
+#[test]
+fn json_compatible_keys() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(
+            py,
+            &vec![(1.5, 0), (1e20, 1)].into_iter().collect::<Floats>(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(obj.repr().unwrap(), "{'1.5': 0, '1e20': 1}");
+
+        let err = to_pyobject_with(py, &BTreeMap::from([((1, 2), 0)]), &options).unwrap_err();
+        assert_eq!(err.to_string(), "RuntimeError: key must be a string");
+    });
+}
+
+/// Map with `f64` keys, which are not `Ord`
+struct Floats(Vec<(f64, i32)>);
+
+impl FromIterator<(f64, i32)> for Floats {
+    fn from_iter<I: IntoIterator<Item = (f64, i32)>>(iter: I) -> Self {
+        Floats(iter.into_iter().collect())
+    }
+}
+
+impl Serialize for Floats {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
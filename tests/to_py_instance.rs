@@ -0,0 +1,106 @@
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+use serde::Serialize;
+use serde_pyobject::to_py_instance;
+
+#[derive(Serialize)]
+struct Account {
+    owner: String,
+    balance: i64,
+}
+
+fn classes(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+class WithInit:
+    def __init__(self, owner, balance):
+        if balance < 0:
+            raise ValueError("negative balance")
+        self.owner = owner.upper()
+        self.balance = balance
+
+class WithoutInit:
+    kind = "plain"
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+fn class<'py>(globals: &Bound<'py, PyDict>, name: &str) -> Bound<'py, PyType> {
+    globals
+        .get_item(name)
+        .unwrap()
+        .unwrap()
+        .downcast_into()
+        .unwrap()
+}
+
+#[test]
+fn keyword_arguments() {
+    Python::with_gil(|py| {
+        let globals = classes(py);
+        let cls = class(&globals, "WithInit");
+        let account = Account {
+            owner: "alice".to_string(),
+            balance: 10,
+        };
+        let obj = to_py_instance(py, &account, &cls).unwrap();
+        assert!(obj.is_instance(&cls).unwrap());
+        assert_eq!(
+            obj.getattr("owner").unwrap().extract::<String>().unwrap(),
+            "ALICE"
+        );
+
+        // Errors in `__init__` propagate
+        let account = Account {
+            owner: "bob".to_string(),
+            balance: -1,
+        };
+        let err = to_py_instance(py, &account, &cls).unwrap_err();
+        assert_eq!(err.to_string(), "ValueError: negative balance");
+    });
+}
+
+#[test]
+fn set_attributes() {
+    Python::with_gil(|py| {
+        let globals = classes(py);
+        let cls = class(&globals, "WithoutInit");
+        let account = Account {
+            owner: "carol".to_string(),
+            balance: 5,
+        };
+        let obj = to_py_instance(py, &account, &cls).unwrap();
+        assert_eq!(
+            obj.getattr("owner").unwrap().extract::<String>().unwrap(),
+            "carol"
+        );
+        assert_eq!(obj.getattr("balance").unwrap().extract::<i64>().unwrap(), 5);
+        assert_eq!(
+            obj.getattr("kind").unwrap().extract::<String>().unwrap(),
+            "plain"
+        );
+    });
+}
+
+#[test]
+fn not_struct() {
+    Python::with_gil(|py| {
+        let globals = classes(py);
+        let cls = class(&globals, "WithoutInit");
+        let err = to_py_instance(py, &vec![1, 2], &cls).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'list' object cannot be the fields of 'WithoutInit'"
+        );
+    });
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "pydantic_support")]
+
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+use serde::Serialize;
+use serde_pyobject::to_pydantic;
+
+#[derive(Serialize)]
+struct Order {
+    item: String,
+    quantity: u32,
+}
+
+/// Classes standing for pydantic models, which are detected by duck typing
+fn models(py: Python<'_>) -> Bound<'_, PyDict> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!(
+            r#"
+class ModelV2:
+    def __init__(self, item, quantity):
+        self.item = item
+        self.quantity = quantity
+
+    @classmethod
+    def model_validate(cls, obj):
+        if obj["quantity"] == 0:
+            raise ValueError("quantity must be positive")
+        return cls(obj["item"], str(obj["quantity"]))
+
+class ModelV1:
+    def __init__(self, item, quantity):
+        self.item = item
+        self.quantity = quantity
+
+    @classmethod
+    def parse_obj(cls, obj):
+        return cls(**obj)
+
+class NotModel:
+    pass
+"#
+        ),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    globals
+}
+
+fn class<'py>(globals: &Bound<'py, PyDict>, name: &str) -> Bound<'py, PyType> {
+    globals
+        .get_item(name)
+        .unwrap()
+        .unwrap()
+        .downcast_into()
+        .unwrap()
+}
+
+#[test]
+fn model_validate() {
+    Python::with_gil(|py| {
+        let models = models(py);
+        let cls = class(&models, "ModelV2");
+        let order = Order {
+            item: "apple".to_string(),
+            quantity: 3,
+        };
+        let model = to_pydantic(py, &order, &cls).unwrap();
+        assert!(model.is_instance(&cls).unwrap());
+        // Coerced by the model
+        assert_eq!(
+            model
+                .getattr("quantity")
+                .unwrap()
+                .extract::<String>()
+                .unwrap(),
+            "3"
+        );
+
+        let order = Order {
+            item: "apple".to_string(),
+            quantity: 0,
+        };
+        let err = to_pydantic(py, &order, &cls).unwrap_err();
+        assert_eq!(err.to_string(), "ValueError: quantity must be positive");
+    });
+}
+
+#[test]
+fn parse_obj() {
+    Python::with_gil(|py| {
+        let models = models(py);
+        let cls = class(&models, "ModelV1");
+        let order = Order {
+            item: "pear".to_string(),
+            quantity: 1,
+        };
+        let model = to_pydantic(py, &order, &cls).unwrap();
+        assert_eq!(
+            model.getattr("item").unwrap().extract::<String>().unwrap(),
+            "pear"
+        );
+    });
+}
+
+#[test]
+fn not_model() {
+    Python::with_gil(|py| {
+        let models = models(py);
+        let cls = class(&models, "NotModel");
+        let order = Order {
+            item: "pear".to_string(),
+            quantity: 1,
+        };
+        let err = to_pydantic(py, &order, &cls).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'NotModel' is not a pydantic model, which has model_validate or parse_obj"
+        );
+    });
+}
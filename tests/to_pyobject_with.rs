@@ -0,0 +1,33 @@
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde_pyobject::{to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct A {
+    a: Option<i32>,
+    b: Vec<f64>,
+    c: BTreeMap<&'static str, (u8, bool)>,
+}
+
+#[test]
+fn default_options() {
+    Python::with_gil(|py| {
+        let value = A {
+            a: None,
+            b: vec![1.0, f64::NAN],
+            c: BTreeMap::from([("x", (1, true))]),
+        };
+        // `to_pyobject` is `to_pyobject_with` with the default options
+        let plain = to_pyobject(py, &value).unwrap();
+        let with = to_pyobject_with(py, &value, &PyObjectSerializerOptions::new()).unwrap();
+        assert_eq!(
+            plain.repr().unwrap().to_string(),
+            with.repr().unwrap().to_string()
+        );
+        assert_eq!(
+            plain.repr().unwrap(),
+            "{'a': None, 'b': [1.0, nan], 'c': {'x': (1, True)}}"
+        );
+    });
+}
@@ -0,0 +1,154 @@
+#![cfg(any(feature = "json", feature = "rmp", feature = "cbor", feature = "bson"))]
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde_pyobject::{pydict, pylist};
+
+#[cfg(feature = "json")]
+#[test]
+fn json_reader() {
+    use serde_pyobject::json_reader_to_pyobject;
+    use std::io;
+
+    Python::with_gil(|py| {
+        let json = r#"[{"id": 18446744073709551615, "tags": ["a", "b"]}, -1, "é", {}]"#;
+        let obj = json_reader_to_pyobject(py, io::BufReader::new(json.as_bytes())).unwrap();
+        let expected = pylist![py;
+            pydict! { py, "id" => u64::MAX, "tags" => pylist![py; "a", "b"].unwrap() }.unwrap(),
+            -1,
+            "é",
+            pydict! { py, }.unwrap()
+        ]
+        .unwrap();
+        assert!(obj.eq(expected).unwrap());
+    });
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_reader_errors() {
+    use pyo3::exceptions::PyOSError;
+    use serde_pyobject::json_reader_to_pyobject;
+    use std::io::{self, Read};
+
+    Python::with_gil(|py| {
+        let err = json_reader_to_pyobject(py, "[1,".as_bytes()).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+
+        let err = json_reader_to_pyobject(py, "1 2".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: trailing characters at line 1 column 3"
+        );
+
+        struct Broken;
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disconnected"))
+            }
+        }
+        let err = json_reader_to_pyobject(py, Broken).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyOSError>(py));
+    });
+}
+
+#[cfg(feature = "rmp")]
+#[test]
+fn msgpack() {
+    use serde_pyobject::{msgpack_to_pyobject, pyobject_to_msgpack};
+
+    Python::with_gil(|py| {
+        let obj = pydict! { py,
+            "id" => u64::MAX,
+            "values" => pylist![py; -1, 0.5, "x", py.None(), true].unwrap(),
+            "nested" => pydict! { py, "a" => pylist![py; ].unwrap() }.unwrap()
+        }
+        .unwrap();
+        let bytes = pyobject_to_msgpack(obj.as_any()).unwrap();
+        let decoded = msgpack_to_pyobject(py, &bytes).unwrap();
+        assert!(decoded.eq(&obj).unwrap());
+
+        // bin 8 with 2 bytes
+        let decoded = msgpack_to_pyobject(py, &[0xc4, 0x02, 0x01, 0x02]).unwrap();
+        assert_eq!(decoded.extract::<Vec<u8>>().unwrap(), [1, 2]);
+        assert_eq!(
+            pyobject_to_msgpack(&decoded).unwrap(),
+            [0xc4, 0x02, 0x01, 0x02]
+        );
+    });
+}
+
+#[cfg(feature = "rmp")]
+#[test]
+fn msgpack_errors() {
+    use serde_pyobject::msgpack_to_pyobject;
+
+    Python::with_gil(|py| {
+        // array of 2 elements with only 1
+        let err = msgpack_to_pyobject(py, &[0x92, 0x01]).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+
+        let err = msgpack_to_pyobject(py, &[0x01, 0x02]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: trailing bytes after MessagePack value at position 1"
+        );
+    });
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor() {
+    use serde_pyobject::{cbor_to_pyobject, pyobject_to_cbor};
+
+    Python::with_gil(|py| {
+        let obj = pydict! { py,
+            "id" => u64::MAX,
+            "values" => pylist![py; -1, 0.5, "x", py.None(), false].unwrap()
+        }
+        .unwrap();
+        let bytes = pyobject_to_cbor(obj.as_any()).unwrap();
+        let decoded = cbor_to_pyobject(py, &bytes).unwrap();
+        assert!(decoded.eq(&obj).unwrap());
+
+        // bytes of length 1
+        let decoded = cbor_to_pyobject(py, &[0x41, 0xff]).unwrap();
+        assert_eq!(decoded.extract::<Vec<u8>>().unwrap(), [0xff]);
+
+        // tag 1 (epoch time) around 0
+        let err = cbor_to_pyobject(py, &[0xc1, 0x00]).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+
+        let err = cbor_to_pyobject(py, &[0x82, 0x01]).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+        let err = cbor_to_pyobject(py, &[0x01, 0x02]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: trailing bytes after CBOR value at position 1"
+        );
+    });
+}
+
+#[cfg(feature = "bson")]
+#[test]
+fn bson() {
+    use serde_pyobject::{bson_to_pyobject, pyobject_to_bson};
+
+    Python::with_gil(|py| {
+        let obj = pydict! { py,
+            "name" => "x",
+            "values" => pylist![py; -1, 0.5, py.None(), true].unwrap(),
+            "nested" => pydict! { py, "a" => 1 }.unwrap()
+        }
+        .unwrap();
+        let bytes = pyobject_to_bson(obj.as_any()).unwrap();
+        let decoded = bson_to_pyobject(py, &bytes).unwrap();
+        assert!(decoded.eq(&obj).unwrap());
+
+        let list = pylist![py; 1].unwrap();
+        let err = pyobject_to_bson(list.as_any()).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+
+        let err = bson_to_pyobject(py, &[0x05, 0x00]).unwrap_err();
+        assert!(PyErr::from(err).is_instance_of::<PyValueError>(py));
+    });
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "erased-serde")]
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyString};
+use serde::Serialize;
+use serde_pyobject::{to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+
+#[derive(Serialize)]
+struct Email(String);
+
+#[derive(Serialize)]
+struct User {
+    name: String,
+    email: Email,
+    backup: Option<Email>,
+}
+
+#[test]
+fn adapters() {
+    Python::with_gil(|py| {
+        let user = User {
+            name: "alice".to_string(),
+            email: Email("Alice@Example.com".to_string()),
+            backup: Some(Email("A@B.C".to_string())),
+        };
+        let options = PyObjectSerializerOptions::new()
+            .type_adapter("Email", |py, value| {
+                to_pyobject(py, value)?.call_method0("upper")
+            })
+            // Registering again replaces the function
+            .type_adapter("Email", |py, value| {
+                to_pyobject(py, value)?.call_method0("lower")
+            });
+        let obj = to_pyobject_with(py, &user, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'name': 'alice', 'email': 'alice@example.com', 'backup': 'a@b.c'}"
+        );
+
+        // Other newtype structs are not affected
+        let options = PyObjectSerializerOptions::new()
+            .type_adapter("Phone", |py, _| Ok(PyString::new(py, "?").into_any()));
+        let obj = to_pyobject_with(py, &user, &options).unwrap();
+        assert_eq!(
+            obj.get_item("email").unwrap().to_string(),
+            "Alice@Example.com"
+        );
+    });
+}
+
+#[test]
+fn adapter_error() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .type_adapter("Email", |_, _| Err(PyValueError::new_err("invalid email")));
+        let err = to_pyobject_with(py, &Email("x".to_string()), &options).unwrap_err();
+        assert_eq!(err.to_string(), "ValueError: invalid email");
+    });
+}
+
+#[test]
+fn adapter_serializes_value() {
+    Python::with_gil(|py| {
+        // The value is given unserialized, e.g. to serialize it into another format
+        let options = PyObjectSerializerOptions::new().type_adapter("Email", |py, value| {
+            let json = serde_json::to_string(value)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?;
+            Ok(PyString::new(py, &json).into_any())
+        });
+        let obj = to_pyobject_with(py, &Email("a@b.c".to_string()), &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), r#"'"a@b.c"'"#);
+    });
+}
@@ -0,0 +1,180 @@
+use pyo3::{
+    ffi::c_str,
+    prelude::*,
+    types::{PyDict, PyModule},
+};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{eval_typed, from_py_attr, TypedCallable, TypedIter};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    name: String,
+    workers: u32,
+}
+
+fn define_module(py: Python<'_>) {
+    let module = PyModule::from_code(
+        py,
+        c_str!(
+            r#"
+CONFIG = {"name": "app", "workers": 4}
+BROKEN = {"name": "app", "workers": -1}
+"#
+        ),
+        c_str!("settings.py"),
+        c_str!("settings"),
+    )
+    .unwrap();
+    py.import("sys")
+        .unwrap()
+        .getattr("modules")
+        .unwrap()
+        .set_item("settings", module)
+        .unwrap();
+}
+
+#[test]
+fn from_py_attr_() {
+    Python::with_gil(|py| {
+        define_module(py);
+        let config: Config = from_py_attr(py, "settings", "CONFIG").unwrap();
+        assert_eq!(
+            config,
+            Config {
+                name: "app".to_string(),
+                workers: 4
+            }
+        );
+
+        let err = from_py_attr::<Config>(py, "settings", "BROKEN").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: settings.BROKEN: $['workers']: invalid value: integer `-1`, expected u32"
+        );
+
+        let err = from_py_attr::<Config>(py, "settings", "MISSING").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "AttributeError: module 'settings' has no attribute 'MISSING'"
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Circle { r: f64 },
+}
+
+#[test]
+fn eval_typed_() {
+    Python::with_gil(|py| {
+        define_module(py);
+        let config: Config = eval_typed(py, "__import__('settings').CONFIG", None, None).unwrap();
+        assert_eq!(config.workers, 4);
+
+        let err = eval_typed::<Vec<Vec<Shape>>>(
+            py,
+            "[[], [{'Circle': {'r': 1.0}}, {'Circle': {'r': 'x'}}]]",
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: [[], [{'Circle': {'r': 1.0}}, {'Circle': {'r': 'x'}}]]: \
+             $[1][1].Circle['r']: invalid type: string \"x\", expected f64"
+        );
+
+        let err = eval_typed::<Config>(py, "1 / 0", None, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ZeroDivisionError: 1 / 0: division by zero"
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn typed_callable() {
+    let (scale, origin, not_callable) = Python::with_gil(|py| {
+        let scale = py
+            .eval(
+                c_str!("lambda p, k: {'x': p['x'] * k, 'y': p['y'] * k}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let origin = py.eval(c_str!("lambda: (0, 0)"), None, None).unwrap();
+        let not_callable = py.eval(c_str!("1"), None, None).unwrap();
+        (
+            TypedCallable::<(Point, i32), Point>::new(scale).unwrap(),
+            origin.extract::<TypedCallable<(), Point>>().unwrap(),
+            TypedCallable::<(), ()>::new(not_callable).unwrap_err(),
+        )
+    });
+
+    // Called from another thread
+    let point = std::thread::spawn(move || scale.call(&(Point { x: 1, y: 2 }, 3)))
+        .join()
+        .unwrap()
+        .unwrap();
+    assert_eq!(point, Point { x: 3, y: 6 });
+    assert_eq!(origin.call(&()).unwrap(), Point { x: 0, y: 0 });
+    assert_eq!(
+        not_callable.to_string(),
+        "TypeError: 'int' object is not callable"
+    );
+}
+
+#[test]
+fn typed_iter() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+pulled = 0
+
+def points():
+    global pulled
+    for i in range(3):
+        pulled += 1
+        yield {"x": i, "y": -i} if i < 2 else "broken"
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let generator = globals
+            .get_item("points")
+            .unwrap()
+            .unwrap()
+            .call0()
+            .unwrap();
+        let mut points = TypedIter::<Point>::new(&generator).unwrap();
+        let pulled = || -> i32 {
+            globals
+                .get_item("pulled")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap()
+        };
+
+        assert_eq!(pulled(), 0);
+        assert_eq!(points.next().unwrap().unwrap(), Point { x: 0, y: 0 });
+        assert_eq!(pulled(), 1);
+        assert_eq!(points.next().unwrap().unwrap(), Point { x: 1, y: -1 });
+        assert!(points.next().unwrap().is_err());
+        assert!(points.next().is_none());
+
+        let err = TypedIter::<Point>::new(1_i32.into_pyobject(py).unwrap().as_any()).unwrap_err();
+        assert_eq!(err.to_string(), "TypeError: 'int' object is not iterable");
+    });
+}
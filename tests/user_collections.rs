@@ -0,0 +1,167 @@
+use maplit::hashmap;
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::from_pyobject;
+use std::collections::HashMap;
+
+fn eval<'py>(py: Python<'py>, expr: &std::ffi::CStr) -> Bound<'py, PyAny> {
+    let globals = PyDict::new(py);
+    py.run(
+        c_str!("from collections import UserDict, UserList, UserString"),
+        Some(&globals),
+        None,
+    )
+    .unwrap();
+    py.eval(expr, Some(&globals), None).unwrap()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct A {
+    a: i32,
+    b: String,
+}
+
+#[test]
+fn user_dict() {
+    Python::with_gil(|py| {
+        let map: HashMap<String, i32> =
+            from_pyobject(eval(py, c_str!("UserDict({'x': 1, 'y': 2})"))).unwrap();
+        assert_eq!(map, hashmap! { "x".to_string() => 1, "y".to_string() => 2 });
+
+        let a: A = from_pyobject(eval(py, c_str!("UserDict(a=1, b='x')"))).unwrap();
+        assert_eq!(
+            a,
+            A {
+                a: 1,
+                b: "x".to_string()
+            }
+        );
+    });
+}
+
+#[test]
+fn user_list() {
+    Python::with_gil(|py| {
+        let list: Vec<i32> = from_pyobject(eval(py, c_str!("UserList([1, 2, 3])"))).unwrap();
+        assert_eq!(list, vec![1, 2, 3]);
+
+        let a: A = from_pyobject(eval(py, c_str!("UserList([1, 'x'])"))).unwrap();
+        assert_eq!(
+            a,
+            A {
+                a: 1,
+                b: "x".to_string()
+            }
+        );
+
+        let list: Vec<i32> = from_pyobject(eval(py, c_str!("range(3)"))).unwrap();
+        assert_eq!(list, vec![0, 1, 2]);
+    });
+}
+
+#[test]
+fn user_string() {
+    Python::with_gil(|py| {
+        let s: String = from_pyobject(eval(py, c_str!("UserString('abc')"))).unwrap();
+        assert_eq!(s, "abc");
+
+        let list: Vec<String> =
+            from_pyobject(eval(py, c_str!("UserList([UserString('a'), 'b'])"))).unwrap();
+        assert_eq!(list, vec!["a", "b"]);
+    });
+}
+
+#[test]
+fn keys_and_getitem() {
+    Python::with_gil(|py| {
+        // Neither `dict` nor registered as `collections.abc.Mapping`
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+class Row:
+    def __init__(self, **fields):
+        self._fields = fields
+    def keys(self):
+        return self._fields.keys()
+    def __getitem__(self, key):
+        return self._fields[key]
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let row = py
+            .eval(c_str!("Row(a=1, b='x')"), Some(&globals), None)
+            .unwrap();
+        let map: HashMap<String, serde_json::Value> = from_pyobject(row.clone()).unwrap();
+        assert_eq!(map["a"], 1);
+        let a: A = from_pyobject(row).unwrap();
+        assert_eq!(
+            a,
+            A {
+                a: 1,
+                b: "x".to_string()
+            }
+        );
+
+        let environ: HashMap<String, String> = from_pyobject(
+            py.eval(c_str!("__import__('os').environ"), None, None)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(environ.get("PATH"), std::env::var("PATH").ok().as_ref());
+    });
+}
+
+#[test]
+fn len_and_getitem() {
+    Python::with_gil(|py| {
+        let deque: Vec<i32> = from_pyobject(
+            py.eval(
+                c_str!("__import__('collections').deque([1, 2, 3])"),
+                None,
+                None,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(deque, vec![1, 2, 3]);
+
+        // Neither `list` nor registered as `collections.abc.Sequence`
+        let globals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+class Pair:
+    def __init__(self, a, b):
+        self._items = [a, b]
+    def __len__(self):
+        return len(self._items)
+    def __getitem__(self, index):
+        return self._items[index]
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let pair = py
+            .eval(c_str!("Pair(1, 'x')"), Some(&globals), None)
+            .unwrap();
+        let tuple: (i32, String) = from_pyobject(pair.clone()).unwrap();
+        assert_eq!(tuple, (1, "x".to_string()));
+        let a: A = from_pyobject(pair).unwrap();
+        assert_eq!(
+            a,
+            A {
+                a: 1,
+                b: "x".to_string()
+            }
+        );
+        let list: Vec<serde_json::Value> =
+            from_pyobject(py.eval(c_str!("Pair(1, 2)"), Some(&globals), None).unwrap()).unwrap();
+        assert_eq!(list, vec![1, 2]);
+    });
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "uuid")]
+
+use pyo3::{ffi::c_str, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{from_pyobject, to_pyobject, to_pyobject_with, PyObjectSerializerOptions};
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    #[serde(with = "serde_pyobject::uuid")]
+    id: Uuid,
+    #[serde(with = "serde_pyobject::uuid::option")]
+    parent: Option<Uuid>,
+}
+
+const ID: Uuid = Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        for parent in [None, Some(Uuid::nil())] {
+            let value = Record { id: ID, parent };
+            let obj = to_pyobject(py, &value).unwrap();
+            let uuid = py.import("uuid").unwrap().getattr("UUID").unwrap();
+            assert!(obj.get_item("id").unwrap().is_exact_instance(&uuid));
+            let back: Record = from_pyobject(obj).unwrap();
+            assert_eq!(back, value);
+        }
+    });
+}
+
+#[test]
+fn strings() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("{'id': '67e55044-10b1-426f-9247-bb680e5fe0c8', 'parent': '{00000000-0000-0000-0000-000000000000}'}"),
+                None,
+                None,
+            )
+            .unwrap();
+        let back: Record = from_pyobject(obj).unwrap();
+        assert_eq!(
+            back,
+            Record {
+                id: ID,
+                parent: Some(Uuid::nil())
+            }
+        );
+
+        let options = PyObjectSerializerOptions::new().json_compatible(true);
+        let obj = to_pyobject_with(py, &back, &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'id': '67e55044-10b1-426f-9247-bb680e5fe0c8', 'parent': '00000000-0000-0000-0000-000000000000'}"
+        );
+
+        let obj = py
+            .eval(c_str!("{'id': 'x', 'parent': None}"), None, None)
+            .unwrap();
+        assert!(from_pyobject::<Record, _>(obj).is_err());
+    });
+}
+
+#[test]
+fn deserialize_any_canonical_string() {
+    Python::with_gil(|py| {
+        let obj = py
+            .eval(
+                c_str!("__import__('uuid').UUID('67e55044-10b1-426f-9247-bb680e5fe0c8')"),
+                None,
+                None,
+            )
+            .unwrap();
+        // Without the attribute, `Uuid` parses the string
+        let id: Uuid = from_pyobject(obj.clone()).unwrap();
+        assert_eq!(id, ID);
+        let id: String = from_pyobject(obj).unwrap();
+        assert_eq!(id, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+    });
+}
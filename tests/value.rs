@@ -0,0 +1,106 @@
+use pyo3::{prelude::*, types::PyList};
+use serde::{
+    de::{value::UnitDeserializer, DeserializeSeed, Deserializer},
+    Deserialize,
+};
+use serde_pyobject::{
+    pylist,
+    value::{BoolDeserializer, IntDeserializer, StrDeserializer},
+};
+
+#[test]
+fn bool_deserializer() {
+    Python::with_gil(|py| {
+        let obj = true.into_pyobject(py).unwrap().to_owned();
+        assert!(bool::deserialize(BoolDeserializer::new(obj)).unwrap());
+
+        let obj = 1.into_pyobject(py).unwrap();
+        let err = bool::deserialize(BoolDeserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'int' object cannot be converted to 'PyBool'"
+        );
+    });
+}
+
+#[test]
+fn int_deserializer() {
+    Python::with_gil(|py| {
+        let obj = (-3).into_pyobject(py).unwrap();
+        assert_eq!(i64::deserialize(IntDeserializer::new(obj)).unwrap(), -3);
+
+        let obj = u128::MAX.into_pyobject(py).unwrap();
+        assert_eq!(
+            u128::deserialize(IntDeserializer::new(obj)).unwrap(),
+            u128::MAX
+        );
+
+        let obj = 300.into_pyobject(py).unwrap();
+        let err = u8::deserialize(IntDeserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid value: integer `300`, expected u8"
+        );
+
+        let obj = true.into_pyobject(py).unwrap().to_owned();
+        let err = i32::deserialize(IntDeserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RuntimeError: invalid type: boolean `true`, expected i32"
+        );
+
+        let obj = "1".into_pyobject(py).unwrap();
+        let err = i32::deserialize(IntDeserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'str' object cannot be interpreted as an integer"
+        );
+    });
+}
+
+#[test]
+fn str_deserializer() {
+    Python::with_gil(|py| {
+        let obj = "abc".into_pyobject(py).unwrap();
+        assert_eq!(
+            String::deserialize(StrDeserializer::new(obj)).unwrap(),
+            "abc"
+        );
+        let obj = "x".into_pyobject(py).unwrap();
+        assert_eq!(char::deserialize(StrDeserializer::new(obj)).unwrap(), 'x');
+
+        let obj = 1.into_pyobject(py).unwrap();
+        let err = String::deserialize(StrDeserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: 'int' object cannot be converted to 'PyString'"
+        );
+    });
+}
+
+/// Sums a Python list of integers by the seed itself, without `PyAnyDeserializer`
+struct SumSeed<'py>(Bound<'py, PyList>);
+
+impl<'de> DeserializeSeed<'de> for SumSeed<'_> {
+    type Value = i64;
+
+    fn deserialize<D: Deserializer<'de>>(self, _: D) -> Result<i64, D::Error> {
+        let mut sum = 0;
+        for item in self.0.iter() {
+            sum +=
+                i64::deserialize(IntDeserializer::new(item)).map_err(serde::de::Error::custom)?;
+        }
+        Ok(sum)
+    }
+}
+
+#[test]
+fn in_seed() {
+    Python::with_gil(|py| {
+        let list = pylist![py; 1, 2, 3].unwrap();
+        let sum = SumSeed(list)
+            .deserialize(UnitDeserializer::<serde::de::value::Error>::new())
+            .unwrap();
+        assert_eq!(sum, 6);
+    });
+}
@@ -0,0 +1,72 @@
+use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+use serde::Deserialize;
+use serde_pyobject::from_pyobject;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Node {
+    value: i32,
+    children: Vec<BTreeMap<String, i32>>,
+}
+
+#[test]
+fn weakref_referent() {
+    Python::with_gil(|py| {
+        let locals = PyDict::new(py);
+        py.run(
+            c_str!(
+                r#"
+import weakref
+
+class D(dict):
+    pass
+
+child = D(a=1)
+node = {"value": 1, "children": [weakref.ref(child), weakref.proxy(child)]}
+"#
+            ),
+            None,
+            Some(&locals),
+        )
+        .unwrap();
+        let node: Node = from_pyobject(locals.get_item("node").unwrap().unwrap()).unwrap();
+        let child = BTreeMap::from([("a".to_string(), 1)]);
+        assert_eq!(
+            node,
+            Node {
+                value: 1,
+                children: vec![child.clone(), child]
+            }
+        );
+    });
+}
+
+#[test]
+fn dead_weakref() {
+    Python::with_gil(|py| {
+        let globals = PyDict::new(py);
+        // Collect explicitly since the referent is not freed immediately on PyPy
+        py.run(
+            c_str!(
+                r#"
+import gc, weakref
+
+class D(dict):
+    pass
+
+obj = weakref.ref(D())
+gc.collect()
+"#
+            ),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        let obj = globals.get_item("obj").unwrap().unwrap();
+        let err = from_pyobject::<BTreeMap<String, i32>, _>(obj).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ReferenceError: weakly-referenced object no longer exists"
+        );
+    });
+}
@@ -0,0 +1,100 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_pyobject::{
+    from_pyobject, from_pyobject_with, to_pyobject_with, PyObjectDeserializerOptions,
+    PyObjectSerializerOptions, StructOutput,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct UserId(u64);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Role {
+    Admin,
+    Guest,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Status {
+    Status,
+    Other(u8),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: UserId,
+    roles: Vec<Role>,
+    #[serde(with = "serde_pyobject::set")]
+    tags: Vec<String>,
+}
+
+fn user() -> User {
+    User {
+        id: UserId(42),
+        roles: vec![Role::Admin, Role::Guest],
+        tags: vec!["a".to_string()],
+    }
+}
+
+#[test]
+fn round_trip() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().wrap_type_names(true);
+        let obj = to_pyobject_with(py, &user(), &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'User': {'id': {'UserId': 42}, 'roles': [{'Role': 'Admin'}, {'Role': 'Guest'}], 'tags': {'a'}}}"
+        );
+
+        let options = PyObjectDeserializerOptions::new().wrap_type_names(true);
+        let back: User = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, user());
+
+        // Unwrapped values are accepted as well
+        let obj = to_pyobject_with(py, &user(), &PyObjectSerializerOptions::new()).unwrap();
+        let back: User = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, user());
+    });
+}
+
+#[test]
+fn wrapped_struct_output() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new()
+            .wrap_type_names(true)
+            .struct_output(StructOutput::Tuple);
+        let obj = to_pyobject_with(py, &user(), &options).unwrap();
+        assert_eq!(
+            obj.repr().unwrap().to_string(),
+            "{'User': ({'UserId': 42}, [{'Role': 'Admin'}, {'Role': 'Guest'}], {'a'})}"
+        );
+    });
+}
+
+#[test]
+fn variant_named_as_enum() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().wrap_type_names(true);
+        let obj = to_pyobject_with(py, &Status::Status, &options).unwrap();
+        assert_eq!(obj.repr().unwrap().to_string(), "{'Status': 'Status'}");
+
+        // `{"Status": ...}` is read as the variant `Status` rather than a wrapped variant
+        let options = PyObjectDeserializerOptions::new().wrap_type_names(true);
+        let back: Status = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, Status::Status);
+        let obj =
+            to_pyobject_with(py, &Status::Other(1), &PyObjectSerializerOptions::new()).unwrap();
+        let back: Status = from_pyobject_with(obj, &options).unwrap();
+        assert_eq!(back, Status::Other(1));
+    });
+}
+
+#[test]
+fn disabled_by_default() {
+    Python::with_gil(|py| {
+        let options = PyObjectSerializerOptions::new().wrap_type_names(true);
+        let obj = to_pyobject_with(py, &UserId(1), &options).unwrap();
+        let err = from_pyobject::<UserId, _>(obj).unwrap_err();
+        assert!(err.to_string().contains("invalid type: map"), "{}", err);
+    });
+}